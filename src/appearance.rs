@@ -0,0 +1,170 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::{config::read_config, Vault};
+
+/// The vault's theme and CSS snippets, from `appearance.json` and the `themes` and `snippets`
+/// folders
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Appearance {
+    /// The active community theme, `None` for Obsidian's default theme
+    pub active_theme: Option<String>,
+    /// Installed themes, sorted by name
+    pub themes: Vec<Theme>,
+    /// Snippets in the `snippets` folder, sorted by name
+    pub snippets: Vec<Snippet>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    pub name: String,
+    /// The version from the theme's `manifest.json`, absent for legacy single-file themes
+    pub version: Option<String>,
+    /// The theme's folder, or its `.css` file for legacy themes
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    /// The file name without `.css`, as listed in `enabledCssSnippets`
+    pub name: String,
+    pub path: PathBuf,
+    pub enabled: bool,
+}
+
+#[derive(Deserialize)]
+struct ThemeManifest {
+    #[serde(default)]
+    version: Option<String>,
+}
+
+impl Appearance {
+    pub fn enabled_snippets(&self) -> impl Iterator<Item = &Snippet> {
+        self.snippets.iter().filter(|snippet| snippet.enabled)
+    }
+}
+
+impl Vault {
+    pub fn appearance(&self) -> crate::Result<Appearance> {
+        let config_dir = self.config_dir();
+        let config = self.config()?.appearance;
+
+        let mut themes = Vec::new();
+        for path in read_dir(&config_dir.join("themes"))? {
+            let theme = if path.is_dir() {
+                let manifest: Option<ThemeManifest> = read_config(&path, "manifest.json")?;
+                Theme {
+                    name: file_name(&path),
+                    version: manifest.and_then(|manifest| manifest.version),
+                    path,
+                }
+            } else if path.extension().is_some_and(|ext| ext == "css") {
+                Theme {
+                    name: file_stem(&path),
+                    version: None,
+                    path,
+                }
+            } else {
+                continue;
+            };
+            themes.push(theme);
+        }
+        themes.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut snippets: Vec<Snippet> = read_dir(&config_dir.join("snippets"))?
+            .into_iter()
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "css"))
+            .map(|path| {
+                let name = file_stem(&path);
+                Snippet {
+                    enabled: config.enabled_css_snippets.contains(&name),
+                    name,
+                    path,
+                }
+            })
+            .collect();
+        snippets.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(Appearance {
+            active_theme: Some(config.css_theme).filter(|theme| !theme.is_empty()),
+            themes,
+            snippets,
+        })
+    }
+}
+
+/// The entries of a folder, or none if it doesn't exist
+fn read_dir(dir: &Path) -> crate::Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    entries
+        .map(|entry| Ok(entry?.path()))
+        .collect::<crate::Result<_>>()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CONFIG_DIR;
+
+    #[test]
+    fn appearance_combines_config_and_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join(CONFIG_DIR);
+        fs::create_dir_all(config.join("themes/Minimal")).unwrap();
+        fs::create_dir_all(config.join("snippets")).unwrap();
+        fs::write(
+            config.join("themes/Minimal/manifest.json"),
+            r#"{"name": "Minimal", "version": "7.7.7"}"#,
+        )
+        .unwrap();
+        fs::write(config.join("themes/Old.css"), "body {}").unwrap();
+        fs::write(config.join("snippets/wide.css"), "").unwrap();
+        fs::write(config.join("snippets/fonts.css"), "").unwrap();
+        fs::write(config.join("snippets/notes.txt"), "").unwrap();
+        fs::write(
+            config.join("appearance.json"),
+            r#"{"cssTheme": "Minimal", "enabledCssSnippets": ["wide", "gone"]}"#,
+        )
+        .unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let appearance = vault.appearance().unwrap();
+        assert_eq!(appearance.active_theme.as_deref(), Some("Minimal"));
+        let themes: Vec<(&str, Option<&str>)> = appearance
+            .themes
+            .iter()
+            .map(|t| (t.name.as_str(), t.version.as_deref()))
+            .collect();
+        assert_eq!(themes, vec![("Minimal", Some("7.7.7")), ("Old", None)]);
+
+        let snippets: Vec<(&str, bool)> = appearance
+            .snippets
+            .iter()
+            .map(|s| (s.name.as_str(), s.enabled))
+            .collect();
+        assert_eq!(snippets, vec![("fonts", false), ("wide", true)]);
+        assert_eq!(appearance.enabled_snippets().count(), 1);
+    }
+}
@@ -0,0 +1,296 @@
+use std::ops::Range;
+
+use pulldown_cmark::{CodeBlockKind, Event, LinkType, Options, Parser, Tag};
+
+use crate::ObsidianNote;
+
+/// A node in a note body's markdown tree. Spans are byte ranges into the parsed text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub kind: NodeKind,
+    pub span: Range<usize>,
+    pub children: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeKind {
+    Paragraph,
+    Heading {
+        level: u8,
+    },
+    BlockQuote,
+    /// A fenced or indented code block, whose text is in its children
+    CodeBlock {
+        /// The fence's info string, `None` for indented blocks
+        info: Option<String>,
+    },
+    HtmlBlock,
+    List {
+        /// The first number of an ordered list, `None` for bullet lists
+        start: Option<u64>,
+    },
+    ListItem {
+        /// Whether a task item is checked, `None` for plain items
+        checked: Option<bool>,
+    },
+    Table,
+    TableHead,
+    TableRow,
+    TableCell,
+    FootnoteDefinition {
+        label: String,
+    },
+    Emphasis,
+    Strong,
+    Strikethrough,
+    Link {
+        destination: String,
+        title: String,
+        /// `[[target]]` rather than `[text](destination)`
+        wikilink: bool,
+    },
+    /// An image or, for wikilinks, any `![[embed]]`
+    Image {
+        destination: String,
+        title: String,
+        wikilink: bool,
+    },
+    Text(String),
+    Code(String),
+    InlineMath(String),
+    DisplayMath(String),
+    Html(String),
+    FootnoteReference(String),
+    SoftBreak,
+    HardBreak,
+    Rule,
+}
+
+impl Node {
+    /// This node and everything under it, depth first
+    pub fn descendants(&self) -> Vec<&Node> {
+        let mut nodes = vec![self];
+        for child in &self.children {
+            nodes.extend(child.descendants());
+        }
+        nodes
+    }
+
+    /// The concatenated text and inline code under this node
+    pub fn text(&self) -> String {
+        self.descendants()
+            .into_iter()
+            .filter_map(|node| match &node.kind {
+                NodeKind::Text(text) | NodeKind::Code(text) => Some(text.as_str()),
+                NodeKind::SoftBreak | NodeKind::HardBreak => Some(" "),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl ObsidianNote {
+    pub fn ast(&self) -> Vec<Node> {
+        parse_ast(&self.file_body)
+    }
+}
+
+/// The markdown extensions Obsidian supports
+pub(crate) fn options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_MATH
+        | Options::ENABLE_WIKILINKS
+}
+
+pub fn parse_ast(text: &str) -> Vec<Node> {
+    let mut roots = Vec::new();
+    let mut open: Vec<Node> = Vec::new();
+
+    for (event, span) in Parser::new_ext(text, options()).into_offset_iter() {
+        let kind = match event {
+            Event::Start(tag) => {
+                open.push(Node {
+                    kind: container_kind(tag),
+                    span,
+                    children: Vec::new(),
+                });
+                continue;
+            }
+            Event::End(_) => {
+                if let Some(node) = open.pop() {
+                    match open.last_mut() {
+                        Some(parent) => parent.children.push(node),
+                        None => roots.push(node),
+                    }
+                }
+                continue;
+            }
+            Event::TaskListMarker(checked) => {
+                if let Some(Node {
+                    kind: NodeKind::ListItem { checked: item },
+                    ..
+                }) = open.last_mut()
+                {
+                    *item = Some(checked);
+                }
+                continue;
+            }
+            Event::Text(text) => NodeKind::Text(text.into_string()),
+            Event::Code(code) => NodeKind::Code(code.into_string()),
+            Event::InlineMath(math) => NodeKind::InlineMath(math.into_string()),
+            Event::DisplayMath(math) => NodeKind::DisplayMath(math.into_string()),
+            Event::Html(html) | Event::InlineHtml(html) => NodeKind::Html(html.into_string()),
+            Event::FootnoteReference(label) => NodeKind::FootnoteReference(label.into_string()),
+            Event::SoftBreak => NodeKind::SoftBreak,
+            Event::HardBreak => NodeKind::HardBreak,
+            Event::Rule => NodeKind::Rule,
+        };
+
+        let node = Node {
+            kind,
+            span,
+            children: Vec::new(),
+        };
+        match open.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => roots.push(node),
+        }
+    }
+
+    roots
+}
+
+fn container_kind(tag: Tag) -> NodeKind {
+    match tag {
+        Tag::Paragraph => NodeKind::Paragraph,
+        Tag::Heading { level, .. } => NodeKind::Heading { level: level as u8 },
+        Tag::BlockQuote(_) => NodeKind::BlockQuote,
+        Tag::CodeBlock(CodeBlockKind::Fenced(info)) => NodeKind::CodeBlock {
+            info: Some(info.into_string()),
+        },
+        Tag::CodeBlock(CodeBlockKind::Indented) => NodeKind::CodeBlock { info: None },
+        Tag::HtmlBlock => NodeKind::HtmlBlock,
+        Tag::List(start) => NodeKind::List { start },
+        Tag::Item => NodeKind::ListItem { checked: None },
+        Tag::FootnoteDefinition(label) => NodeKind::FootnoteDefinition {
+            label: label.into_string(),
+        },
+        Tag::Table(_) => NodeKind::Table,
+        Tag::TableHead => NodeKind::TableHead,
+        Tag::TableRow => NodeKind::TableRow,
+        Tag::TableCell => NodeKind::TableCell,
+        Tag::Emphasis => NodeKind::Emphasis,
+        Tag::Strong => NodeKind::Strong,
+        Tag::Strikethrough => NodeKind::Strikethrough,
+        Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            ..
+        } => NodeKind::Link {
+            destination: dest_url.into_string(),
+            title: title.into_string(),
+            wikilink: matches!(link_type, LinkType::WikiLink { .. }),
+        },
+        Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            ..
+        } => NodeKind::Image {
+            destination: dest_url.into_string(),
+            title: title.into_string(),
+            wikilink: matches!(link_type, LinkType::WikiLink { .. }),
+        },
+        // Only emitted by extensions that aren't enabled
+        Tag::DefinitionList
+        | Tag::DefinitionListTitle
+        | Tag::DefinitionListDefinition
+        | Tag::Superscript
+        | Tag::Subscript
+        | Tag::MetadataBlock(_) => NodeKind::Paragraph,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn parse_ast_builds_block_tree_with_spans() {
+        let text = indoc! {r"
+            # Title
+
+            Some *text* here.
+
+            - [x] done
+            - plain
+        "};
+        let ast = parse_ast(text);
+
+        assert_eq!(ast.len(), 3);
+        assert_eq!(ast[0].kind, NodeKind::Heading { level: 1 });
+        assert_eq!(&text[ast[0].span.clone()], "# Title\n");
+        assert_eq!(ast[1].text(), "Some text here.");
+
+        let items = &ast[2].children;
+        assert_eq!(
+            items[0].kind,
+            NodeKind::ListItem {
+                checked: Some(true)
+            }
+        );
+        assert_eq!(items[1].kind, NodeKind::ListItem { checked: None });
+        assert_eq!(items[0].text(), "done");
+    }
+
+    #[test]
+    fn parse_ast_reads_wikilinks_and_embeds() {
+        let text = "See [[Other note|alias]] and ![[image.png]]";
+        let nodes = parse_ast(text);
+        let kinds: Vec<_> = nodes[0]
+            .descendants()
+            .into_iter()
+            .filter(|n| matches!(n.kind, NodeKind::Link { .. } | NodeKind::Image { .. }))
+            .collect();
+
+        assert!(matches!(
+            &kinds[0].kind,
+            NodeKind::Link { destination, wikilink: true, .. } if destination == "Other note"
+        ));
+        assert_eq!(kinds[0].text(), "alias");
+        assert_eq!(&text[kinds[1].span.clone()], "![[image.png]]");
+        assert!(matches!(
+            &kinds[1].kind,
+            NodeKind::Image { wikilink: true, .. }
+        ));
+    }
+
+    #[test]
+    fn parse_ast_reads_code_blocks_and_tables() {
+        let text = indoc! {r"
+            ```rust
+            fn main() {}
+            ```
+
+            | a | b |
+            |---|---|
+            | 1 | 2 |
+        "};
+        let ast = parse_ast(text);
+
+        assert_eq!(
+            ast[0].kind,
+            NodeKind::CodeBlock {
+                info: Some("rust".to_string())
+            }
+        );
+        assert_eq!(ast[0].text(), "fn main() {}\n");
+        assert_eq!(ast[1].kind, NodeKind::Table);
+        assert_eq!(ast[1].children[1].kind, NodeKind::TableRow);
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+};
+
+use tokio::fs;
+
+use crate::{
+    vault::{app_json_path, is_note},
+    writer::temp_path,
+    Error, ObsidianNote, ReadOptions, Vault,
+};
+
+impl ObsidianNote {
+    pub async fn read_from_path_async(file_path: &Path) -> crate::Result<Self> {
+        Self::read_from_path_with_async(file_path, ReadOptions::default()).await
+    }
+
+    pub async fn read_from_path_with_async(
+        file_path: &Path,
+        options: ReadOptions,
+    ) -> crate::Result<Self> {
+        let file_contents = options.decode(file_path, fs::read(file_path).await?)?;
+        Self::parse(file_path, file_contents)
+    }
+
+    /// Like [`ObsidianNote::write_to_path`], through a temporary file
+    pub async fn write_to_path_async(&self, file_path: &Path) -> crate::Result<()> {
+        let temp = temp_path(file_path);
+        if let Err(err) = fs::write(&temp, self.to_string()).await {
+            let _ = fs::remove_file(&temp).await;
+            return Err(err.into());
+        }
+        fs::rename(temp, file_path).await?;
+        Ok(())
+    }
+}
+
+/// Notes read one at a time on the async runtime, in the same order as [`Vault::notes`]
+#[derive(Debug)]
+pub struct AsyncNotes {
+    paths: VecDeque<PathBuf>,
+    options: ReadOptions,
+}
+
+impl AsyncNotes {
+    pub async fn next(&mut self) -> Option<crate::Result<ObsidianNote>> {
+        loop {
+            let path = self.paths.pop_front()?;
+            let note = ObsidianNote::read_from_path_with_async(&path, self.options).await;
+            if self.options.keeps(&note) {
+                return Some(note);
+            }
+        }
+    }
+}
+
+impl Vault {
+    pub async fn open_async(path: impl AsRef<Path>) -> crate::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let is_dir = fs::metadata(&path)
+            .await
+            .is_ok_and(|metadata| metadata.is_dir());
+        if !is_dir {
+            return Err(Error::NotADirectory(path));
+        }
+
+        let app_json = fs::read_to_string(app_json_path(&path)).await.ok();
+        Ok(Self::from_parts(path, app_json.as_deref()))
+    }
+
+    /// Every file in the vault, skipping hidden folders such as `.obsidian` and excluded files
+    pub async fn files_async(&self) -> crate::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        let mut dirs = vec![self.path.clone()];
+
+        while let Some(dir) = dirs.pop() {
+            let mut entries = fs::read_dir(&dir).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let file_type = entry.file_type().await?;
+                if file_type.is_dir() {
+                    if !entry.file_name().to_string_lossy().starts_with('.') {
+                        dirs.push(entry.path());
+                    }
+                } else if file_type.is_file() {
+                    let path = entry.path();
+                    if !self.excluded.excludes(self.relative_path(&path)) {
+                        files.push(path);
+                    }
+                }
+            }
+        }
+
+        // Matches the depth-first, name-sorted order of `Vault::files`
+        files.sort_by(|a, b| a.components().cmp(b.components()));
+        Ok(files)
+    }
+
+    pub async fn note_paths_async(&self) -> crate::Result<Vec<PathBuf>> {
+        let mut files = self.files_async().await?;
+        files.retain(|path| is_note(path));
+        Ok(files)
+    }
+
+    pub async fn notes_async(&self) -> crate::Result<AsyncNotes> {
+        Ok(AsyncNotes {
+            paths: self.note_paths_async().await?.into(),
+            options: self.read_options,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, contents) in [
+            ("b.md", "---\nstatus: done\n---\nB"),
+            ("a/c.md", "C"),
+            ("a.md", "A"),
+            (".obsidian/hidden.md", ""),
+            ("image.png", ""),
+        ] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+        dir
+    }
+
+    #[tokio::test]
+    async fn notes_async_matches_sync_order() {
+        let dir = vault_dir();
+        let vault = Vault::open_async(dir.path()).await.unwrap();
+
+        let mut notes = vault.notes_async().await.unwrap();
+        let mut paths = Vec::new();
+        while let Some(note) = notes.next().await {
+            paths.push(note.unwrap().file_path);
+        }
+
+        let sync_paths: Vec<_> = vault.note_paths().map(Result::unwrap).collect();
+        assert_eq!(paths, sync_paths);
+        assert_eq!(paths.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn open_async_rejects_missing_directory() {
+        assert!(Vault::open_async("/definitely/not/a/vault").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn read_and_write_async_round_trip() {
+        let dir = vault_dir();
+        let path = dir.path().join("b.md");
+
+        let note = ObsidianNote::read_from_path_async(&path).await.unwrap();
+        assert_eq!(note.properties.as_ref().unwrap()["status"], "done");
+
+        let copy = dir.path().join("copy.md");
+        note.write_to_path_async(&copy).await.unwrap();
+        let written = ObsidianNote::read_from_path(&copy).unwrap();
+        assert_eq!(written.properties, note.properties);
+        assert_eq!(written.file_body, note.file_body);
+    }
+}
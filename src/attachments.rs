@@ -0,0 +1,165 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crate::{vault::is_note, Error, LinkResolver, Vault};
+
+impl Vault {
+    /// Every file in the vault that isn't a note, such as images, PDFs and canvases
+    pub fn attachments(&self) -> impl Iterator<Item = crate::Result<PathBuf>> {
+        self.files()
+            .filter(|path| path.as_ref().map_or(true, |path| !is_note(path)))
+    }
+
+    /// Attachments that no note embeds or links to, by wikilink or markdown link
+    pub fn unused_attachments(&self) -> crate::Result<Vec<PathBuf>> {
+        let used: HashSet<PathBuf> = self
+            .resolved_links()?
+            .into_values()
+            .flat_map(|targets| targets.into_keys())
+            .collect();
+
+        let mut unused = Vec::new();
+        for path in self.attachments() {
+            let path = path?;
+            if !used.contains(self.relative_path(&path)) {
+                unused.push(path);
+            }
+        }
+        Ok(unused)
+    }
+
+    /// Moves an attachment, rewriting every embed and link that pointed at it. Returns the paths
+    /// of the notes whose links were updated.
+    pub fn move_attachment(
+        &self,
+        old: impl AsRef<Path>,
+        new: impl AsRef<Path>,
+    ) -> crate::Result<Vec<PathBuf>> {
+        let old = self.path.join(old);
+        if !old.is_file() || is_note(&old) {
+            return Err(Error::AttachmentNotFound(old));
+        }
+        self.rename_file(old, self.path.join(new))
+    }
+
+    /// Copies `file` into the folder the vault's attachment setting picks for `note`, numbering
+    /// the name as in `image 1.png` if it's taken. Returns the new attachment's path and the
+    /// embed to put in the note, in the vault's link style.
+    pub fn add_attachment(
+        &self,
+        note: impl AsRef<Path>,
+        file: impl AsRef<Path>,
+    ) -> crate::Result<(PathBuf, String)> {
+        let file = file.as_ref();
+        let note = self.path.join(note);
+        let folder = self
+            .config()?
+            .app
+            .attachment_folder(self.relative_path(&note));
+
+        let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+        let extension = file.extension().map(|ext| ext.to_string_lossy());
+        let path = (0..)
+            .map(|n| {
+                let name = match n {
+                    0 => stem.to_string(),
+                    n => format!("{stem} {n}"),
+                };
+                let name = match &extension {
+                    Some(extension) => format!("{name}.{extension}"),
+                    None => name,
+                };
+                self.path.join(&folder).join(name)
+            })
+            .find(|path| !path.exists())
+            .ok_or_else(|| Error::AlreadyExists(self.path.join(&folder)))?;
+
+        let files = self.files().collect::<crate::Result<Vec<_>>>()?;
+        let resolver = LinkResolver::new(&self.path, files);
+        let embed = self
+            .link_style()?
+            .link(self, &resolver, &path, &note, "", true);
+
+        self.writer.copy(file, &path)?;
+        Ok((path, embed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::vault_with_files;
+    use std::fs;
+
+    #[test]
+    fn attachments_find_unused_and_move_with_links() {
+        let (dir, vault) = vault_with_files(&[
+            (
+                "note.md",
+                "![[photo.png]] ![[photo.png|200]] ![chart](assets/chart%201.svg)",
+            ),
+            ("photo.png", ""),
+            ("assets/chart 1.svg", ""),
+            ("assets/unused.pdf", ""),
+        ]);
+
+        assert_eq!(vault.attachments().count(), 3);
+        assert_eq!(
+            vault.unused_attachments().unwrap(),
+            vec![dir.path().join("assets/unused.pdf")]
+        );
+
+        let updated = vault
+            .move_attachment("photo.png", "assets/holiday.png")
+            .unwrap();
+        vault
+            .move_attachment("assets/chart 1.svg", "images/chart.svg")
+            .unwrap();
+        assert_eq!(updated, vec![dir.path().join("note.md")]);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("note.md")).unwrap(),
+            "![[holiday.png]] ![[holiday.png|200]] ![chart](images/chart.svg)"
+        );
+        assert!(matches!(
+            vault.move_attachment("note.md", "other.md"),
+            Err(Error::AttachmentNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn add_attachment_uses_configured_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".obsidian")).unwrap();
+        fs::create_dir_all(dir.path().join("Other")).unwrap();
+        fs::write(
+            dir.path().join(".obsidian/app.json"),
+            r#"{"attachmentFolderPath": "./assets"}"#,
+        )
+        .unwrap();
+        fs::write(dir.path().join("Other/diagram.png"), "").unwrap();
+        fs::write(source.path().join("photo.png"), "png").unwrap();
+        fs::write(source.path().join("diagram.png"), "").unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let (path, embed) = vault
+            .add_attachment("Projects/plan.md", source.path().join("photo.png"))
+            .unwrap();
+        assert_eq!(path, dir.path().join("Projects/assets/photo.png"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "png");
+        assert_eq!(embed, "![[photo.png]]");
+
+        let (path, embed) = vault
+            .add_attachment("Projects/plan.md", source.path().join("photo.png"))
+            .unwrap();
+        assert_eq!(path, dir.path().join("Projects/assets/photo 1.png"));
+        assert_eq!(embed, "![[photo 1.png]]");
+
+        let (_, embed) = vault
+            .add_attachment("Projects/plan.md", source.path().join("diagram.png"))
+            .unwrap();
+        assert_eq!(embed, "![[Projects/assets/diagram.png]]");
+    }
+}
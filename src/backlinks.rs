@@ -0,0 +1,149 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{links::scan_links, LinkResolver, ObsidianNote, Vault, WikiLink};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backlink {
+    pub source: PathBuf,
+    /// The referencing link or embed, whose span locates it in the source note's body. Markdown
+    /// links appear as the equivalent wikilink.
+    pub link: WikiLink,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BacklinkIndex {
+    backlinks: HashMap<PathBuf, Vec<Backlink>>,
+}
+
+impl BacklinkIndex {
+    /// Builds the index using only `notes` to resolve links
+    pub fn from_notes(notes: &[ObsidianNote]) -> Self {
+        Self::new(notes, &LinkResolver::from_notes(notes))
+    }
+
+    pub fn new(notes: &[ObsidianNote], resolver: &LinkResolver) -> Self {
+        let mut backlinks: HashMap<PathBuf, Vec<Backlink>> = HashMap::new();
+
+        for note in notes {
+            for (_, link) in scan_links(&note.file_body) {
+                let Some(target) = resolver.resolve_link(&link, &note.file_path) else {
+                    continue;
+                };
+                if target == note.file_path {
+                    continue;
+                }
+
+                backlinks
+                    .entry(target.to_path_buf())
+                    .or_default()
+                    .push(Backlink {
+                        source: note.file_path.clone(),
+                        link,
+                    });
+            }
+        }
+
+        Self { backlinks }
+    }
+
+    pub fn backlinks(&self, note: &Path) -> &[Backlink] {
+        self.backlinks.get(note).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&PathBuf, &Vec<Backlink>)> {
+        self.backlinks.iter()
+    }
+}
+
+impl Vault {
+    pub fn backlinks(&self) -> crate::Result<BacklinkIndex> {
+        let notes = self.notes().collect::<crate::Result<Vec<_>>>()?;
+        let resolver = LinkResolver::from_vault_notes(self, &notes)?;
+        Ok(BacklinkIndex::new(&notes, &resolver))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(path: &str, body: &str) -> ObsidianNote {
+        ObsidianNote::parse(&PathBuf::from(path), body.to_string()).unwrap()
+    }
+
+    #[test]
+    fn from_notes_collects_inbound_links() {
+        let notes = vec![
+            note("vault/a.md", "Links to [[b]] and ![[b#Section]]"),
+            note("vault/b.md", "Links to [[c]]"),
+            note("vault/folder/c.md", "Links back to [[a|A]]"),
+        ];
+        let index = BacklinkIndex::from_notes(&notes);
+
+        let to_b = index.backlinks(Path::new("vault/b.md"));
+        assert_eq!(to_b.len(), 2);
+        assert_eq!(to_b[0].source, PathBuf::from("vault/a.md"));
+        assert_eq!(to_b[0].link.span, 9..14);
+        assert_eq!(to_b[1].link.heading.as_deref(), Some("Section"));
+
+        let to_c = index.backlinks(Path::new("vault/folder/c.md"));
+        assert_eq!(to_c[0].source, PathBuf::from("vault/b.md"));
+    }
+
+    #[test]
+    fn from_notes_skips_self_and_unresolved_links() {
+        let notes = vec![note("a.md", "[[a]] [[#Heading]] [[missing]]")];
+        let index = BacklinkIndex::from_notes(&notes);
+
+        assert!(index.backlinks(Path::new("a.md")).is_empty());
+        assert_eq!(index.iter().count(), 0);
+    }
+
+    #[test]
+    fn vault_backlinks_resolves_attachments() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "![[image.png]] [[b]]").unwrap();
+        std::fs::write(dir.path().join("b.md"), "").unwrap();
+        std::fs::write(dir.path().join("image.png"), "").unwrap();
+        let index = Vault::open(dir.path()).unwrap().backlinks().unwrap();
+
+        assert_eq!(index.backlinks(&dir.path().join("image.png")).len(), 1);
+        assert_eq!(
+            index.backlinks(&dir.path().join("b.md"))[0].source,
+            dir.path().join("a.md")
+        );
+    }
+
+    #[test]
+    fn from_notes_matches_folder_paths() {
+        let notes = vec![
+            note("one/note.md", ""),
+            note("two/note.md", ""),
+            note("source.md", "[[two/note]]"),
+        ];
+        let index = BacklinkIndex::from_notes(&notes);
+
+        assert!(index.backlinks(Path::new("one/note.md")).is_empty());
+        assert_eq!(index.backlinks(Path::new("two/note.md")).len(), 1);
+    }
+
+    #[test]
+    fn from_notes_collects_markdown_links() {
+        let notes = vec![
+            note(
+                "a.md",
+                "[the plan](My%20Plan.md#Goals) [site](https://example.com)",
+            ),
+            note("My Plan.md", ""),
+        ];
+        let index = BacklinkIndex::from_notes(&notes);
+
+        let to_plan = index.backlinks(Path::new("My Plan.md"));
+        assert_eq!(to_plan.len(), 1);
+        assert_eq!(to_plan[0].link.heading.as_deref(), Some("Goals"));
+        assert_eq!(to_plan[0].link.span, 0..30);
+    }
+}
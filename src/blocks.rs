@@ -0,0 +1,227 @@
+use std::{
+    collections::HashMap,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    code::{fenced_ranges, in_ranges},
+    LinkResolver, ObsidianNote, Vault, WikiLink,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub id: String,
+    /// The block's text without its `^id` marker
+    pub text: String,
+    /// Byte range of the block, including its marker
+    pub span: Range<usize>,
+}
+
+/// A block a link such as `[[Note^abc123]]` points to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockRef {
+    /// The note the block is in
+    pub note: PathBuf,
+    pub block: Block,
+}
+
+impl ObsidianNote {
+    pub fn blocks(&self) -> HashMap<String, Block> {
+        parse_blocks(&self.file_body)
+            .into_iter()
+            .map(|block| (block.id.clone(), block))
+            .collect()
+    }
+
+    pub fn block(&self, id: &str) -> Option<Block> {
+        parse_blocks(&self.file_body)
+            .into_iter()
+            .find(|block| block.id == id)
+    }
+}
+
+impl Vault {
+    /// The block a link from `source` references, reading the note it resolves to. `None` when
+    /// the link has no block ID or either the note or the block doesn't exist.
+    pub fn resolve_block(
+        &self,
+        resolver: &LinkResolver,
+        link: &WikiLink,
+        source: &Path,
+    ) -> crate::Result<Option<BlockRef>> {
+        let Some(id) = &link.block else {
+            return Ok(None);
+        };
+        let Some(path) = resolver.resolve_link(link, source) else {
+            return Ok(None);
+        };
+
+        let note = ObsidianNote::read_from_path_with(path, self.read_options)?;
+        Ok(note.block(id).map(|block| BlockRef {
+            note: note.file_path,
+            block,
+        }))
+    }
+}
+
+pub fn parse_blocks(text: &str) -> Vec<Block> {
+    let code = fenced_ranges(text);
+    let mut blocks = Vec::new();
+    // Start of the paragraph the current line belongs to
+    let mut paragraph_start: Option<usize> = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let content = line.trim_end();
+
+        if content.is_empty() || in_ranges(&code, start) {
+            paragraph_start = None;
+            continue;
+        }
+        let block_start = if is_list_item(content) {
+            start
+        } else {
+            *paragraph_start.get_or_insert(start)
+        };
+
+        let Some((id, marker_start)) = block_id(content) else {
+            continue;
+        };
+        let end = start + content.len();
+
+        // A marker on its own line identifies the paragraph, list or table above it
+        let text_range = if marker_start == 0 {
+            paragraph_start.unwrap_or(start)..start
+        } else {
+            block_start..start + marker_start
+        };
+
+        blocks.push(Block {
+            id: id.to_string(),
+            span: text_range.start..end,
+            text: text[text_range].trim_end().to_string(),
+        });
+        paragraph_start = None;
+    }
+
+    blocks
+}
+
+/// The id of a trailing `^id` marker and the offset the marker starts at
+fn block_id(line: &str) -> Option<(&str, usize)> {
+    let caret = line.rfind('^')?;
+    let id = &line[caret + 1..];
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+    if caret > 0 && !line[..caret].ends_with([' ', '\t']) {
+        return None;
+    }
+
+    let marker_start = line[..caret].trim_end().len();
+    Some((id, marker_start))
+}
+
+fn is_list_item(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("- ") || trimmed.starts_with("* ") || trimmed.starts_with("+ ") {
+        return true;
+    }
+
+    let digits = trimmed.len()
+        - trimmed
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .len();
+    digits > 0 && (trimmed[digits..].starts_with(". ") || trimmed[digits..].starts_with(") "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::path::PathBuf;
+
+    #[test]
+    fn parse_blocks_finds_trailing_ids() {
+        let text = indoc! {r"
+            First line
+            Some paragraph ^abc123
+
+            Unmarked paragraph
+        "};
+        let blocks = parse_blocks(text);
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].id, "abc123");
+        assert_eq!(blocks[0].text, "First line\nSome paragraph");
+        assert_eq!(
+            &text[blocks[0].span.clone()],
+            "First line\nSome paragraph ^abc123"
+        );
+    }
+
+    #[test]
+    fn parse_blocks_handles_list_items() {
+        let blocks = parse_blocks("- one\n- two ^item-2\n- three");
+        assert_eq!(blocks[0].text, "- two");
+    }
+
+    #[test]
+    fn parse_blocks_handles_standalone_markers() {
+        let blocks = parse_blocks(indoc! {r"
+            | a | b |
+            |---|---|
+            ^table
+        "});
+        assert_eq!(blocks[0].id, "table");
+        assert_eq!(blocks[0].text, "| a | b |\n|---|---|");
+    }
+
+    #[test]
+    fn parse_blocks_ignores_carets_inside_text() {
+        assert!(parse_blocks("2^10 is x^2 `^code`").is_empty());
+    }
+
+    #[test]
+    fn resolve_block_follows_links_into_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Note.md"), "Intro\n\n- Key point ^abc123\n").unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+        let resolver = vault.link_resolver().unwrap();
+        let source = dir.path().join("Index.md");
+        let links = crate::links::scan_wikilinks("[[Note^abc123]] [[Note^gone]] [[Note]]");
+
+        let found = vault
+            .resolve_block(&resolver, &links[0].1, &source)
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.note, dir.path().join("Note.md"));
+        assert_eq!(found.block.text, "- Key point");
+        assert_eq!(
+            vault
+                .resolve_block(&resolver, &links[1].1, &source)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            vault
+                .resolve_block(&resolver, &links[2].1, &source)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn blocks_maps_ids() {
+        let note =
+            ObsidianNote::parse(&PathBuf::from("a.md"), "Para ^one\n\nPara ^two".to_string())
+                .unwrap();
+        let blocks = note.blocks();
+
+        assert_eq!(blocks["one"].text, "Para");
+        assert_eq!(blocks["two"].text, "Para");
+    }
+}
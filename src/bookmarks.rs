@@ -0,0 +1,177 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::{config::read_config, Vault};
+
+/// `bookmarks.json`
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct Bookmarks {
+    #[serde(default)]
+    pub items: Vec<Bookmark>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Bookmark {
+    /// A custom title shown instead of the file name, query or URL
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Creation time in milliseconds since the epoch
+    #[serde(default)]
+    pub ctime: Option<i64>,
+    #[serde(flatten)]
+    pub kind: BookmarkKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BookmarkKind {
+    /// A note or attachment, or a heading or block within one when `subpath` is set
+    File {
+        /// Relative to the vault root
+        path: String,
+        /// `#Heading` or `#^block-id`
+        #[serde(default)]
+        subpath: Option<String>,
+    },
+    Folder {
+        path: String,
+    },
+    Search {
+        query: String,
+    },
+    Url {
+        url: String,
+    },
+    Group {
+        #[serde(default)]
+        items: Vec<Bookmark>,
+    },
+    /// Graph views and bookmark types added after this crate
+    #[serde(other)]
+    Other,
+}
+
+impl Bookmark {
+    /// The heading a file bookmark points to
+    pub fn heading(&self) -> Option<&str> {
+        match &self.kind {
+            BookmarkKind::File {
+                subpath: Some(subpath),
+                ..
+            } => subpath
+                .strip_prefix('#')
+                .filter(|heading| !heading.starts_with('^') && !heading.is_empty()),
+            _ => None,
+        }
+    }
+}
+
+impl Bookmarks {
+    /// Every bookmark, with groups followed by their contents
+    pub fn all(&self) -> Vec<&Bookmark> {
+        fn walk<'a>(items: &'a [Bookmark], all: &mut Vec<&'a Bookmark>) {
+            for item in items {
+                all.push(item);
+                if let BookmarkKind::Group { items } = &item.kind {
+                    walk(items, all);
+                }
+            }
+        }
+
+        let mut all = Vec::new();
+        walk(&self.items, &mut all);
+        all
+    }
+
+    /// The vault-relative paths of bookmarked files, including those in groups and those
+    /// bookmarked by heading or block
+    pub fn file_paths(&self) -> Vec<&str> {
+        self.all()
+            .into_iter()
+            .filter_map(|bookmark| match &bookmark.kind {
+                BookmarkKind::File { path, .. } => Some(path.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Search queries, including those in groups
+    pub fn searches(&self) -> Vec<&str> {
+        self.all()
+            .into_iter()
+            .filter_map(|bookmark| match &bookmark.kind {
+                BookmarkKind::Search { query } => Some(query.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Vault {
+    /// The vault's bookmarks, empty if it has none
+    pub fn bookmarks(&self) -> crate::Result<Bookmarks> {
+        Ok(read_config(&self.config_dir(), "bookmarks.json")?.unwrap_or_default())
+    }
+
+    /// Bookmarked files that exist in the vault, once each in bookmark order
+    pub fn bookmarked_files(&self) -> crate::Result<Vec<PathBuf>> {
+        let mut files: Vec<PathBuf> = Vec::new();
+        for path in self.bookmarks()?.file_paths() {
+            let path = self.path.join(path);
+            if path.is_file() && !files.contains(&path) {
+                files.push(path);
+            }
+        }
+        Ok(files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CONFIG_DIR;
+    use std::fs;
+
+    #[test]
+    fn bookmarks_reads_typed_items() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(CONFIG_DIR)).unwrap();
+        fs::create_dir_all(dir.path().join("Projects")).unwrap();
+        fs::write(dir.path().join("Projects/Launch.md"), "# Plan").unwrap();
+        fs::write(dir.path().join("Inbox.md"), "").unwrap();
+        fs::write(
+            dir.path().join(CONFIG_DIR).join("bookmarks.json"),
+            r##"{"items": [
+                {"type": "file", "ctime": 1700000000000, "path": "Inbox.md"},
+                {"type": "group", "ctime": 1700000000001, "title": "Work", "items": [
+                    {"type": "file", "path": "Projects/Launch.md", "subpath": "#Plan", "title": "Launch plan"},
+                    {"type": "search", "query": "tag:#urgent"},
+                    {"type": "file", "path": "Deleted.md"}
+                ]},
+                {"type": "url", "url": "https://obsidian.md"},
+                {"type": "graph", "options": {}}
+            ]}"##,
+        )
+        .unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let bookmarks = vault.bookmarks().unwrap();
+        assert_eq!(bookmarks.items.len(), 4);
+        assert_eq!(bookmarks.items[0].ctime, Some(1_700_000_000_000));
+        assert_eq!(bookmarks.items[1].title.as_deref(), Some("Work"));
+        assert_eq!(bookmarks.items[3].kind, BookmarkKind::Other);
+
+        let launch = bookmarks.all()[2];
+        assert_eq!(launch.heading(), Some("Plan"));
+        assert_eq!(bookmarks.searches(), vec!["tag:#urgent"]);
+
+        assert_eq!(
+            vault.bookmarked_files().unwrap(),
+            vec![
+                dir.path().join("Inbox.md"),
+                dir.path().join("Projects/Launch.md")
+            ]
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+
+use crate::{
+    links::{parse_markdown_links, scan_wikilinks},
+    LinkResolver, ObsidianNote, Vault,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenLink {
+    pub source: PathBuf,
+    /// 1-based line number within the source file
+    pub line: usize,
+    /// The link as written, e.g. `[[Missing#Heading|alias]]`
+    pub raw: String,
+    pub target: String,
+}
+
+impl Vault {
+    /// Every link or embed whose target doesn't resolve to a note or attachment
+    pub fn broken_links(&self) -> crate::Result<Vec<BrokenLink>> {
+        let notes = self.notes().collect::<crate::Result<Vec<_>>>()?;
+        let resolver = LinkResolver::from_vault_notes(self, &notes)?;
+        Ok(find_broken_links(&notes, &resolver))
+    }
+}
+
+pub fn find_broken_links(notes: &[ObsidianNote], resolver: &LinkResolver) -> Vec<BrokenLink> {
+    let mut broken = Vec::new();
+
+    for note in notes {
+        let wikilinks = scan_wikilinks(&note.file_body)
+            .into_iter()
+            .filter(|(_, link)| !link.target.is_empty())
+            .filter(|(_, link)| resolver.resolve_link(link, &note.file_path).is_none())
+            .map(|(_, link)| (link.span, link.target));
+        let markdown_links = parse_markdown_links(&note.file_body)
+            .into_iter()
+            .filter_map(|link| Some((link.path()?, link.span)))
+            .filter(|(path, _)| resolver.resolve(path, &note.file_path).is_none())
+            .map(|(path, span)| (span, path));
+
+        let mut note_broken: Vec<BrokenLink> = wikilinks
+            .chain(markdown_links)
+            .map(|(span, target)| BrokenLink {
+                source: note.file_path.clone(),
+                line: note.body_line(span.start),
+                raw: note.file_body[span].to_string(),
+                target,
+            })
+            .collect();
+        note_broken.sort_by_key(|b| b.line);
+        broken.extend(note_broken);
+    }
+
+    broken
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::path::Path;
+
+    #[test]
+    fn find_broken_links_reports_unresolved_targets() {
+        let notes = vec![
+            ObsidianNote::parse(
+                Path::new("source.md"),
+                indoc! {r"
+                    ---
+                    title: Source
+                    ---
+                    [[Exists]] [[Missing|Alias]]
+                    ![[missing.png]] [[#Same note]]
+                    [md](Nowhere.md) [web](https://example.com)
+                "}
+                .to_string(),
+            )
+            .unwrap(),
+            ObsidianNote::parse(Path::new("Exists.md"), String::new()).unwrap(),
+        ];
+        let resolver = LinkResolver::from_notes(&notes);
+        let broken = find_broken_links(&notes, &resolver);
+
+        let summary: Vec<(usize, &str, &str)> = broken
+            .iter()
+            .map(|b| (b.line, b.raw.as_str(), b.target.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (4, "[[Missing|Alias]]", "Missing"),
+                (5, "![[missing.png]]", "missing.png"),
+                (6, "[md](Nowhere.md)", "Nowhere.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn broken_links_checks_attachments_in_vault() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "![[image.png]] ![[gone.pdf]]").unwrap();
+        std::fs::write(dir.path().join("image.png"), "").unwrap();
+
+        let broken = Vault::open(dir.path()).unwrap().broken_links().unwrap();
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].target, "gone.pdf");
+        assert_eq!(broken[0].source, dir.path().join("a.md"));
+    }
+}
@@ -0,0 +1,162 @@
+use std::path::PathBuf;
+
+use crate::{FileChange, ObsidianNote, Properties, Query, Vault};
+
+/// A change to a note's properties, for [`Vault::patch_properties`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyPatch {
+    /// Sets a property, adding it if it's missing
+    Set(String, Properties),
+    /// Adds a property to notes that don't have it yet
+    SetDefault(String, Properties),
+    Rename {
+        from: String,
+        to: String,
+    },
+    Remove(String),
+}
+
+impl ObsidianNote {
+    /// Applies a patch, returning whether it changed anything
+    pub fn apply_patch(&mut self, patch: &PropertyPatch) -> crate::Result<bool> {
+        let current = |key: &str| self.properties.as_ref().and_then(|p| p.get(key));
+        match patch {
+            PropertyPatch::Set(key, value) => {
+                if current(key) == Some(value) {
+                    return Ok(false);
+                }
+                self.set_property(key, value.clone())?;
+                Ok(true)
+            }
+            PropertyPatch::SetDefault(key, value) => {
+                if current(key).is_some() {
+                    return Ok(false);
+                }
+                self.set_property(key, value.clone())?;
+                Ok(true)
+            }
+            PropertyPatch::Rename { from, to } => Ok(from != to && self.rename_property(from, to)?),
+            PropertyPatch::Remove(key) => Ok(self.remove_property(key).is_some()),
+        }
+    }
+}
+
+impl Vault {
+    /// Runs `update` on every note matching `query` and writes back the notes it changed,
+    /// returning their paths
+    ///
+    /// Nothing is written unless every note is read and updated successfully.
+    pub fn update_properties(
+        &self,
+        query: &Query,
+        mut update: impl FnMut(&mut ObsidianNote) -> crate::Result<()>,
+    ) -> crate::Result<Vec<PathBuf>> {
+        let mut writes = Vec::new();
+        for note in self.notes() {
+            let mut note = note?;
+            if !query.matches(&note) {
+                continue;
+            }
+
+            let before = note.to_string();
+            update(&mut note).map_err(|err| err.in_file(&note.file_path))?;
+            let after = note.to_string();
+            if after != before {
+                writes.push(FileChange::Write {
+                    path: note.file_path,
+                    contents: after,
+                });
+            }
+        }
+
+        let paths = writes
+            .iter()
+            .map(|change| change.path().to_path_buf())
+            .collect();
+        self.writer.apply(writes)?;
+        Ok(paths)
+    }
+
+    /// Applies `patches` in order to every note matching `query`, like
+    /// [`Vault::update_properties`]
+    pub fn patch_properties(
+        &self,
+        query: &Query,
+        patches: &[PropertyPatch],
+    ) -> crate::Result<Vec<PathBuf>> {
+        self.update_properties(query, |note| {
+            for patch in patches {
+                note.apply_patch(patch)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::vault_with_files;
+    use indoc::indoc;
+    use std::fs;
+
+    #[test]
+    fn patch_properties_edits_matching_notes() {
+        let (dir, vault) = vault_with_files(&[
+            ("a.md", "---\nstatus: active\nowner: me\n---\nA\n"),
+            ("b.md", "---\nstatus: done\n---\nB"),
+        ]);
+
+        let changed = vault
+            .patch_properties(
+                &Query::new().eq("status", "active"),
+                &[
+                    PropertyPatch::Rename {
+                        from: "owner".to_string(),
+                        to: "assignee".to_string(),
+                    },
+                    PropertyPatch::SetDefault("priority".to_string(), 1.into()),
+                    PropertyPatch::Set("status".to_string(), "archived".into()),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(changed, vec![dir.path().join("a.md")]);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("a.md")).unwrap(),
+            indoc! {"
+                ---
+                status: archived
+                assignee: me
+                priority: 1
+                ---
+                A
+            "}
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("b.md")).unwrap(),
+            "---\nstatus: done\n---\nB"
+        );
+    }
+
+    #[test]
+    fn update_properties_writes_nothing_on_error() {
+        let (dir, vault) = vault_with_files(&[
+            ("a.md", "---\ncount: 1\n---\n"),
+            ("b.md", "---\ncount: 2\nnext: 3\n---\n"),
+        ]);
+
+        let result = vault.update_properties(&Query::new(), |note| {
+            note.update_property("count", |v| Properties::from(v.as_i64().unwrap() + 1))?;
+            note.rename_property("count", "next")?;
+            Ok(())
+        });
+
+        assert!(result.is_err());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("a.md")).unwrap(),
+            "---\ncount: 1\n---\n"
+        );
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+}
@@ -0,0 +1,329 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::{ObsidianNote, Properties, Vault};
+
+/// Metadata parsed from a note, as stored in the cache
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteMetadata {
+    pub path: PathBuf,
+    /// Modification time in nanoseconds since the Unix epoch
+    pub modified: u64,
+    /// FNV-1a hash of the file contents
+    pub hash: u64,
+    pub properties: Option<Properties>,
+    /// Wikilink and embed targets
+    pub links: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+/// A vault whose note metadata was loaded through a SQLite cache
+#[derive(Debug)]
+pub struct CachedVault {
+    pub vault: Vault,
+    notes: BTreeMap<PathBuf, NoteMetadata>,
+    /// Number of notes that had to be re-parsed when opening
+    pub reparsed: usize,
+}
+
+impl CachedVault {
+    pub fn metadata(&self, path: &Path) -> Option<&NoteMetadata> {
+        self.notes.get(path)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &NoteMetadata> {
+        self.notes.values()
+    }
+}
+
+impl NoteMetadata {
+    fn from_note(note: &ObsidianNote, modified: u64, hash: u64) -> Self {
+        Self {
+            path: note.file_path.clone(),
+            modified,
+            hash,
+            properties: note.properties.clone(),
+            links: note
+                .links()
+                .into_iter()
+                .chain(note.embeds().into_iter().map(|embed| embed.link().clone()))
+                .map(|link| link.target)
+                .collect(),
+            tags: note.tags().into_iter().map(|tag| tag.name).collect(),
+        }
+    }
+}
+
+impl Vault {
+    /// Opens a vault, reading note metadata from the SQLite database at `cache_path` and only
+    /// re-parsing notes whose modification time and contents changed since they were cached
+    pub fn open_cached(
+        path: impl AsRef<Path>,
+        cache_path: impl AsRef<Path>,
+    ) -> crate::Result<CachedVault> {
+        Vault::open(path)?.cached(cache_path)
+    }
+
+    /// Like [`Vault::open_cached`], for a vault already opened with its options
+    pub fn cached(self, cache_path: impl AsRef<Path>) -> crate::Result<CachedVault> {
+        let vault = self;
+        let mut connection = Connection::open(cache_path)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS notes (
+                path TEXT PRIMARY KEY,
+                modified INTEGER NOT NULL,
+                hash INTEGER NOT NULL,
+                properties TEXT,
+                links TEXT NOT NULL,
+                tags TEXT NOT NULL
+            )",
+        )?;
+
+        let transaction = connection.transaction()?;
+        let mut notes = BTreeMap::new();
+        let mut reparsed = 0;
+
+        for path in vault.note_paths() {
+            let path = path?;
+            let key = vault.relative_path(&path).to_string_lossy().into_owned();
+            let modified = modified(&path)?;
+            let cached = read_cached(&transaction, &key, &path)?;
+
+            let metadata = match cached {
+                Some(cached) if cached.modified == modified => cached,
+                cached => {
+                    let bytes = fs::read(&path)?;
+                    let hash = fnv1a(&bytes);
+                    let metadata = match cached {
+                        Some(cached) if cached.hash == hash => NoteMetadata { modified, ..cached },
+                        _ => {
+                            let options = vault.read_options;
+                            let note = options
+                                .decode(&path, bytes)
+                                .and_then(|contents| ObsidianNote::parse(&path, contents));
+                            if !options.keeps(&note) {
+                                continue;
+                            }
+                            reparsed += 1;
+                            NoteMetadata::from_note(&note?, modified, hash)
+                        }
+                    };
+                    write_cached(&transaction, &key, &metadata)?;
+                    metadata
+                }
+            };
+            notes.insert(path, metadata);
+        }
+
+        let cached_keys: Vec<String> = transaction
+            .prepare("SELECT path FROM notes")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        for key in cached_keys {
+            if !notes.contains_key(&vault.path.join(&key)) {
+                transaction.execute("DELETE FROM notes WHERE path = ?1", [&key])?;
+            }
+        }
+        transaction.commit()?;
+
+        Ok(CachedVault {
+            vault,
+            notes,
+            reparsed,
+        })
+    }
+}
+
+fn read_cached(
+    connection: &Connection,
+    key: &str,
+    path: &Path,
+) -> crate::Result<Option<NoteMetadata>> {
+    let row = connection
+        .query_row(
+            "SELECT modified, hash, properties, links, tags FROM notes WHERE path = ?1",
+            [key],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                ))
+            },
+        )
+        .optional()?;
+
+    let Some((modified, hash, properties, links, tags)) = row else {
+        return Ok(None);
+    };
+    Ok(Some(NoteMetadata {
+        path: path.to_path_buf(),
+        modified: modified as u64,
+        hash: hash as u64,
+        properties: properties
+            .map(|properties| serde_json::from_str(&properties))
+            .transpose()?,
+        links: serde_json::from_str(&links)?,
+        tags: serde_json::from_str(&tags)?,
+    }))
+}
+
+fn write_cached(connection: &Connection, key: &str, metadata: &NoteMetadata) -> crate::Result<()> {
+    connection.execute(
+        "INSERT OR REPLACE INTO notes (path, modified, hash, properties, links, tags)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            key,
+            metadata.modified as i64,
+            metadata.hash as i64,
+            metadata
+                .properties
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?,
+            serde_json::to_string(&metadata.links)?,
+            serde_json::to_string(&metadata.tags)?,
+        ],
+    )?;
+    Ok(())
+}
+
+fn modified(path: &Path) -> crate::Result<u64> {
+    Ok(fs::metadata(path)?
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64)
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReadOptions;
+    use std::time::{Duration, SystemTime};
+
+    fn set_modified(path: &Path, time: SystemTime) {
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
+    #[test]
+    fn open_cached_stores_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("cache.db");
+        let vault_dir = dir.path().join("vault");
+        fs::create_dir(&vault_dir).unwrap();
+        fs::write(
+            vault_dir.join("a.md"),
+            "---\nstatus: active\n---\nSee [[b]] and ![[c.png]] #tag",
+        )
+        .unwrap();
+
+        let cached = Vault::open_cached(&vault_dir, &cache).unwrap();
+        assert_eq!(cached.reparsed, 1);
+
+        let cached = Vault::open_cached(&vault_dir, &cache).unwrap();
+        assert_eq!(cached.reparsed, 0);
+        let metadata = cached.metadata(&vault_dir.join("a.md")).unwrap();
+        assert_eq!(metadata.properties.as_ref().unwrap()["status"], "active");
+        assert_eq!(metadata.links, vec!["b", "c.png"]);
+        assert_eq!(metadata.tags, vec!["tag"]);
+    }
+
+    #[test]
+    fn cached_applies_read_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("cache.db");
+        let vault_dir = dir.path().join("vault");
+        fs::create_dir(&vault_dir).unwrap();
+        fs::write(vault_dir.join("a.md"), "A").unwrap();
+        fs::write(vault_dir.join("b.md"), b"#b\xff").unwrap();
+        assert!(Vault::open_cached(&vault_dir, &cache).is_err());
+
+        let options = |lossy| ReadOptions {
+            lossy,
+            skip_invalid: true,
+        };
+        let vault = Vault::open(&vault_dir).unwrap();
+        let cached = vault
+            .clone()
+            .with_read_options(options(false))
+            .cached(&cache)
+            .unwrap();
+        assert_eq!(cached.iter().count(), 1);
+
+        let cached = vault
+            .with_read_options(options(true))
+            .cached(&cache)
+            .unwrap();
+        assert_eq!(cached.iter().count(), 2);
+        assert_eq!(
+            cached.metadata(&vault_dir.join("b.md")).unwrap().tags,
+            vec!["b\u{fffd}"]
+        );
+    }
+
+    #[test]
+    fn open_cached_reparses_changed_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("cache.db");
+        let vault_dir = dir.path().join("vault");
+        fs::create_dir(&vault_dir).unwrap();
+        let a = vault_dir.join("a.md");
+        let b = vault_dir.join("b.md");
+        fs::write(&a, "#before").unwrap();
+        fs::write(&b, "unchanged").unwrap();
+        Vault::open_cached(&vault_dir, &cache).unwrap();
+
+        fs::write(&a, "#after").unwrap();
+        set_modified(&a, UNIX_EPOCH + Duration::from_secs(1));
+        // Touched but identical contents only refresh the stored modification time
+        set_modified(&b, UNIX_EPOCH + Duration::from_secs(2));
+
+        let cached = Vault::open_cached(&vault_dir, &cache).unwrap();
+        assert_eq!(cached.reparsed, 1);
+        assert_eq!(cached.metadata(&a).unwrap().tags, vec!["after"]);
+        assert_eq!(
+            cached.metadata(&b).unwrap().modified,
+            Duration::from_secs(2).as_nanos() as u64
+        );
+    }
+
+    #[test]
+    fn open_cached_drops_deleted_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("cache.db");
+        let vault_dir = dir.path().join("vault");
+        fs::create_dir(&vault_dir).unwrap();
+        fs::write(vault_dir.join("a.md"), "").unwrap();
+        Vault::open_cached(&vault_dir, &cache).unwrap();
+
+        fs::remove_file(vault_dir.join("a.md")).unwrap();
+        let cached = Vault::open_cached(&vault_dir, &cache).unwrap();
+        assert_eq!(cached.iter().count(), 0);
+
+        let rows: i64 = Connection::open(&cache)
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 0);
+    }
+}
@@ -0,0 +1,152 @@
+use std::ops::Range;
+
+use crate::{
+    code::{fenced_ranges, in_ranges},
+    ObsidianNote,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Callout {
+    /// The lowercased callout type, e.g. `note` or `warning`
+    pub kind: String,
+    pub title: Option<String>,
+    pub fold: Option<Fold>,
+    /// The callout's content with one level of `>` removed, so nested callouts can be parsed again
+    pub body: String,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fold {
+    /// `+`, foldable and expanded by default
+    Expanded,
+    /// `-`, foldable and collapsed by default
+    Collapsed,
+}
+
+impl ObsidianNote {
+    pub fn callouts(&self) -> Vec<Callout> {
+        parse_callouts(&self.file_body)
+    }
+}
+
+pub fn parse_callouts(text: &str) -> Vec<Callout> {
+    let code = fenced_ranges(text);
+    let mut callouts: Vec<Callout> = Vec::new();
+    let mut current: Option<Callout> = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let content = line.trim_end_matches(['\n', '\r']);
+
+        let quoted = (!in_ranges(&code, start))
+            .then(|| strip_quote(content))
+            .flatten();
+
+        if let (Some(callout), Some(inner)) = (&mut current, quoted) {
+            if header(inner).is_none() {
+                if !callout.body.is_empty() || !inner.trim().is_empty() {
+                    callout.body.push_str(inner);
+                    callout.body.push('\n');
+                }
+                callout.span.end = start + content.len();
+                continue;
+            }
+        }
+        callouts.extend(current.take());
+
+        if let Some((kind, fold, title)) = quoted.and_then(header) {
+            current = Some(Callout {
+                kind,
+                title,
+                fold,
+                body: String::new(),
+                span: start..start + content.len(),
+            });
+        }
+    }
+    callouts.extend(current);
+
+    for callout in &mut callouts {
+        callout.body = callout.body.trim_end().to_string();
+    }
+    callouts
+}
+
+/// The line with one level of blockquote removed, if it's quoted
+pub(crate) fn strip_quote(line: &str) -> Option<&str> {
+    let rest = line.trim_start().strip_prefix('>')?;
+    Some(rest.strip_prefix(' ').unwrap_or(rest))
+}
+
+fn header(line: &str) -> Option<(String, Option<Fold>, Option<String>)> {
+    let rest = line.trim_start().strip_prefix("[!")?;
+    let close = rest.find(']')?;
+    let kind = rest[..close].trim();
+    if kind.is_empty() {
+        return None;
+    }
+
+    let rest = &rest[close + 1..];
+    let (fold, rest) = match rest.chars().next() {
+        Some('+') => (Some(Fold::Expanded), &rest[1..]),
+        Some('-') => (Some(Fold::Collapsed), &rest[1..]),
+        _ => (None, rest),
+    };
+    let title = Some(rest.trim()).filter(|t| !t.is_empty());
+
+    Some((kind.to_lowercase(), fold, title.map(str::to_string)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn parse_callouts_reads_type_title_and_body() {
+        let text = indoc! {r"
+            Intro
+            > [!Warning] Be careful
+            > First line
+            >
+            > Second paragraph
+            After
+        "};
+        let callouts = parse_callouts(text);
+
+        assert_eq!(callouts.len(), 1);
+        assert_eq!(callouts[0].kind, "warning");
+        assert_eq!(callouts[0].title.as_deref(), Some("Be careful"));
+        assert_eq!(callouts[0].fold, None);
+        assert_eq!(callouts[0].body, "First line\n\nSecond paragraph");
+        assert!(text[callouts[0].span.clone()].ends_with("> Second paragraph"));
+    }
+
+    #[test]
+    fn parse_callouts_reads_fold_state() {
+        let callouts = parse_callouts("> [!faq]- Collapsed\n> Hidden\n\n> [!tip]+\n> Shown");
+
+        assert_eq!(callouts[0].fold, Some(Fold::Collapsed));
+        assert_eq!(callouts[1].fold, Some(Fold::Expanded));
+        assert_eq!(callouts[1].title, None);
+        assert_eq!(callouts[1].body, "Shown");
+    }
+
+    #[test]
+    fn parse_callouts_keeps_nested_callouts_in_body() {
+        let callouts = parse_callouts("> [!note] Outer\n> > [!info] Inner\n> > Nested body");
+
+        assert_eq!(callouts.len(), 1);
+        let nested = parse_callouts(&callouts[0].body);
+        assert_eq!(nested[0].kind, "info");
+        assert_eq!(nested[0].body, "Nested body");
+    }
+
+    #[test]
+    fn parse_callouts_ignores_plain_blockquotes() {
+        assert!(parse_callouts("> Just a quote\n> [not a callout]").is_empty());
+    }
+}
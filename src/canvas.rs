@@ -0,0 +1,197 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{links::parse_wikilinks, Vault, VaultWriter};
+
+/// A `.canvas` file, following the JSON Canvas format
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Canvas {
+    #[serde(default)]
+    pub nodes: Vec<CanvasNode>,
+    #[serde(default)]
+    pub edges: Vec<CanvasEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CanvasNode {
+    pub id: String,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(flatten)]
+    pub kind: CanvasNodeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum CanvasNodeKind {
+    Text {
+        text: String,
+    },
+    File {
+        file: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        subpath: Option<String>,
+    },
+    Link {
+        url: String,
+    },
+    Group {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        background: Option<String>,
+        #[serde(
+            default,
+            rename = "backgroundStyle",
+            skip_serializing_if = "Option::is_none"
+        )]
+        background_style: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasEdge {
+    pub id: String,
+    pub from_node: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_side: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_end: Option<String>,
+    pub to_node: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_side: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_end: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl Canvas {
+    pub fn read_from_path(file_path: &Path) -> crate::Result<Self> {
+        let contents = fs::read_to_string(file_path)?;
+        Self::parse(&contents)
+    }
+
+    pub fn parse(contents: &str) -> crate::Result<Self> {
+        if contents.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(contents)?)
+    }
+
+    pub fn write_to_path(&self, file_path: &Path) -> crate::Result<()> {
+        VaultWriter::default().write(file_path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn node(&self, id: &str) -> Option<&CanvasNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// Link targets referenced by the canvas: file nodes, plus wikilinks inside text nodes
+    pub fn linked_files(&self) -> Vec<String> {
+        self.nodes
+            .iter()
+            .flat_map(|node| match &node.kind {
+                CanvasNodeKind::File { file, .. } => vec![file.clone()],
+                CanvasNodeKind::Text { text } => parse_wikilinks(text)
+                    .into_iter()
+                    .map(|link| link.target)
+                    .filter(|target| !target.is_empty())
+                    .collect(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+}
+
+impl Vault {
+    pub fn canvas_paths(&self) -> impl Iterator<Item = crate::Result<PathBuf>> {
+        self.files().filter(|path| {
+            path.as_ref().map_or(true, |path| {
+                path.extension().is_some_and(|ext| ext == "canvas")
+            })
+        })
+    }
+
+    pub fn canvases(&self) -> impl Iterator<Item = crate::Result<(PathBuf, Canvas)>> {
+        self.canvas_paths().map(|path| {
+            let path = path?;
+            let canvas = Canvas::read_from_path(&path)?;
+            Ok((path, canvas))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    const CANVAS: &str = indoc! {r##"
+        {
+          "nodes": [
+            {"id": "1", "type": "text", "text": "See [[Note]]", "x": 0, "y": 0, "width": 250, "height": 60},
+            {"id": "2", "type": "file", "file": "folder/Other.md", "subpath": "#Heading", "x": 300, "y": 0, "width": 400, "height": 400, "color": "1"},
+            {"id": "3", "type": "link", "url": "https://obsidian.md", "x": 0, "y": 500, "width": 400, "height": 300},
+            {"id": "4", "type": "group", "label": "Group", "backgroundStyle": "cover", "x": -50, "y": -50, "width": 900, "height": 900}
+          ],
+          "edges": [
+            {"id": "e1", "fromNode": "1", "fromSide": "right", "toNode": "2", "toEnd": "arrow", "label": "refers to"}
+          ]
+        }
+    "##};
+
+    #[test]
+    fn parse_reads_typed_nodes_and_edges() {
+        let canvas = Canvas::parse(CANVAS).unwrap();
+
+        assert_eq!(canvas.nodes.len(), 4);
+        assert_eq!(
+            canvas.node("2").unwrap().kind,
+            CanvasNodeKind::File {
+                file: "folder/Other.md".to_string(),
+                subpath: Some("#Heading".to_string()),
+            }
+        );
+        assert!(matches!(
+            &canvas.node("4").unwrap().kind,
+            CanvasNodeKind::Group { background_style: Some(style), .. } if style == "cover"
+        ));
+        assert_eq!(canvas.edges[0].from_node, "1");
+        assert_eq!(canvas.edges[0].to_end.as_deref(), Some("arrow"));
+    }
+
+    #[test]
+    fn linked_files_includes_text_wikilinks() {
+        let canvas = Canvas::parse(CANVAS).unwrap();
+        assert_eq!(canvas.linked_files(), vec!["Note", "folder/Other.md"]);
+    }
+
+    #[test]
+    fn parse_handles_empty_canvas() {
+        assert_eq!(Canvas::parse("").unwrap(), Canvas::default());
+        assert_eq!(Canvas::parse("{}").unwrap(), Canvas::default());
+    }
+
+    #[test]
+    fn write_to_path_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("board.canvas"), CANVAS).unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let (path, canvas) = vault.canvases().next().unwrap().unwrap();
+        canvas.write_to_path(&path).unwrap();
+        assert_eq!(Canvas::read_from_path(&path).unwrap(), canvas);
+    }
+}
@@ -0,0 +1,204 @@
+use std::ops::Range;
+
+use crate::ObsidianNote;
+
+/// Byte ranges of fenced code blocks and inline code spans, in order
+pub(crate) fn code_ranges(text: &str) -> Vec<Range<usize>> {
+    let fenced = fenced_ranges(text);
+    let mut ranges = Vec::new();
+    let mut cursor = 0;
+
+    for block in fenced {
+        ranges.extend(inline_ranges(text, cursor..block.start));
+        cursor = block.end;
+        ranges.push(block);
+    }
+    ranges.extend(inline_ranges(text, cursor..text.len()));
+
+    ranges
+}
+
+pub(crate) fn in_ranges(ranges: &[Range<usize>], position: usize) -> bool {
+    ranges.iter().any(|range| range.contains(&position))
+}
+
+/// A line opening or closing a fence: the fence character, its length and the info string
+pub(crate) fn fence(line: &str) -> Option<(char, usize, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+
+    let trimmed = &line[indent..];
+    let fence_char = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let fence_len = trimmed.len() - trimmed.trim_start_matches(fence_char).len();
+    if fence_len < 3 {
+        return None;
+    }
+
+    let info = trimmed[fence_len..].trim();
+    if fence_char == '`' && info.contains('`') {
+        return None;
+    }
+    Some((fence_char, fence_len, info))
+}
+
+pub(crate) fn fenced_ranges(text: &str) -> Vec<Range<usize>> {
+    parse_code_blocks(text)
+        .into_iter()
+        .map(|block| block.span)
+        .collect()
+}
+
+/// A fenced code block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    /// The first word of the info string, e.g. `rust` for ` ```rust title="main.rs" `
+    pub language: Option<String>,
+    /// Everything after the opening fence
+    pub info: String,
+    /// The lines between the fences
+    pub contents: String,
+    /// Byte range from the opening fence to the end of the closing one, or of the text if the
+    /// block is never closed
+    pub span: Range<usize>,
+}
+
+impl CodeBlock {
+    fn new(text: &str, span: Range<usize>, info: &str, contents: Range<usize>) -> Self {
+        Self {
+            language: info.split_whitespace().next().map(str::to_string),
+            info: info.to_string(),
+            contents: text[contents].to_string(),
+            span,
+        }
+    }
+}
+
+impl ObsidianNote {
+    pub fn code_blocks(&self) -> Vec<CodeBlock> {
+        parse_code_blocks(&self.file_body)
+    }
+}
+
+pub fn parse_code_blocks(text: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut open: Option<(usize, usize, char, usize, &str)> = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let line_end = offset + line.len();
+        let content = line.trim_end_matches(['\n', '\r']);
+
+        match (open, fence(content)) {
+            (None, Some((fence_char, fence_len, info))) => {
+                open = Some((offset, line_end, fence_char, fence_len, info))
+            }
+            (Some((start, contents_start, fence_char, fence_len, info)), Some((c, len, "")))
+                if c == fence_char && len >= fence_len =>
+            {
+                blocks.push(CodeBlock::new(
+                    text,
+                    start..line_end,
+                    info,
+                    contents_start..offset,
+                ));
+                open = None;
+            }
+            _ => {}
+        }
+        offset = line_end;
+    }
+
+    // An unclosed fence runs to the end of the document
+    if let Some((start, contents_start, _, _, info)) = open {
+        blocks.push(CodeBlock::new(
+            text,
+            start..text.len(),
+            info,
+            contents_start..text.len(),
+        ));
+    }
+
+    blocks
+}
+
+fn inline_ranges(text: &str, within: Range<usize>) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let segment = &text[within.clone()];
+    let mut cursor = 0;
+
+    while let Some(offset) = segment[cursor..].find('`') {
+        let start = cursor + offset;
+        let run = backtick_run(&segment[start..]);
+        let search_from = start + run;
+
+        let closing = segment[search_from..]
+            .match_indices('`')
+            .map(|(i, _)| search_from + i)
+            .find(|&i| !segment[..i].ends_with('`') && backtick_run(&segment[i..]) == run);
+
+        match closing {
+            Some(close) => {
+                ranges.push(within.start + start..within.start + close + run);
+                cursor = close + run;
+            }
+            None => cursor = search_from,
+        }
+    }
+
+    ranges
+}
+
+fn backtick_run(text: &str) -> usize {
+    text.len() - text.trim_start_matches('`').len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn code_ranges_finds_fenced_blocks() {
+        let text = indoc! {r"
+            Before
+            ```rust
+            let a = 1;
+            ```
+            After
+        "};
+        let ranges = code_ranges(text);
+
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(&text[ranges[0].clone()], "```rust\nlet a = 1;\n```\n");
+    }
+
+    #[test]
+    fn parse_code_blocks_reads_language_and_contents() {
+        let text = "Text\n```rust title=\"main.rs\"\nfn main() {}\n```\n~~~\nplain\n";
+        let blocks = parse_code_blocks(text);
+
+        assert_eq!(blocks[0].language.as_deref(), Some("rust"));
+        assert_eq!(blocks[0].info, "rust title=\"main.rs\"");
+        assert_eq!(blocks[0].contents, "fn main() {}\n");
+        assert_eq!(blocks[0].span, 5..46);
+        assert_eq!(blocks[1].language, None);
+        assert_eq!(blocks[1].contents, "plain\n");
+    }
+
+    #[test]
+    fn code_ranges_finds_inline_spans() {
+        let text = "Use `foo` or ``a ` b`` here";
+        let ranges = code_ranges(text);
+
+        assert_eq!(&text[ranges[0].clone()], "`foo`");
+        assert_eq!(&text[ranges[1].clone()], "``a ` b``");
+    }
+
+    #[test]
+    fn code_ranges_runs_unclosed_fence_to_end() {
+        let text = "Text\n~~~\nunclosed";
+        assert_eq!(code_ranges(text), vec![5..text.len()]);
+    }
+}
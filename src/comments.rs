@@ -0,0 +1,149 @@
+use std::ops::Range;
+
+use crate::{
+    code::{code_ranges, in_ranges},
+    ObsidianNote, TextEdit,
+};
+
+/// A `%%` comment, which Obsidian hides in reading view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    /// The text between the `%%`s, trimmed
+    pub text: String,
+    /// Whether the comment spans more than one line
+    pub is_block: bool,
+    /// Byte range of the comment including both `%%`s
+    pub span: Range<usize>,
+}
+
+impl ObsidianNote {
+    pub fn comments(&self) -> Vec<Comment> {
+        parse_comments(&self.file_body)
+    }
+
+    /// Removes every comment from the body, along with the lines comments took up on their own
+    pub fn strip_comments(&mut self) -> crate::Result<()> {
+        let edits: Vec<TextEdit> = removal_ranges(&self.file_body)
+            .into_iter()
+            .map(|range| TextEdit::new(range, ""))
+            .collect();
+        if edits.is_empty() {
+            return Ok(());
+        }
+        self.edit_body(&edits)
+    }
+}
+
+/// Finds `%%` comments outside code. An unclosed `%%` comments out the rest of the text, as in
+/// Obsidian.
+pub fn parse_comments(text: &str) -> Vec<Comment> {
+    let code = code_ranges(text);
+    let mut comments = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(offset) = text[cursor..].find("%%") {
+        let start = cursor + offset;
+        if in_ranges(&code, start) {
+            cursor = start + 2;
+            continue;
+        }
+
+        let inner_start = start + 2;
+        let inner_end = text[inner_start..]
+            .find("%%")
+            .map_or(text.len(), |len| inner_start + len);
+        let end = (inner_end + 2).min(text.len());
+        let inner = &text[inner_start..inner_end];
+        comments.push(Comment {
+            text: inner.trim().to_string(),
+            is_block: inner.contains('\n'),
+            span: start..end,
+        });
+        cursor = end;
+    }
+
+    comments
+}
+
+/// `text` without its comments, for exports that mustn't show them
+pub fn strip_comments(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for range in removal_ranges(text) {
+        stripped.push_str(&text[cursor..range.start]);
+        cursor = range.end;
+    }
+    stripped.push_str(&text[cursor..]);
+    stripped
+}
+
+/// Comment spans, widened to whole lines when a comment is alone on its lines
+fn removal_ranges(text: &str) -> Vec<Range<usize>> {
+    parse_comments(text)
+        .into_iter()
+        .map(|comment| {
+            let line_start = text[..comment.span.start].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = text[comment.span.end..]
+                .find('\n')
+                .map_or(text.len(), |i| comment.span.end + i + 1);
+            let alone = text[line_start..comment.span.start].trim().is_empty()
+                && text[comment.span.end..line_end].trim().is_empty();
+            if alone {
+                line_start..line_end
+            } else {
+                comment.span
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::path::Path;
+
+    #[test]
+    fn parse_comments_finds_inline_and_block_comments() {
+        let text = indoc! {r"
+            Visible %%hidden%% text `%%code%%`
+            %%
+            Block
+            comment
+            %%
+            Last %% unclosed
+        "};
+
+        let comments = parse_comments(text);
+        let summary: Vec<(&str, bool)> = comments
+            .iter()
+            .map(|c| (c.text.as_str(), c.is_block))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("hidden", false),
+                ("Block\ncomment", true),
+                ("unclosed", true)
+            ]
+        );
+        assert_eq!(&text[comments[0].span.clone()], "%%hidden%%");
+        assert_eq!(comments[2].span.end, text.len());
+    }
+
+    #[test]
+    fn strip_comments_drops_comment_only_lines() {
+        let mut note = ObsidianNote::parse(
+            Path::new("a.md"),
+            "---\ntitle: A\n---\nKeep %%secret%% this\n%% toc %%\n- item\n%%\nnotes\n%%\nEnd\n"
+                .to_string(),
+        )
+        .unwrap();
+
+        note.strip_comments().unwrap();
+        assert_eq!(
+            note.to_string(),
+            "---\ntitle: A\n---\nKeep  this\n- item\nEnd\n"
+        );
+    }
+}
@@ -0,0 +1,353 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{Error, LinkStyle, LinkSyntax, Vault};
+
+pub const CONFIG_DIR: &str = ".obsidian";
+
+/// The typed contents of a vault's `.obsidian` folder. Missing files fall back to Obsidian's
+/// defaults.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct VaultConfig {
+    pub app: AppConfig,
+    pub appearance: AppearanceConfig,
+    pub core_plugins: CorePlugins,
+    pub community_plugins: Vec<String>,
+    pub hotkeys: Hotkeys,
+}
+
+/// `app.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppConfig {
+    pub attachment_folder_path: String,
+    pub new_link_format: NewLinkFormat,
+    pub use_markdown_links: bool,
+    pub new_file_location: NewFileLocation,
+    pub new_file_folder_path: String,
+    pub always_update_links: bool,
+    pub user_ignore_filters: Vec<String>,
+    /// Settings without a typed field
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            attachment_folder_path: "/".to_string(),
+            new_link_format: NewLinkFormat::default(),
+            use_markdown_links: false,
+            new_file_location: NewFileLocation::default(),
+            new_file_folder_path: String::new(),
+            always_update_links: false,
+            user_ignore_filters: Vec::new(),
+            other: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NewLinkFormat {
+    #[default]
+    Shortest,
+    Relative,
+    Absolute,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NewFileLocation {
+    #[default]
+    Root,
+    Current,
+    Folder,
+}
+
+/// Where Obsidian puts newly added attachments, decoded from `attachmentFolderPath`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttachmentLocation {
+    VaultRoot,
+    /// Next to the note the attachment is added to
+    SameFolder,
+    /// A subfolder under the note's folder, from `./name`
+    Subfolder(String),
+    Folder(String),
+}
+
+impl AppConfig {
+    pub fn attachment_location(&self) -> AttachmentLocation {
+        let path = self.attachment_folder_path.trim();
+        match path {
+            "" | "/" => AttachmentLocation::VaultRoot,
+            "." | "./" => AttachmentLocation::SameFolder,
+            _ => match path.strip_prefix("./") {
+                Some(subfolder) => {
+                    AttachmentLocation::Subfolder(subfolder.trim_end_matches('/').to_string())
+                }
+                None => AttachmentLocation::Folder(path.trim_matches('/').to_string()),
+            },
+        }
+    }
+
+    pub fn link_style(&self) -> LinkStyle {
+        let syntax = if self.use_markdown_links {
+            LinkSyntax::Markdown
+        } else {
+            LinkSyntax::Wikilink
+        };
+        LinkStyle {
+            syntax,
+            format: self.new_link_format,
+        }
+    }
+
+    /// The folder, relative to the vault root, that attachments added to `note` go in. `note` is
+    /// relative to the vault root too.
+    pub fn attachment_folder(&self, note: &Path) -> PathBuf {
+        let note_folder = note.parent().unwrap_or(Path::new(""));
+        match self.attachment_location() {
+            AttachmentLocation::VaultRoot => PathBuf::new(),
+            AttachmentLocation::SameFolder => note_folder.to_path_buf(),
+            AttachmentLocation::Subfolder(name) => note_folder.join(name),
+            AttachmentLocation::Folder(folder) => PathBuf::from(folder),
+        }
+    }
+}
+
+/// `appearance.json`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppearanceConfig {
+    /// The active community theme, empty for the default theme
+    pub css_theme: String,
+    /// `obsidian` (dark), `moonstone` (light) or `system`
+    pub theme: Option<String>,
+    pub base_font_size: Option<f64>,
+    pub accent_color: Option<String>,
+    pub enabled_css_snippets: Vec<String>,
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+/// `core-plugins.json`, which is a map of plugin ID to enabled state in current versions and
+/// a list of enabled plugin IDs in older ones
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "CorePluginsFormat")]
+pub struct CorePlugins(pub BTreeMap<String, bool>);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CorePluginsFormat {
+    Map(BTreeMap<String, bool>),
+    List(Vec<String>),
+}
+
+impl From<CorePluginsFormat> for CorePlugins {
+    fn from(format: CorePluginsFormat) -> Self {
+        match format {
+            CorePluginsFormat::Map(map) => Self(map),
+            CorePluginsFormat::List(ids) => Self(ids.into_iter().map(|id| (id, true)).collect()),
+        }
+    }
+}
+
+impl CorePlugins {
+    pub fn is_enabled(&self, id: &str) -> bool {
+        self.0.get(id).copied().unwrap_or(false)
+    }
+}
+
+/// `hotkeys.json`: the hotkeys the user changed from Obsidian's defaults, by command ID such as
+/// `editor:toggle-bold`. An empty list means the command's default hotkey was removed.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hotkeys(pub BTreeMap<String, Vec<Hotkey>>);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hotkey {
+    #[serde(default)]
+    pub modifiers: Vec<Modifier>,
+    /// The key as Obsidian names it, e.g. `B`, `ArrowUp` or `F5`
+    pub key: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Modifier {
+    /// Cmd on macOS and Ctrl elsewhere
+    Mod,
+    Ctrl,
+    Meta,
+    Shift,
+    Alt,
+}
+
+impl fmt::Display for Hotkey {
+    /// Formats as `Mod+Shift+B`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for modifier in &self.modifiers {
+            write!(f, "{modifier:?}+")?;
+        }
+        f.write_str(&self.key)
+    }
+}
+
+impl Hotkeys {
+    /// The hotkeys bound to a command, or `None` if it keeps its default
+    pub fn get(&self, command: &str) -> Option<&[Hotkey]> {
+        self.0.get(command).map(Vec::as_slice)
+    }
+
+    /// The commands `hotkey` is bound to
+    pub fn commands_for(&self, hotkey: &Hotkey) -> Vec<&str> {
+        self.0
+            .iter()
+            .filter(|(_, hotkeys)| hotkeys.contains(hotkey))
+            .map(|(command, _)| command.as_str())
+            .collect()
+    }
+}
+
+impl VaultConfig {
+    pub fn read_from_dir(config_dir: &Path) -> crate::Result<Self> {
+        Ok(Self {
+            app: read_config(config_dir, "app.json")?.unwrap_or_default(),
+            appearance: read_config(config_dir, "appearance.json")?.unwrap_or_default(),
+            core_plugins: read_config(config_dir, "core-plugins.json")?.unwrap_or_default(),
+            community_plugins: read_config(config_dir, "community-plugins.json")?
+                .unwrap_or_default(),
+            hotkeys: read_config(config_dir, "hotkeys.json")?.unwrap_or_default(),
+        })
+    }
+}
+
+impl Vault {
+    pub fn config_dir(&self) -> PathBuf {
+        self.path.join(CONFIG_DIR)
+    }
+
+    pub fn config(&self) -> crate::Result<VaultConfig> {
+        VaultConfig::read_from_dir(&self.config_dir())
+    }
+}
+
+/// Reads a JSON config file, returning `None` when it doesn't exist
+pub(crate) fn read_config<T: DeserializeOwned>(
+    config_dir: &Path,
+    name: &str,
+) -> crate::Result<Option<T>> {
+    let path = config_dir.join(name);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let config = serde_json::from_str(&contents).map_err(|err| Error::from(err).in_file(&path))?;
+    Ok(Some(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::vault_with_files;
+
+    #[test]
+    fn config_reads_app_json() {
+        let (_dir, vault) = vault_with_files(&[(
+            ".obsidian/app.json",
+            r#"{"attachmentFolderPath": "./attachments", "newLinkFormat": "relative", "useMarkdownLinks": true, "vimMode": true}"#,
+        )]);
+        let config = vault.config().unwrap();
+
+        assert_eq!(
+            config.app.attachment_location(),
+            AttachmentLocation::Subfolder("attachments".to_string())
+        );
+        assert_eq!(config.app.new_link_format, NewLinkFormat::Relative);
+        assert!(config.app.use_markdown_links);
+        assert_eq!(config.app.other["vimMode"], serde_json::json!(true));
+    }
+
+    #[test]
+    fn config_defaults_missing_files() {
+        let (_dir, vault) = vault_with_files(&[]);
+        let config = vault.config().unwrap();
+
+        assert_eq!(config, VaultConfig::default());
+        assert_eq!(
+            config.app.attachment_location(),
+            AttachmentLocation::VaultRoot
+        );
+    }
+
+    #[test]
+    fn config_reads_plugins_and_appearance() {
+        let (_dir, vault) = vault_with_files(&[
+            (
+                ".obsidian/core-plugins.json",
+                r#"{"daily-notes": true, "canvas": false}"#,
+            ),
+            (
+                ".obsidian/community-plugins.json",
+                r#"["dataview", "templater-obsidian"]"#,
+            ),
+            (
+                ".obsidian/appearance.json",
+                r#"{"cssTheme": "Minimal", "enabledCssSnippets": ["wide"]}"#,
+            ),
+        ]);
+        let config = vault.config().unwrap();
+
+        assert!(config.core_plugins.is_enabled("daily-notes"));
+        assert!(!config.core_plugins.is_enabled("canvas"));
+        assert_eq!(
+            config.community_plugins,
+            vec!["dataview", "templater-obsidian"]
+        );
+        assert_eq!(config.appearance.css_theme, "Minimal");
+        assert_eq!(config.appearance.enabled_css_snippets, vec!["wide"]);
+    }
+
+    #[test]
+    fn config_reads_hotkeys() {
+        let (_dir, vault) = vault_with_files(&[(
+            ".obsidian/hotkeys.json",
+            r#"{"editor:toggle-bold": [{"modifiers": ["Mod", "Shift"], "key": "B"}], "app:go-back": [], "editor:swap-line-up": [{"modifiers": ["Alt"], "key": "ArrowUp"}]}"#,
+        )]);
+        let hotkeys = vault.config().unwrap().hotkeys;
+
+        let bold = &hotkeys.get("editor:toggle-bold").unwrap()[0];
+        assert_eq!(bold.to_string(), "Mod+Shift+B");
+        assert_eq!(hotkeys.get("app:go-back"), Some(&[][..]));
+        assert_eq!(hotkeys.get("app:reload"), None);
+        assert_eq!(hotkeys.commands_for(bold), vec!["editor:toggle-bold"]);
+    }
+
+    #[test]
+    fn core_plugins_reads_legacy_list() {
+        let plugins: CorePlugins = serde_json::from_str(r#"["file-explorer"]"#).unwrap();
+        assert!(plugins.is_enabled("file-explorer"));
+    }
+
+    #[test]
+    fn attachment_location_decodes_paths() {
+        let app = |path: &str| AppConfig {
+            attachment_folder_path: path.to_string(),
+            ..AppConfig::default()
+        };
+        assert_eq!(
+            app("./").attachment_location(),
+            AttachmentLocation::SameFolder
+        );
+        assert_eq!(
+            app("Assets/Images").attachment_location(),
+            AttachmentLocation::Folder("Assets/Images".to_string())
+        );
+    }
+}
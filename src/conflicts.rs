@@ -0,0 +1,179 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::Vault;
+
+/// A duplicate a sync tool left behind when two devices changed the same file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncConflict {
+    pub conflict: PathBuf,
+    /// The file the conflict is a copy of, which may since have been deleted
+    pub original: PathBuf,
+}
+
+/// A line of a [`SyncConflict::diff`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// In both files
+    Same(String),
+    /// Only in the original
+    Removed(String),
+    /// Only in the conflicted copy
+    Added(String),
+}
+
+impl SyncConflict {
+    /// A line diff from the original to the conflicted copy. A missing original counts as
+    /// empty.
+    pub fn diff(&self) -> crate::Result<Vec<DiffLine>> {
+        let original = match fs::read_to_string(&self.original) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.into()),
+        };
+        let conflict = fs::read_to_string(&self.conflict)?;
+        Ok(diff_lines(&original, &conflict))
+    }
+}
+
+impl Vault {
+    /// Every file that's a sync conflict, paired with its original, sorted by path
+    pub fn sync_conflicts(&self) -> crate::Result<Vec<SyncConflict>> {
+        let mut conflicts = Vec::new();
+        for path in self.files() {
+            let path = path?;
+            if let Some(original) = conflict_original(&path) {
+                conflicts.push(SyncConflict {
+                    conflict: path,
+                    original,
+                });
+            }
+        }
+        conflicts.sort_by(|a, b| a.conflict.cmp(&b.conflict));
+        Ok(conflicts)
+    }
+}
+
+/// The file a sync conflict copies, recognising Dropbox's `Note (conflicted copy 2024-03-01).md`
+/// and Syncthing's `Note.sync-conflict-20240301-120000-ABCDEFG.md`
+pub fn conflict_original(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_str()?;
+    let (stem, extension) = match name.rfind('.') {
+        Some(dot) if dot > 0 => name.split_at(dot),
+        _ => (name, ""),
+    };
+
+    let original_stem = if let Some(marker) = stem.find(".sync-conflict-") {
+        &stem[..marker]
+    } else {
+        let open = stem.rfind(" (")?;
+        let inner = stem[open + 2..].strip_suffix(')')?;
+        if !inner.contains("conflicted copy") {
+            return None;
+        }
+        &stem[..open]
+    };
+    if original_stem.is_empty() {
+        return None;
+    }
+
+    Some(path.with_file_name(format!("{original_stem}{extension}")))
+}
+
+/// A longest-common-subsequence diff of two texts' lines
+fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lengths[i][j] is the LCS length of old[i..] and new[j..]
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(
+        old[i..]
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string())),
+    );
+    diff.extend(
+        new[j..]
+            .iter()
+            .map(|line| DiffLine::Added(line.to_string())),
+    );
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conflict_original_recognises_sync_tools() {
+        let original = |name: &str| conflict_original(Path::new(name));
+
+        assert_eq!(
+            original("notes/Plan (conflicted copy 2024-03-01).md"),
+            Some(PathBuf::from("notes/Plan.md"))
+        );
+        assert_eq!(
+            original("Plan (Ada's conflicted copy 2024-03-01).md"),
+            Some(PathBuf::from("Plan.md"))
+        );
+        assert_eq!(
+            original("photo.sync-conflict-20240301-120000-ABCDEFG.png"),
+            Some(PathBuf::from("photo.png"))
+        );
+        assert_eq!(original("Plan (draft).md"), None);
+        assert_eq!(original("Plan.md"), None);
+    }
+
+    #[test]
+    fn sync_conflicts_pairs_and_diffs() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("Plan.md"), "# Plan\nShip it\nCelebrate\n").unwrap();
+        fs::write(
+            dir.path()
+                .join("Plan.sync-conflict-20240301-120000-ABCDEFG.md"),
+            "# Plan\nShip it tomorrow\nCelebrate\n",
+        )
+        .unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let conflicts = vault.sync_conflicts().unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].original, dir.path().join("Plan.md"));
+        assert_eq!(
+            conflicts[0].diff().unwrap(),
+            vec![
+                DiffLine::Same("# Plan".to_string()),
+                DiffLine::Removed("Ship it".to_string()),
+                DiffLine::Added("Ship it tomorrow".to_string()),
+                DiffLine::Same("Celebrate".to_string()),
+            ]
+        );
+    }
+}
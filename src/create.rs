@@ -0,0 +1,117 @@
+use std::path::{Path, PathBuf};
+
+use crate::{Error, NewFileLocation, ObsidianNote, Properties, Vault};
+
+/// Characters Obsidian doesn't allow in file names
+pub(crate) const FORBIDDEN: [char; 9] = ['\\', '/', ':', '*', '?', '"', '<', '>', '|'];
+
+impl Vault {
+    /// Creates a note in the folder the vault's "default location for new notes" setting
+    /// picks, returning the parsed note
+    ///
+    /// Characters that can't appear in file names are dropped from `title`, and a taken name
+    /// gets a number as in `Title 1.md`. With the "same folder as current file" setting, which
+    /// needs an open file, notes go in the vault root.
+    pub fn create_note(
+        &self,
+        title: &str,
+        body: &str,
+        properties: Option<&Properties>,
+    ) -> crate::Result<ObsidianNote> {
+        let app = self.config()?.app;
+        let folder = match app.new_file_location {
+            NewFileLocation::Folder => self.path.join(app.new_file_folder_path.trim_matches('/')),
+            NewFileLocation::Root | NewFileLocation::Current => self.path.clone(),
+        };
+        self.create_note_in(&folder, title, body, properties)
+    }
+
+    /// Like [`Vault::create_note`], in `folder` (absolute or relative to the vault root)
+    pub fn create_note_in(
+        &self,
+        folder: &Path,
+        title: &str,
+        body: &str,
+        properties: Option<&Properties>,
+    ) -> crate::Result<ObsidianNote> {
+        let folder = self.path.join(folder);
+        let mut contents = String::new();
+        if let Some(properties) = properties.filter(|p| !is_empty(p)) {
+            contents.push_str("---\n");
+            contents.push_str(&serde_yaml::to_string(properties)?);
+            contents.push_str("---\n");
+        }
+        contents.push_str(body);
+
+        let title: String = title.chars().filter(|c| !FORBIDDEN.contains(c)).collect();
+        let title = match title.trim() {
+            "" => "Untitled",
+            title => title,
+        };
+        let path = (0..)
+            .map(|n| numbered_path(&folder, title, n))
+            .find(|path| !path.exists())
+            .ok_or_else(|| Error::AlreadyExists(folder.join(format!("{title}.md"))))?;
+        self.writer.write(&path, contents.as_str())?;
+        ObsidianNote::parse(&path, contents)
+    }
+}
+
+/// `Title.md`, then `Title 1.md`, `Title 2.md` and so on
+pub(crate) fn numbered_path(folder: &Path, title: &str, n: usize) -> PathBuf {
+    match n {
+        0 => folder.join(format!("{title}.md")),
+        n => folder.join(format!("{title} {n}.md")),
+    }
+}
+
+fn is_empty(properties: &Properties) -> bool {
+    match properties {
+        Properties::Null => true,
+        Properties::Mapping(mapping) => mapping.is_empty(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CONFIG_DIR;
+    use std::fs;
+
+    #[test]
+    fn create_note_uses_configured_folder_and_numbers_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join(CONFIG_DIR)).unwrap();
+        fs::write(
+            dir.path().join(CONFIG_DIR).join("app.json"),
+            r#"{"newFileLocation": "folder", "newFileFolderPath": "Inbox"}"#,
+        )
+        .unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+        let properties: Properties = serde_yaml::from_str("status: new").unwrap();
+
+        let first = vault
+            .create_note("Plan: v2", "# Plan\n", Some(&properties))
+            .unwrap();
+        assert_eq!(first.file_path, dir.path().join("Inbox/Plan v2.md"));
+        assert_eq!(
+            fs::read_to_string(&first.file_path).unwrap(),
+            "---\nstatus: new\n---\n# Plan\n"
+        );
+        assert_eq!(first.properties, Some(properties));
+
+        let second = vault.create_note("Plan v2", "", None).unwrap();
+        assert_eq!(second.file_path, dir.path().join("Inbox/Plan v2 1.md"));
+    }
+
+    #[test]
+    fn create_note_defaults_to_vault_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let note = vault.create_note("  ", "Body", None).unwrap();
+        assert_eq!(note.file_path, dir.path().join("Untitled.md"));
+        assert_eq!(note.file_body, "Body");
+    }
+}
@@ -0,0 +1,499 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::read_config,
+    expand_template, expand_templater, format_moment,
+    headings::{atx_heading, find_heading},
+    parse_moment,
+    rename::path_to_link,
+    Error, ObsidianNote, Position, ReadOptions, TemplateContext, TextEdit, Vault,
+};
+
+pub const DEFAULT_DAILY_FORMAT: &str = "YYYY-MM-DD";
+
+/// Properties that date a note for [`Vault::notes_between`], as dates or datetimes
+pub const DATE_PROPERTIES: [&str; 3] = ["created", "date", "due"];
+
+/// The periods the Periodic Notes plugin keeps notes for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl Period {
+    /// The plugin's format when none is configured
+    pub fn default_format(self) -> &'static str {
+        match self {
+            Self::Day => DEFAULT_DAILY_FORMAT,
+            Self::Week => "gggg-[W]ww",
+            Self::Month => "YYYY-MM",
+            Self::Quarter => "YYYY-[Q]Q",
+            Self::Year => "YYYY",
+        }
+    }
+}
+
+/// `daily-notes.json`, the daily notes core plugin's settings
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DailyNotesConfig {
+    pub folder: String,
+    /// A moment.js format, which may include `/` to nest notes in folders. Empty means
+    /// [`DEFAULT_DAILY_FORMAT`].
+    pub format: String,
+    /// The template's path from the vault root, usually without `.md`
+    pub template: String,
+    /// Settings without a typed field
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+impl DailyNotesConfig {
+    pub fn format(&self) -> &str {
+        match self.format.trim() {
+            "" => DEFAULT_DAILY_FORMAT,
+            format => format,
+        }
+    }
+}
+
+/// One period's settings in the Periodic Notes plugin's `data.json`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PeriodicNoteConfig {
+    pub enabled: bool,
+    pub folder: String,
+    /// Empty means [`Period::default_format`]
+    pub format: String,
+    pub template: String,
+}
+
+/// The Periodic Notes plugin's `data.json`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PeriodicNotesConfig {
+    pub daily: PeriodicNoteConfig,
+    pub weekly: PeriodicNoteConfig,
+    pub monthly: PeriodicNoteConfig,
+    pub quarterly: PeriodicNoteConfig,
+    pub yearly: PeriodicNoteConfig,
+    /// Settings without a typed field
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+impl PeriodicNotesConfig {
+    pub fn get(&self, period: Period) -> &PeriodicNoteConfig {
+        match period {
+            Period::Day => &self.daily,
+            Period::Week => &self.weekly,
+            Period::Month => &self.monthly,
+            Period::Quarter => &self.quarterly,
+            Period::Year => &self.yearly,
+        }
+    }
+}
+
+impl Vault {
+    pub fn daily_notes_config(&self) -> crate::Result<DailyNotesConfig> {
+        Ok(read_config(&self.config_dir(), "daily-notes.json")?.unwrap_or_default())
+    }
+
+    /// Where the daily note for `date` lives, whether or not it exists
+    pub fn daily_note_path(&self, date: NaiveDate) -> crate::Result<PathBuf> {
+        let config = self.daily_notes_config()?;
+        Ok(self.period_path(&config.folder, &format_moment(date.into(), config.format())))
+    }
+
+    pub fn daily_note(&self, date: NaiveDate) -> crate::Result<Option<ObsidianNote>> {
+        read_if_exists(&self.daily_note_path(date)?, self.read_options)
+    }
+
+    /// Today's daily note, in local time
+    pub fn today(&self) -> crate::Result<Option<ObsidianNote>> {
+        self.daily_note(Local::now().date_naive())
+    }
+
+    /// Creates the daily note for `date` from the configured template, failing if it already
+    /// exists
+    pub fn create_daily_note(&self, date: NaiveDate) -> crate::Result<ObsidianNote> {
+        let config = self.daily_notes_config()?;
+        let path = self.period_path(&config.folder, &format_moment(date.into(), config.format()));
+        // The note's date with the current time, as Obsidian fills in `{{time}}`
+        let now = date.and_time(Local::now().time());
+        self.create_from_template(&path, &config.template, now, config.format())
+    }
+
+    pub fn periodic_notes_config(&self) -> crate::Result<PeriodicNotesConfig> {
+        let plugin_dir = self.config_dir().join("plugins/periodic-notes");
+        Ok(read_config(&plugin_dir, "data.json")?.unwrap_or_default())
+    }
+
+    /// Where the note for the period containing `date` lives, whether or not it exists
+    pub fn periodic_note_path(&self, period: Period, date: NaiveDate) -> crate::Result<PathBuf> {
+        let (folder, format, _) = self.period_settings(period)?;
+        Ok(self.period_path(&folder, &format_moment(date.into(), &format)))
+    }
+
+    pub fn periodic_note(
+        &self,
+        period: Period,
+        date: NaiveDate,
+    ) -> crate::Result<Option<ObsidianNote>> {
+        read_if_exists(&self.periodic_note_path(period, date)?, self.read_options)
+    }
+
+    /// The first day of the period a note name such as `2024-W07` refers to, using the
+    /// configured format
+    pub fn periodic_note_date(
+        &self,
+        period: Period,
+        name: &str,
+    ) -> crate::Result<Option<NaiveDate>> {
+        let (_, format, _) = self.period_settings(period)?;
+        Ok(parse_moment(name, &format).map(|datetime| datetime.date()))
+    }
+
+    /// Creates the note for the period containing `date` from the period's template, failing
+    /// if it already exists
+    pub fn create_periodic_note(
+        &self,
+        period: Period,
+        date: NaiveDate,
+    ) -> crate::Result<ObsidianNote> {
+        let (folder, format, template) = self.period_settings(period)?;
+        let name = format_moment(date.into(), &format);
+        let path = self.period_path(&folder, &name);
+        // Templates see the start of the period
+        let start = parse_moment(&name, &format).map_or(date, |datetime| datetime.date());
+        let now = start.and_time(Local::now().time());
+        self.create_from_template(&path, &template, now, &format)
+    }
+
+    /// The folder, format and template for a period. Days use the core daily notes settings
+    /// unless the plugin manages them.
+    fn period_settings(&self, period: Period) -> crate::Result<(String, String, String)> {
+        let plugin = self.periodic_notes_config()?;
+        let config = plugin.get(period);
+        if period == Period::Day && !config.enabled {
+            let daily = self.daily_notes_config()?;
+            return Ok((
+                daily.folder.clone(),
+                daily.format().to_string(),
+                daily.template,
+            ));
+        }
+
+        let format = match config.format.trim() {
+            "" => period.default_format(),
+            format => format,
+        };
+        Ok((
+            config.folder.clone(),
+            format.to_string(),
+            config.template.clone(),
+        ))
+    }
+
+    /// Notes dated `date`, see [`Vault::notes_between`]
+    pub fn notes_on(&self, date: NaiveDate) -> crate::Result<Vec<ObsidianNote>> {
+        self.notes_between(date, date)
+    }
+
+    /// Notes dated between `start` and `end` inclusive, by daily note name or one of the
+    /// [`DATE_PROPERTIES`], in date order. A note with several dates in the range is listed
+    /// once, at the earliest.
+    pub fn notes_between(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> crate::Result<Vec<ObsidianNote>> {
+        let (folder, format, _) = self.period_settings(Period::Day)?;
+        let folder = self.path.join(folder.trim_matches('/'));
+
+        let mut dated = Vec::new();
+        for note in self.notes() {
+            let note = note?;
+            let from_name =
+                note.file_path.strip_prefix(&folder).ok().and_then(|name| {
+                    parse_moment(&path_to_link(&name.with_extension("")), &format)
+                });
+            let from_properties = DATE_PROPERTIES.iter().filter_map(|key| {
+                let value = note.properties.as_ref()?.get(key)?.as_str()?;
+                parse_moment(value.get(..10)?, DEFAULT_DAILY_FORMAT)
+            });
+
+            let date = from_name
+                .into_iter()
+                .chain(from_properties)
+                .map(|datetime| datetime.date())
+                .filter(|date| (start..=end).contains(date))
+                .min();
+            if let Some(date) = date {
+                dated.push((date, note));
+            }
+        }
+
+        dated.sort_by(|(a, x), (b, y)| a.cmp(b).then_with(|| x.file_path.cmp(&y.file_path)));
+        Ok(dated.into_iter().map(|(_, note)| note).collect())
+    }
+
+    /// Adds `text` to the end of a section of the daily note for `date`, creating the note
+    /// from its template if needed. A missing heading is added at the end of the note, as
+    /// given if it's written like `## Log` and as a level 2 heading otherwise.
+    pub fn append_to_daily_note(
+        &self,
+        date: NaiveDate,
+        heading: &str,
+        text: &str,
+    ) -> crate::Result<ObsidianNote> {
+        let mut note = match self.daily_note(date)? {
+            Some(note) => note,
+            None => self.create_daily_note(date)?,
+        };
+
+        let heading = heading.trim();
+        if find_heading(&note.headings(), heading).is_none() {
+            let line = match atx_heading(heading) {
+                Some(_) => heading.to_string(),
+                None => format!("## {heading}"),
+            };
+            let end = note.file_body.len();
+            let separator = if end == 0 { "" } else { "\n\n" };
+            note.edit_body(&[TextEdit::new(end..end, format!("{separator}{line}"))])?;
+        }
+        note.insert_under_heading(heading, text, Position::End)?;
+
+        self.writer.write(&note.file_path, note.to_string())?;
+        Ok(note)
+    }
+
+    /// `folder/name.md` under the vault root
+    pub(crate) fn period_path(&self, folder: &str, name: &str) -> PathBuf {
+        let folder = folder.trim_matches('/');
+        self.path.join(folder).join(format!("{name}.md"))
+    }
+
+    /// Writes a new note at `path` from a template (or an empty note), with `{{date}}` in
+    /// `date_format`
+    pub(crate) fn create_from_template(
+        &self,
+        path: &Path,
+        template: &str,
+        datetime: NaiveDateTime,
+        date_format: &str,
+    ) -> crate::Result<ObsidianNote> {
+        if path.exists() {
+            return Err(Error::AlreadyExists(path.to_path_buf()));
+        }
+
+        let template = match template.trim().trim_start_matches('/') {
+            "" => String::new(),
+            template => {
+                let mut template_path = self.path.join(template);
+                if template_path.extension().is_none_or(|ext| ext != "md") {
+                    template_path.set_extension("md");
+                }
+                fs::read_to_string(template_path)?
+            }
+        };
+        let title = path.file_stem().unwrap_or_default().to_string_lossy();
+        let mut context = TemplateContext::new(&title).at(datetime);
+        context.date_format = date_format.to_string();
+        let contents = expand_template(&expand_templater(&template, &context)?, &context);
+
+        self.writer.write(path, contents.as_str())?;
+        ObsidianNote::parse(path, contents)
+    }
+}
+
+/// Reads a note as [`Vault::notes`] would, so one it skips counts as missing
+fn read_if_exists(path: &Path, options: ReadOptions) -> crate::Result<Option<ObsidianNote>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let note = ObsidianNote::read_from_path_with(path, options);
+    if !options.keeps(&note) {
+        return Ok(None);
+    }
+    note.map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::vault_with_files;
+    use crate::CONFIG_DIR;
+
+    #[test]
+    fn daily_note_uses_configured_folder_and_format() {
+        let (dir, vault) = vault_with_files(&[
+            (
+                &format!("{CONFIG_DIR}/daily-notes.json"),
+                r#"{"folder": "Journal/", "format": "YYYY/MM/YYYY-MM-DD ddd"}"#,
+            ),
+            ("Journal/2024/03/2024-03-01 Fri.md", "Friday"),
+        ]);
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+
+        assert_eq!(
+            vault.daily_note_path(date).unwrap(),
+            dir.path().join("Journal/2024/03/2024-03-01 Fri.md")
+        );
+        assert_eq!(vault.daily_note(date).unwrap().unwrap().file_body, "Friday");
+        assert!(vault
+            .daily_note(date.succ_opt().unwrap())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn daily_note_applies_read_options() {
+        let (dir, vault) = vault_with_files(&[]);
+        fs::write(dir.path().join("2024-03-01.md"), b"Fri\xff").unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let with = |options| vault.clone().with_read_options(options).daily_note(date);
+
+        assert!(matches!(vault.daily_note(date), Err(Error::InvalidUtf8(_))));
+        let lossy = with(ReadOptions {
+            lossy: true,
+            ..ReadOptions::default()
+        });
+        assert_eq!(lossy.unwrap().unwrap().file_body, "Fri\u{fffd}");
+        let skipping = with(ReadOptions {
+            skip_invalid: true,
+            ..ReadOptions::default()
+        });
+        assert!(skipping.unwrap().is_none());
+    }
+
+    #[test]
+    fn create_daily_note_fills_in_template() {
+        let (dir, vault) = vault_with_files(&[
+            (
+                &format!("{CONFIG_DIR}/daily-notes.json"),
+                r#"{"template": "Templates/Daily"}"#,
+            ),
+            (
+                "Templates/Daily.md",
+                "# {{title}}\n{{date:dddd}}, {{ unknown }}\n",
+            ),
+        ]);
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+
+        let note = vault.create_daily_note(date).unwrap();
+        assert_eq!(note.file_path, dir.path().join("2024-03-01.md"));
+        assert_eq!(
+            fs::read_to_string(&note.file_path).unwrap(),
+            "# 2024-03-01\nFriday, {{ unknown }}\n"
+        );
+        assert!(matches!(
+            vault.create_daily_note(date),
+            Err(Error::AlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    fn periodic_notes_use_plugin_settings() {
+        let (dir, vault) = vault_with_files(&[
+            (
+                &format!("{CONFIG_DIR}/plugins/periodic-notes/data.json"),
+                r#"{
+                    "weekly": {"enabled": true, "folder": "Weekly", "format": "GGGG-[W]WW", "template": "Week"},
+                    "quarterly": {"enabled": true, "folder": "Quarters"}
+                }"#,
+            ),
+            ("Week.md", "Week of {{date:MMM D}}"),
+            ("Quarters/2024-Q1.md", "Q1"),
+        ]);
+        let date = NaiveDate::from_ymd_opt(2024, 2, 15).unwrap();
+
+        let note = vault.create_periodic_note(Period::Week, date).unwrap();
+        assert_eq!(note.file_path, dir.path().join("Weekly/2024-W07.md"));
+        assert_eq!(note.file_body, "Week of Feb 12");
+
+        assert_eq!(
+            vault
+                .periodic_note(Period::Quarter, date)
+                .unwrap()
+                .unwrap()
+                .file_body,
+            "Q1"
+        );
+        assert_eq!(
+            vault.periodic_note_path(Period::Month, date).unwrap(),
+            dir.path().join("2024-02.md")
+        );
+        assert_eq!(
+            vault
+                .periodic_note_date(Period::Quarter, "2024-Q1")
+                .unwrap(),
+            NaiveDate::from_ymd_opt(2024, 1, 1)
+        );
+    }
+
+    #[test]
+    fn notes_between_uses_daily_names_and_date_properties() {
+        let (_dir, vault) = vault_with_files(&[
+            (
+                &format!("{CONFIG_DIR}/daily-notes.json"),
+                r#"{"folder": "Journal", "format": "YYYY/YYYY-MM-DD"}"#,
+            ),
+            ("Journal/2024/2024-03-02.md", "Saturday"),
+            ("Journal/2024/2024-03-09.md", "Too late"),
+            ("Meeting.md", "---\ncreated: 2024-03-01T09:30\n---\nMeeting"),
+            (
+                "Task.md",
+                "---\ndue: 2024-03-02\ndate: 2023-01-01\n---\nTask",
+            ),
+            ("2024-03-01.md", "Not in the daily folder"),
+        ]);
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let bodies = |notes: Vec<ObsidianNote>| -> Vec<String> {
+            notes.into_iter().map(|note| note.file_body).collect()
+        };
+
+        assert_eq!(
+            bodies(vault.notes_between(day(1), day(7)).unwrap()),
+            vec!["Meeting", "Saturday", "Task"]
+        );
+        assert_eq!(bodies(vault.notes_on(day(9)).unwrap()), vec!["Too late"]);
+    }
+
+    #[test]
+    fn append_to_daily_note_creates_note_and_heading() {
+        let (dir, vault) =
+            vault_with_files(&[("2024-03-02.md", "# Saturday\n## Log\n- woke up\n## Notes\n")]);
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+
+        vault
+            .append_to_daily_note(day(2), "Log", "- coffee")
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("2024-03-02.md")).unwrap(),
+            "# Saturday\n## Log\n- woke up\n- coffee\n## Notes\n"
+        );
+
+        vault
+            .append_to_daily_note(day(3), "### Log", "- rest")
+            .unwrap();
+        vault
+            .append_to_daily_note(day(3), "### Log", "- read")
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("2024-03-03.md")).unwrap(),
+            "### Log\n- rest\n- read"
+        );
+    }
+}
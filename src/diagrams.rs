@@ -0,0 +1,104 @@
+use std::ops::Range;
+
+use crate::{parse_code_blocks, ObsidianNote, TextEdit};
+
+/// Code block languages that hold diagram source rather than code
+pub const DIAGRAM_LANGUAGES: &[&str] = &["mermaid", "plantuml", "dot", "graphviz", "d2", "tikz"];
+
+/// A fenced code block in one of the [`DIAGRAM_LANGUAGES`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagram {
+    /// The language as written on the opening fence, e.g. `mermaid`
+    pub language: String,
+    pub source: String,
+    /// Byte range of the whole block, fences included
+    pub span: Range<usize>,
+}
+
+impl ObsidianNote {
+    pub fn diagrams(&self) -> Vec<Diagram> {
+        parse_diagrams(&self.file_body)
+    }
+
+    /// Replaces each diagram for which `render` returns `Some`, such as with an embed of an SVG
+    /// rendered from it. Diagrams it returns `None` for are left as code blocks.
+    pub fn replace_diagrams(
+        &mut self,
+        mut render: impl FnMut(&Diagram) -> Option<String>,
+    ) -> crate::Result<()> {
+        let edits: Vec<TextEdit> = self
+            .diagrams()
+            .into_iter()
+            .filter_map(|diagram| {
+                let rendered = render(&diagram)?;
+                Some(TextEdit::new(diagram.span, rendered))
+            })
+            .collect();
+        if edits.is_empty() {
+            return Ok(());
+        }
+        self.edit_body(&edits)
+    }
+}
+
+pub fn parse_diagrams(text: &str) -> Vec<Diagram> {
+    parse_code_blocks(text)
+        .into_iter()
+        .filter_map(|block| {
+            let language = block.language?;
+            is_diagram_language(&language).then_some(Diagram {
+                language,
+                source: block.contents,
+                span: block.span,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn is_diagram_language(language: &str) -> bool {
+    DIAGRAM_LANGUAGES
+        .iter()
+        .any(|diagram| diagram.eq_ignore_ascii_case(language))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::path::Path;
+
+    #[test]
+    fn replace_diagrams_swaps_rendered_blocks() {
+        let mut note = ObsidianNote::parse(
+            Path::new("a.md"),
+            indoc! {r"
+                ```mermaid
+                graph TD
+                  A --> B
+                ```
+                ```rust
+                fn main() {}
+                ```
+                ```dot
+                digraph {}
+                ```
+            "}
+            .to_string(),
+        )
+        .unwrap();
+
+        let diagrams = note.diagrams();
+        assert_eq!(diagrams.len(), 2);
+        assert_eq!(diagrams[0].language, "mermaid");
+        assert_eq!(diagrams[0].source, "graph TD\n  A --> B\n");
+
+        note.replace_diagrams(|diagram| {
+            (diagram.language == "mermaid").then(|| "![[diagram-1.svg]]\n".to_string())
+        })
+        .unwrap();
+        assert_eq!(
+            note.file_body,
+            "![[diagram-1.svg]]\n```rust\nfn main() {}\n```\n```dot\ndigraph {}\n```"
+        );
+    }
+}
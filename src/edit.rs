@@ -0,0 +1,216 @@
+use std::ops::Range;
+
+use crate::{headings::find_heading, Error, ObsidianNote, SourceRange};
+
+/// A replacement of a byte range within a note's body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub span: Range<usize>,
+    pub replacement: String,
+}
+
+impl TextEdit {
+    pub fn new(span: Range<usize>, replacement: impl Into<String>) -> Self {
+        Self {
+            span,
+            replacement: replacement.into(),
+        }
+    }
+}
+
+/// Where [`ObsidianNote::insert_under_heading`] puts text within a section
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    /// Right below the heading
+    Start,
+    /// After the section's last line, before any nested or following heading
+    #[default]
+    End,
+}
+
+/// Applies non-overlapping edits to `text`, in any order
+pub fn apply_edits(text: &str, edits: &[TextEdit]) -> String {
+    let mut edits: Vec<&TextEdit> = edits.iter().collect();
+    edits.sort_by_key(|edit| edit.span.start);
+
+    let mut result = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for edit in edits {
+        result.push_str(&text[cursor..edit.span.start]);
+        result.push_str(&edit.replacement);
+        cursor = edit.span.end;
+    }
+    result.push_str(&text[cursor..]);
+
+    result
+}
+
+impl ObsidianNote {
+    /// Applies edits with spans relative to `file_body`. Line breaks in the replacements follow
+    /// the note's [`LineEnding`](crate::LineEnding).
+    pub fn edit_body(&mut self, edits: &[TextEdit]) -> crate::Result<()> {
+        if edits.is_empty() {
+            return Ok(());
+        }
+
+        let edits: Vec<TextEdit> = edits
+            .iter()
+            .map(|edit| TextEdit::new(edit.span.clone(), self.line_ending.apply(&edit.replacement)))
+            .collect();
+        self.file_body = apply_edits(&self.file_body, &edits);
+        let start = self.body_range.bytes.start;
+        self.file_contents
+            .replace_range(self.body_range.bytes.clone(), &self.file_body);
+        self.body_range =
+            SourceRange::new(&self.file_contents, start..start + self.file_body.len());
+
+        Ok(())
+    }
+}
+
+impl ObsidianNote {
+    /// Inserts `text` as whole lines in the section under a heading, such as `## Log` or just
+    /// `Log` for any level, leaving the rest of the body as it was
+    ///
+    /// With [`Position::End`], the text goes after the section's own content, before any
+    /// subheadings and trailing blank lines.
+    pub fn insert_under_heading(
+        &mut self,
+        heading: &str,
+        text: &str,
+        position: Position,
+    ) -> crate::Result<()> {
+        let body = &self.file_body;
+        let headings = self.headings();
+        let index = find_heading(&headings, heading).ok_or_else(|| Error::HeadingNotFound {
+            note: self.file_path.clone(),
+            heading: heading.to_string(),
+        })?;
+
+        let heading_end = headings[index].span.end;
+        let at = match position {
+            Position::Start => heading_end,
+            Position::End => {
+                let end = headings
+                    .get(index + 1)
+                    .map_or(body.len(), |next| next.span.start);
+                heading_end + body[heading_end..end].trim_end().len()
+            }
+        };
+
+        // `at` is at the end of a line: insert after its newline, or add one
+        let mut insertion = String::new();
+        let at = if body[at..].starts_with("\r\n") {
+            at + 2
+        } else if body[at..].starts_with('\n') {
+            at + 1
+        } else {
+            insertion.push('\n');
+            at
+        };
+        insertion.push_str(text.trim_end_matches('\n'));
+        if at < body.len() {
+            insertion.push('\n');
+        }
+
+        self.edit_body(&[TextEdit::new(at..at, insertion)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::path::Path;
+
+    #[test]
+    fn apply_edits_handles_unordered_edits() {
+        let edits = vec![TextEdit::new(6..11, "there"), TextEdit::new(0..5, "Hi")];
+        assert_eq!(apply_edits("Hello world!", &edits), "Hi there!");
+    }
+
+    #[test]
+    fn edit_body_updates_contents() {
+        let mut note = ObsidianNote::parse(
+            Path::new("a.md"),
+            "---\nkey: Hello\n---\n\nHello world\n".to_string(),
+        )
+        .unwrap();
+        note.edit_body(&[TextEdit::new(0..5, "Goodbye")]).unwrap();
+
+        assert_eq!(note.file_body, "Goodbye world");
+        assert_eq!(note.to_string(), "---\nkey: Hello\n---\n\nGoodbye world\n");
+
+        note.edit_body(&[TextEdit::new(7..7, "\nbig")]).unwrap();
+        assert_eq!(
+            note.file_contents,
+            "---\nkey: Hello\n---\n\nGoodbye\nbig world\n"
+        );
+        assert_eq!(
+            &note.file_contents[note.body_range.bytes.clone()],
+            note.file_body
+        );
+        assert_eq!(
+            (note.body_range.start_line, note.body_range.end_line),
+            (5, 6)
+        );
+    }
+
+    #[test]
+    fn edit_body_keeps_unsaved_properties() {
+        let mut note = ObsidianNote::parse(
+            Path::new("a.md"),
+            "---\naliases: [Ada]\n---\nBody\n".to_string(),
+        )
+        .unwrap();
+        note.set_property("aliases", vec!["Countess"]).unwrap();
+        note.edit_body(&[TextEdit::new(0..4, "Text")]).unwrap();
+
+        assert_eq!(note.aliases(), vec!["Countess"]);
+        assert_eq!(note.to_string(), "---\naliases:\n- Countess\n---\nText\n");
+    }
+
+    #[test]
+    fn insert_under_heading_respects_section_boundaries() {
+        let mut note = ObsidianNote::parse(
+            Path::new("a.md"),
+            indoc! {"
+                # Day
+                ## Log
+                - first
+
+                ### Details
+                ## Tasks
+                - [ ] task
+            "}
+            .to_string(),
+        )
+        .unwrap();
+
+        note.insert_under_heading("## Log", "- second", Position::End)
+            .unwrap();
+        note.insert_under_heading("Log", "- zeroth", Position::Start)
+            .unwrap();
+        note.insert_under_heading("## Tasks", "- [ ] another\n", Position::End)
+            .unwrap();
+
+        assert_eq!(
+            note.file_body,
+            indoc! {"
+                # Day
+                ## Log
+                - zeroth
+                - first
+                - second
+
+                ### Details
+                ## Tasks
+                - [ ] task
+                - [ ] another"}
+        );
+        assert!(matches!(
+            note.insert_under_heading("# Log", "x", Position::End),
+            Err(Error::HeadingNotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,244 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    edit::{apply_edits, TextEdit},
+    links::scan_wikilinks,
+    vault::is_note,
+    LinkResolver, ObsidianNote, Vault, WikiLink,
+};
+
+const IMAGE_EXTENSIONS: &[&str] = &["avif", "bmp", "gif", "jpeg", "jpg", "png", "svg", "webp"];
+/// Other file types Obsidian can embed
+const ATTACHMENT_EXTENSIONS: &[&str] = &[
+    "3gp", "canvas", "flac", "m4a", "mkv", "mov", "mp3", "mp4", "ogg", "ogv", "pdf", "wav", "webm",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Embed {
+    /// Transclusion of another note, or a heading or block within it
+    Note(WikiLink),
+    Image(WikiLink),
+    /// A PDF, audio, video or canvas file
+    Attachment(WikiLink),
+}
+
+impl Embed {
+    /// Classifies an embed by its target's extension. Targets without a known image or
+    /// attachment extension are notes, as in `![[v1.2 release notes]]`.
+    pub(crate) fn from_link(link: WikiLink) -> Self {
+        let extension = Path::new(&link.target)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some(ext) if IMAGE_EXTENSIONS.contains(&ext) => Self::Image(link),
+            Some(ext) if ATTACHMENT_EXTENSIONS.contains(&ext) => Self::Attachment(link),
+            _ => Self::Note(link),
+        }
+    }
+
+    pub fn link(&self) -> &WikiLink {
+        match self {
+            Self::Note(link) | Self::Image(link) | Self::Attachment(link) => link,
+        }
+    }
+
+    pub fn target(&self) -> &str {
+        &self.link().target
+    }
+}
+
+impl ObsidianNote {
+    pub fn embeds(&self) -> Vec<Embed> {
+        parse_embeds(&self.file_body)
+    }
+
+    /// The note's body with note, heading and block embeds replaced by what they embed, in
+    /// turn flattened up to `depth_limit` levels deep. Embeds that don't resolve, that would
+    /// embed themselves or that are nested deeper are left as written, as are attachments.
+    pub fn flatten_embeds(&self, vault: &Vault, depth_limit: usize) -> crate::Result<String> {
+        let resolver = LinkResolver::from_vault(vault)?;
+        let mut flattener = Flattener {
+            vault,
+            resolver: &resolver,
+            depth_limit,
+            root: self,
+            notes: HashMap::new(),
+            stack: vec![(self.file_path.clone(), None, None)],
+        };
+        flattener.flatten(&self.file_body, &self.file_path, 0)
+    }
+}
+
+/// A note, heading or block being embedded
+type EmbedKey = (PathBuf, Option<String>, Option<String>);
+
+struct Flattener<'a> {
+    vault: &'a Vault,
+    resolver: &'a LinkResolver,
+    depth_limit: usize,
+    /// The note being flattened, which may have unsaved changes
+    root: &'a ObsidianNote,
+    /// Other notes read so far, by path
+    notes: HashMap<PathBuf, ObsidianNote>,
+    /// The embeds being flattened, outermost first, to detect cycles
+    stack: Vec<EmbedKey>,
+}
+
+impl Flattener<'_> {
+    fn flatten(&mut self, text: &str, source: &Path, depth: usize) -> crate::Result<String> {
+        if depth >= self.depth_limit {
+            return Ok(text.to_string());
+        }
+
+        let mut edits = Vec::new();
+        for embed in parse_embeds(text) {
+            let Embed::Note(link) = embed else {
+                continue;
+            };
+            let Some(path) = self.resolver.resolve_link(&link, source) else {
+                continue;
+            };
+            let key = (path.to_path_buf(), link.heading.clone(), link.block.clone());
+            if !is_note(path) || self.stack.contains(&key) {
+                continue;
+            }
+            let Some(content) = self.content(path, &link)? else {
+                continue;
+            };
+
+            self.stack.push(key);
+            let flattened = self.flatten(&content, path, depth + 1)?;
+            self.stack.pop();
+            edits.push(TextEdit::new(link.span, flattened));
+        }
+
+        Ok(apply_edits(text, &edits))
+    }
+
+    /// The text an embed of `path` shows: a block, a section or the whole body
+    fn content(&mut self, path: &Path, link: &WikiLink) -> crate::Result<Option<String>> {
+        let note = if path == self.root.file_path {
+            self.root
+        } else {
+            if !self.notes.contains_key(path) {
+                let note = ObsidianNote::read_from_path_with(path, self.vault.read_options)?;
+                self.notes.insert(path.to_path_buf(), note);
+            }
+            &self.notes[path]
+        };
+
+        Ok(if let Some(id) = &link.block {
+            note.block(id).map(|block| block.text)
+        } else if let Some(heading) = &link.heading {
+            note.section(heading).map(|section| section.text)
+        } else {
+            Some(note.file_body.trim_end().to_string())
+        })
+    }
+}
+
+pub fn parse_embeds(text: &str) -> Vec<Embed> {
+    scan_wikilinks(text)
+        .into_iter()
+        .filter_map(|(is_embed, link)| is_embed.then(|| Embed::from_link(link)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::vault_with_files;
+
+    #[test]
+    fn parse_embeds_distinguishes_kinds() {
+        let embeds = parse_embeds("![[image.PNG]] ![[Note]] ![[paper.pdf]] ![[Other.md]]");
+        assert!(matches!(embeds[0], Embed::Image(_)));
+        assert!(matches!(embeds[1], Embed::Note(_)));
+        assert!(matches!(embeds[2], Embed::Attachment(_)));
+        assert!(matches!(embeds[3], Embed::Note(_)));
+    }
+
+    #[test]
+    fn parse_embeds_treats_dotted_names_as_notes() {
+        let embeds = parse_embeds("![[v1.2 release notes]] ![[Meeting 2024.01.05#Actions]]");
+        assert!(matches!(embeds[0], Embed::Note(_)));
+        assert!(matches!(embeds[1], Embed::Note(_)));
+    }
+
+    #[test]
+    fn parse_embeds_handles_sections_and_blocks() {
+        let embeds = parse_embeds("![[Note#Section]]\n![[Note^block]]");
+
+        assert_eq!(embeds[0].target(), "Note");
+        assert_eq!(embeds[0].link().heading.as_deref(), Some("Section"));
+        assert_eq!(embeds[1].target(), "Note");
+        assert_eq!(embeds[1].link().block.as_deref(), Some("block"));
+    }
+
+    #[test]
+    fn parse_embeds_spans_include_bang() {
+        let text = "Before ![[image.png|300]]";
+        let embeds = parse_embeds(text);
+
+        assert_eq!(&text[embeds[0].link().span.clone()], "![[image.png|300]]");
+        assert_eq!(embeds[0].link().alias.as_deref(), Some("300"));
+    }
+
+    #[test]
+    fn flatten_embeds_inlines_notes_sections_and_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                "Main.md",
+                "# Main\n![[Part]]\n\n![[Facts#Moon]]\n\nQuote: ![[Facts^quote]]\n![[pic.png]]",
+            ),
+            (
+                "Part.md",
+                "---\ntags: [part]\n---\nPart text\n![[Main]]\n![[Deep]]\n",
+            ),
+            ("Deep.md", "Deep text"),
+            (
+                "Facts.md",
+                "## Sun\nHot\n## Moon\nCold\n\nSmall step ^quote\n",
+            ),
+        ];
+        for (name, contents) in files {
+            std::fs::write(dir.path().join(name), contents).unwrap();
+        }
+        let vault = Vault::open(dir.path()).unwrap();
+        let main = ObsidianNote::read_from_path(&dir.path().join("Main.md")).unwrap();
+
+        assert_eq!(
+            main.flatten_embeds(&vault, 5).unwrap(),
+            "# Main\nPart text\n![[Main]]\nDeep text\n\n## Moon\nCold\n\nSmall step ^quote\n\n\
+             Quote: Small step\n![[pic.png]]"
+        );
+        assert_eq!(
+            main.flatten_embeds(&vault, 1).unwrap().lines().nth(3),
+            Some("![[Deep]]")
+        );
+    }
+
+    #[test]
+    fn flatten_embeds_inlines_dotted_note_names() {
+        let (dir, vault) = vault_with_files(&[
+            ("Main.md", "![[v1.2 release notes#Fixes]]"),
+            ("v1.2 release notes.md", "## Fixes\nFewer crashes\n"),
+        ]);
+        let main = ObsidianNote::read_from_path(&dir.path().join("Main.md")).unwrap();
+
+        assert_eq!(
+            main.flatten_embeds(&vault, 5).unwrap(),
+            "## Fixes\nFewer crashes"
+        );
+    }
+
+    #[test]
+    fn parse_embeds_skips_links() {
+        assert!(parse_embeds("[[Just a link]]").is_empty());
+    }
+}
@@ -0,0 +1,231 @@
+use std::{io, path::PathBuf};
+
+/// Errors returned by this crate, which also convert into `anyhow::Error`
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    Walk(#[from] walkdir::Error),
+
+    /// Frontmatter or other YAML that failed to parse. `line` and `column` are 1-based and, when
+    /// `path` is set, point into that file.
+    #[error("invalid YAML{}: {source}", in_path(path))]
+    Yaml {
+        path: Option<PathBuf>,
+        line: Option<usize>,
+        column: Option<usize>,
+        #[source]
+        source: serde_yaml::Error,
+    },
+
+    /// Frontmatter that parsed but has the wrong shape, such as a list instead of a mapping
+    #[error("invalid frontmatter in {}: {message}", path.display())]
+    InvalidFrontmatter { path: PathBuf, message: String },
+
+    /// Properties that couldn't be deserialized into the requested type
+    #[error("failed to deserialize properties of {}{}: {source}", path.display(), at_field(field))]
+    Properties {
+        path: PathBuf,
+        /// The path to the field that failed, such as `authors[1]`
+        field: String,
+        #[source]
+        source: serde_yaml::Error,
+    },
+
+    #[error("invalid JSON{}: {source}", in_path(path))]
+    Json {
+        path: Option<PathBuf>,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("invalid TOML{}: {source}", in_path(path))]
+    Toml {
+        path: Option<PathBuf>,
+        #[source]
+        source: toml::de::Error,
+    },
+
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+
+    #[error(transparent)]
+    Xml(#[from] quick_xml::Error),
+
+    #[error(transparent)]
+    Base64(#[from] base64::DecodeError),
+
+    #[error("vault path is not a directory: {}", .0.display())]
+    NotADirectory(PathBuf),
+
+    #[error("{} is not valid UTF-8", .0.display())]
+    InvalidUtf8(PathBuf),
+
+    #[error("no note at {}", .0.display())]
+    NoteNotFound(PathBuf),
+
+    #[error("no attachment at {}", .0.display())]
+    AttachmentNotFound(PathBuf),
+
+    #[error("a file already exists at {}", .0.display())]
+    AlreadyExists(PathBuf),
+
+    #[error("no heading {heading:?} in {}", note.display())]
+    HeadingNotFound { note: PathBuf, heading: String },
+
+    #[error("not a valid tag: {0:?}")]
+    InvalidTag(String),
+
+    #[error("unsupported Templater syntax: {0}")]
+    Templater(String),
+
+    #[error("invalid Excalidraw drawing: {0}")]
+    Excalidraw(String),
+
+    #[error("invalid obsidian:// URI: {0}")]
+    InvalidUri(String),
+
+    #[error("link to {target:?} in {} doesn't resolve to a file", note.display())]
+    UnresolvedLink { note: PathBuf, target: String },
+
+    #[cfg(feature = "tantivy")]
+    #[error(transparent)]
+    Tantivy(#[from] tantivy::TantivyError),
+
+    #[cfg(feature = "tantivy")]
+    #[error(transparent)]
+    SearchQuery(#[from] tantivy::query::QueryParserError),
+
+    #[cfg(feature = "sqlite")]
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[cfg(feature = "watch")]
+    #[error(transparent)]
+    Watch(#[from] notify::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// Attaches the file an error came from, for errors that carry an optional path
+    pub(crate) fn in_file(self, file: impl Into<PathBuf>) -> Self {
+        match self {
+            Self::Yaml {
+                path: None,
+                line,
+                column,
+                source,
+            } => Self::Yaml {
+                path: Some(file.into()),
+                line,
+                column,
+                source,
+            },
+            Self::Json { path: None, source } => Self::Json {
+                path: Some(file.into()),
+                source,
+            },
+            Self::Toml { path: None, source } => Self::Toml {
+                path: Some(file.into()),
+                source,
+            },
+            other => other,
+        }
+    }
+}
+
+impl From<serde_yaml::Error> for Error {
+    fn from(source: serde_yaml::Error) -> Self {
+        let location = source.location();
+        Self::Yaml {
+            path: None,
+            line: location.as_ref().map(|l| l.line()),
+            column: location.as_ref().map(|l| l.column()),
+            source,
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(source: serde_json::Error) -> Self {
+        Self::Json { path: None, source }
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(source: toml::de::Error) -> Self {
+        Self::Toml { path: None, source }
+    }
+}
+
+fn in_path(path: &Option<PathBuf>) -> String {
+    path.as_ref()
+        .map(|path| format!(" in {}", path.display()))
+        .unwrap_or_default()
+}
+
+fn at_field(field: &str) -> String {
+    if field.is_empty() || field == "." {
+        String::new()
+    } else {
+        format!(" at {field}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ObsidianNote, Vault};
+    use indoc::indoc;
+    use std::path::Path;
+
+    #[test]
+    fn yaml_errors_point_at_the_file_line() {
+        let contents = indoc! {r"
+            ---
+            title: ok
+            key: value: other
+            ---
+            Body
+        "};
+        let err = ObsidianNote::parse(Path::new("a.md"), contents.to_string()).unwrap_err();
+
+        let Error::Yaml { path, line, .. } = &err else {
+            panic!("expected a YAML error, got {err:?}");
+        };
+        assert_eq!(path.as_deref(), Some(Path::new("a.md")));
+        assert_eq!(*line, Some(3));
+        assert!(err.to_string().starts_with("invalid YAML in a.md: "));
+    }
+
+    #[test]
+    fn properties_errors_name_the_field() {
+        #[derive(Debug, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Book {
+            pages: u32,
+        }
+
+        let note =
+            ObsidianNote::parse(Path::new("book.md"), "---\npages: many\n---".to_string()).unwrap();
+        let err = note.properties_as::<Book>().unwrap_err();
+        assert!(matches!(&err, Error::Properties { field, .. } if field == "pages"));
+    }
+
+    #[test]
+    fn errors_convert_into_anyhow() {
+        fn open() -> anyhow::Result<Vault> {
+            Ok(Vault::open("/definitely/not/a/vault")?)
+        }
+
+        let err = open().unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::NotADirectory(_))
+        ));
+    }
+}
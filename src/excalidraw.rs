@@ -0,0 +1,393 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::{
+    headings::atx_heading,
+    links::{scan_wikilinks, url_scheme},
+    parse_code_blocks, Error, ObsidianNote, Vault,
+};
+
+/// A drawing from an Excalidraw plugin note (`.excalidraw.md`)
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Excalidraw {
+    /// Every element in the scene, including deleted ones
+    pub elements: Vec<ExcalidrawElement>,
+    /// The images, PDFs and notes listed under `## Embedded Files`
+    pub embedded_files: Vec<EmbeddedFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExcalidrawElement {
+    pub id: String,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    /// A `[[wikilink]]` or URL the element opens
+    #[serde(default)]
+    pub link: Option<String>,
+    #[serde(default)]
+    pub is_deleted: bool,
+    #[serde(flatten)]
+    pub kind: ExcalidrawElementKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ExcalidrawElementKind {
+    Rectangle,
+    Ellipse,
+    Diamond,
+    Line,
+    Arrow,
+    Freedraw,
+    Text {
+        text: String,
+    },
+    Image {
+        /// Key into [`Excalidraw::embedded_files`]
+        #[serde(default, rename = "fileId")]
+        file_id: Option<String>,
+    },
+    Frame {
+        #[serde(default)]
+        name: Option<String>,
+    },
+    Embeddable,
+    /// Element types added after this crate
+    #[serde(other)]
+    Other,
+}
+
+/// A line like `<file id>: [[image.png]]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedFile {
+    pub id: String,
+    /// The wikilink's target, or the URL for files embedded from the web
+    pub target: String,
+}
+
+#[derive(Deserialize)]
+struct Scene {
+    #[serde(default)]
+    elements: Vec<ExcalidrawElement>,
+}
+
+impl Excalidraw {
+    /// Reads the drawing from the body of an Excalidraw note, from its `compressed-json` or
+    /// `json` code block
+    pub fn parse(body: &str) -> crate::Result<Self> {
+        let block = parse_code_blocks(body)
+            .into_iter()
+            .find(|block| matches!(block.language.as_deref(), Some("compressed-json" | "json")));
+        let elements = match block {
+            Some(block) => {
+                let json = if block.language.as_deref() == Some("compressed-json") {
+                    decompress(&block.contents)?
+                } else {
+                    block.contents
+                };
+                if json.trim().is_empty() {
+                    Vec::new()
+                } else {
+                    serde_json::from_str::<Scene>(&json)?.elements
+                }
+            }
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            elements,
+            embedded_files: embedded_files(body),
+        })
+    }
+
+    pub fn element(&self, id: &str) -> Option<&ExcalidrawElement> {
+        self.elements.iter().find(|element| element.id == id)
+    }
+
+    pub fn embedded_file(&self, id: &str) -> Option<&EmbeddedFile> {
+        self.embedded_files.iter().find(|file| file.id == id)
+    }
+
+    /// Link targets referenced by the drawing: embedded files, element links, and wikilinks
+    /// inside text elements. Deleted elements and URLs are left out.
+    pub fn linked_files(&self) -> Vec<String> {
+        let embedded = self
+            .embedded_files
+            .iter()
+            .filter(|file| url_scheme(&file.target).is_none())
+            .map(|file| file.target.clone());
+        let elements = self
+            .elements
+            .iter()
+            .filter(|element| !element.is_deleted)
+            .flat_map(|element| {
+                let text = match &element.kind {
+                    ExcalidrawElementKind::Text { text } => text.as_str(),
+                    _ => "",
+                };
+                element
+                    .link
+                    .iter()
+                    .map(String::as_str)
+                    .chain([text])
+                    .flat_map(scan_wikilinks)
+                    .map(|(_, link)| link.target)
+                    .collect::<Vec<_>>()
+            });
+
+        embedded
+            .chain(elements)
+            .filter(|target| !target.is_empty())
+            .collect()
+    }
+}
+
+impl ObsidianNote {
+    /// Whether this is an Excalidraw plugin drawing, by its `.excalidraw.md` name or its
+    /// `excalidraw-plugin` property
+    pub fn is_excalidraw(&self) -> bool {
+        let by_name = self
+            .file_path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().ends_with(".excalidraw.md"));
+        let by_property = self
+            .properties
+            .as_ref()
+            .is_some_and(|properties| properties.get("excalidraw-plugin").is_some());
+        by_name || by_property
+    }
+
+    /// The note's drawing, or `None` if it isn't an Excalidraw note
+    pub fn excalidraw(&self) -> crate::Result<Option<Excalidraw>> {
+        if !self.is_excalidraw() {
+            return Ok(None);
+        }
+        Excalidraw::parse(&self.file_body)
+            .map(Some)
+            .map_err(|err| err.in_file(&self.file_path))
+    }
+}
+
+impl Vault {
+    pub fn excalidraw_drawings(
+        &self,
+    ) -> impl Iterator<Item = crate::Result<(PathBuf, Excalidraw)>> {
+        self.notes().filter_map(|note| {
+            let note = match note {
+                Ok(note) => note,
+                Err(err) => return Some(Err(err)),
+            };
+            note.excalidraw()
+                .transpose()
+                .map(|drawing| drawing.map(|drawing| (note.file_path, drawing)))
+        })
+    }
+}
+
+/// The `id: link` lines under the `Embedded Files` heading
+fn embedded_files(body: &str) -> Vec<EmbeddedFile> {
+    body.lines()
+        .skip_while(|line| {
+            !atx_heading(line).is_some_and(|(_, text)| text.eq_ignore_ascii_case("embedded files"))
+        })
+        .skip(1)
+        .take_while(|line| atx_heading(line).is_none() && line.trim() != "%%")
+        .filter_map(|line| {
+            let (id, link) = line.split_once(": ")?;
+            let link = link.trim();
+            let target = match scan_wikilinks(link).into_iter().next() {
+                Some((_, wikilink)) => wikilink.target,
+                None => link.to_string(),
+            };
+            Some(EmbeddedFile {
+                id: id.trim().to_string(),
+                target,
+            })
+        })
+        .collect()
+}
+
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=";
+
+/// Reverses LZString's `compressToBase64`, which the plugin stores drawings with
+fn decompress(compressed: &str) -> crate::Result<String> {
+    let corrupt = || Error::Excalidraw("the compressed drawing is corrupt".to_string());
+    let values: Vec<u32> = compressed
+        .bytes()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .map(|byte| BASE64.iter().position(|&c| c == byte).map(|i| i as u32))
+        .collect::<Option<_>>()
+        .ok_or_else(corrupt)?;
+    if values.is_empty() {
+        return Ok(String::new());
+    }
+
+    let mut bits = Bits {
+        values: &values,
+        index: 0,
+        position: 32,
+    };
+    let mut dictionary: Vec<Vec<u16>> = vec![Vec::new(); 3];
+    let mut enlarge_in = 4u32;
+    let mut num_bits = 3;
+
+    let first = match bits.read(2).ok_or_else(corrupt)? {
+        0 => bits.read(8),
+        1 => bits.read(16),
+        _ => return Ok(String::new()),
+    }
+    .ok_or_else(corrupt)?;
+    let mut previous = vec![first as u16];
+    dictionary.push(previous.clone());
+    let mut result = previous.clone();
+
+    loop {
+        let mut code = bits.read(num_bits).ok_or_else(corrupt)? as usize;
+        match code {
+            0 | 1 => {
+                let char_bits = if code == 0 { 8 } else { 16 };
+                let c = bits.read(char_bits).ok_or_else(corrupt)?;
+                dictionary.push(vec![c as u16]);
+                code = dictionary.len() - 1;
+                enlarge_in -= 1;
+            }
+            2 => return String::from_utf16(&result).map_err(|_| corrupt()),
+            _ => {}
+        }
+        if enlarge_in == 0 {
+            enlarge_in = 1 << num_bits;
+            num_bits += 1;
+        }
+
+        let entry = match dictionary.get(code) {
+            Some(entry) => entry.clone(),
+            None if code == dictionary.len() => {
+                let mut entry = previous.clone();
+                entry.push(previous[0]);
+                entry
+            }
+            None => return Err(corrupt()),
+        };
+        result.extend_from_slice(&entry);
+
+        let mut word = previous;
+        word.push(entry[0]);
+        dictionary.push(word);
+        enlarge_in -= 1;
+        previous = entry;
+
+        if enlarge_in == 0 {
+            enlarge_in = 1 << num_bits;
+            num_bits += 1;
+        }
+    }
+}
+
+/// Reads LZString's bit stream, six bits per base64 character
+struct Bits<'a> {
+    values: &'a [u32],
+    index: usize,
+    position: u32,
+}
+
+impl Bits<'_> {
+    fn read(&mut self, count: u32) -> Option<u32> {
+        let mut bits = 0;
+        for power in 0..count {
+            let value = *self.values.get(self.index)?;
+            if value & self.position != 0 {
+                bits |= 1 << power;
+            }
+            self.position >>= 1;
+            if self.position == 0 {
+                self.position = 32;
+                self.index += 1;
+            }
+        }
+        Some(bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::{fs, path::Path};
+
+    const NOTE: &str = indoc! {r"
+        ---
+        excalidraw-plugin: parsed
+        tags: [excalidraw]
+        ---
+        ==⚠  Switch to EXCALIDRAW VIEW in the MORE OPTIONS menu of this document. ⚠==
+
+        # Excalidraw Data
+
+        ## Text Elements
+        Hello “world” ^t1
+
+        ## Embedded Files
+        abc123: [[photo.png]]
+        def456: https://example.com/logo.svg
+
+        %%
+        ## Drawing
+        ```compressed-json
+        N4IgLgngDgpiBcIYA8DGBDANgSwCYCd0B3EAGhADcZ8BnbAewDsEAmcmTGAWxkbBoQBtUHgTgAjGXDQ4iMCjBTkCcQAZyEVupBE8YABYIAHNv0xsAc32L4LAKzl5yGyAASHTPQAEgHAIi9fExcQFwCEABfUhFcMXxJRxkYmFQwdEYLTiUEbU14bV1cAxVVU3MrGzttHEYAazFBQQAFfHoAKySwAF0O8MiQUURsOOlYMWwudAs4cmVcjQQANjy9Q3h5gBZyM0trBY2QADNsTgBJaMR0ACNUcRYAZh6osVwhyBHED2woGimQGeys8j5QrwcSbUo7EHkbA0AAiHBg8jOYHwAFcYBFHogaC8ElioOh8NUMtMASAcksCitQSAtmUVGEOuR0FAoABlFLyBCgCz4PCs7AAL1k
+        jBRmEwEQORxgAngwDCYSAA
+        ```
+        %%
+    "};
+
+    #[test]
+    fn excalidraw_decompresses_typed_elements() {
+        let note = ObsidianNote::parse(Path::new("Sketch.md"), NOTE.to_string()).unwrap();
+        assert!(note.is_excalidraw());
+
+        let drawing = note.excalidraw().unwrap().unwrap();
+        assert_eq!(drawing.elements.len(), 5);
+        assert_eq!(
+            drawing.element("t1").unwrap().kind,
+            ExcalidrawElementKind::Text {
+                text: "Hello “world”".to_string()
+            }
+        );
+        assert_eq!(
+            drawing.element("i1").unwrap().kind,
+            ExcalidrawElementKind::Image {
+                file_id: Some("abc123".to_string())
+            }
+        );
+        assert!(drawing.element("d1").unwrap().is_deleted);
+        assert_eq!(
+            drawing.element("s1").unwrap().kind,
+            ExcalidrawElementKind::Other
+        );
+        assert_eq!(drawing.embedded_file("abc123").unwrap().target, "photo.png");
+        assert_eq!(drawing.linked_files(), vec!["photo.png", "Project"]);
+    }
+
+    #[test]
+    fn embedded_files_join_the_link_graph() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("Sketch.excalidraw.md"), NOTE).unwrap();
+        fs::write(dir.path().join("photo.png"), b"png").unwrap();
+        fs::write(dir.path().join("Plain.md"), "No drawing").unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let drawings: Vec<_> = vault
+            .excalidraw_drawings()
+            .collect::<crate::Result<_>>()
+            .unwrap();
+        assert_eq!(drawings.len(), 1);
+
+        let links = vault.resolved_links().unwrap();
+        let sketch = &links[Path::new("Sketch.excalidraw.md")];
+        assert_eq!(sketch.get(Path::new("photo.png")), Some(&1));
+        assert!(vault.unused_attachments().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,124 @@
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::{rename::path_to_link, Vault};
+
+/// The "Excluded files" setting (`userIgnoreFilters` in `app.json`), which [`Vault::files`]
+/// and everything built on it skip
+#[derive(Debug, Default, Clone)]
+pub struct ExcludedFiles {
+    filters: Vec<Filter>,
+}
+
+#[derive(Debug, Clone)]
+enum Filter {
+    /// Excludes paths starting with it, so `Archive/` excludes a folder
+    Prefix(String),
+    /// A `/regex/` tested against the vault-relative path
+    Pattern(Regex),
+}
+
+impl ExcludedFiles {
+    /// Filters that are neither paths nor valid `/regex/`es are ignored, as in Obsidian
+    pub fn new<S: AsRef<str>>(filters: &[S]) -> Self {
+        let filters = filters
+            .iter()
+            .filter_map(|filter| {
+                let filter = filter.as_ref().trim();
+                match filter.strip_prefix('/').and_then(|f| f.strip_suffix('/')) {
+                    Some(pattern) if !pattern.is_empty() => {
+                        Regex::new(pattern).ok().map(Filter::Pattern)
+                    }
+                    _ if filter.is_empty() => None,
+                    _ => Some(Filter::Prefix(filter.to_string())),
+                }
+            })
+            .collect();
+        Self { filters }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    /// Whether a path relative to the vault root is excluded
+    pub fn excludes(&self, relative: &Path) -> bool {
+        let path = path_to_link(relative);
+        self.filters.iter().any(|filter| match filter {
+            Filter::Prefix(prefix) => path.starts_with(prefix.as_str()),
+            Filter::Pattern(regex) => regex.is_match(&path),
+        })
+    }
+
+    fn as_strs(&self) -> impl Iterator<Item = &str> {
+        self.filters.iter().map(|filter| match filter {
+            Filter::Prefix(prefix) => prefix.as_str(),
+            Filter::Pattern(regex) => regex.as_str(),
+        })
+    }
+}
+
+impl PartialEq for ExcludedFiles {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_strs().eq(other.as_strs())
+    }
+}
+
+impl Eq for ExcludedFiles {}
+
+impl Vault {
+    /// Stops skipping the vault's excluded files, for tools that need every file
+    pub fn including_excluded_files(mut self) -> Self {
+        self.excluded = ExcludedFiles::default();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LinkResolver, CONFIG_DIR};
+    use std::{fs, path::PathBuf};
+
+    #[test]
+    fn excluded_files_are_skipped_unless_included() {
+        let dir = tempfile::tempdir().unwrap();
+        for path in [
+            "Archive/Old.md",
+            "Notes/Plan.md",
+            "Notes/Plan.draft.md",
+            "Archived.md",
+        ] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        fs::create_dir_all(dir.path().join(CONFIG_DIR)).unwrap();
+        fs::write(
+            dir.path().join(CONFIG_DIR).join("app.json"),
+            r#"{"userIgnoreFilters": ["Archive/", "/\\.draft\\.md$/", "/[/"]}"#,
+        )
+        .unwrap();
+
+        let vault = Vault::open(dir.path()).unwrap();
+        let relative = |vault: &Vault| -> Vec<PathBuf> {
+            vault
+                .files()
+                .map(|path| vault.relative_path(&path.unwrap()).to_path_buf())
+                .collect()
+        };
+        assert_eq!(
+            relative(&vault),
+            vec![PathBuf::from("Archived.md"), PathBuf::from("Notes/Plan.md")]
+        );
+        let resolver = LinkResolver::from_vault(&vault).unwrap();
+        assert_eq!(
+            resolver.resolve("Old", &dir.path().join("Archived.md")),
+            None
+        );
+
+        let everything = vault.including_excluded_files();
+        assert_eq!(relative(&everything).len(), 4);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod json;
+pub mod latex;
+pub mod org;
+pub mod site;
@@ -0,0 +1,123 @@
+use serde_json::{json, Value};
+
+use crate::{rename::path_to_link, ObsidianNote, Vault, WikiLink};
+
+impl ObsidianNote {
+    /// The note and everything extracted from it as a JSON document
+    ///
+    /// Fails if the properties can't be represented in JSON, such as a mapping with non-string
+    /// keys.
+    pub fn to_json(&self) -> crate::Result<Value> {
+        let properties = match &self.properties {
+            Some(properties) => serde_json::to_value(properties)
+                .map_err(|err| crate::Error::from(err).in_file(&self.file_path))?,
+            None => Value::Null,
+        };
+
+        Ok(json!({
+            "path": path_to_link(&self.file_path),
+            "properties": properties,
+            "body": self.file_body,
+            "links": self.links().iter().map(link_json).collect::<Vec<_>>(),
+            "embeds": self.embeds().iter().map(|embed| link_json(embed.link())).collect::<Vec<_>>(),
+            "tags": self.tags().into_iter().map(|tag| tag.name).collect::<Vec<_>>(),
+            "headings": self
+                .headings()
+                .into_iter()
+                .map(|heading| json!({ "level": heading.level, "text": heading.text }))
+                .collect::<Vec<_>>(),
+            "tasks": self
+                .tasks()
+                .into_iter()
+                .map(|task| {
+                    json!({
+                        "status": task.status.to_string(),
+                        "complete": task.is_complete(),
+                        "text": task.text,
+                        "heading": task.heading,
+                        "line": task.line,
+                    })
+                })
+                .collect::<Vec<_>>(),
+        }))
+    }
+}
+
+/// Every note in the vault as an array of [`ObsidianNote::to_json`] documents, with paths
+/// relative to the vault root
+pub fn export_json(vault: &Vault) -> crate::Result<Value> {
+    let mut notes = Vec::new();
+    for note in vault.notes() {
+        let note = note?;
+        let mut document = note.to_json()?;
+        document["path"] = path_to_link(vault.relative_path(&note.file_path)).into();
+        notes.push(document);
+    }
+    Ok(Value::Array(notes))
+}
+
+fn link_json(link: &WikiLink) -> Value {
+    json!({
+        "target": link.target,
+        "heading": link.heading,
+        "block": link.block,
+        "alias": link.alias,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::{fs, path::Path};
+
+    #[test]
+    fn to_json_includes_extracted_data() {
+        let note = ObsidianNote::parse(
+            Path::new("Plan.md"),
+            indoc! {"
+                ---
+                status: active
+                tags: [work]
+                ---
+                # Goals
+
+                - [ ] Ship [[Release#Notes|release]] ![[chart.png]]
+            "}
+            .to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            note.to_json().unwrap(),
+            json!({
+                "path": "Plan.md",
+                "properties": { "status": "active", "tags": ["work"] },
+                "body": "# Goals\n\n- [ ] Ship [[Release#Notes|release]] ![[chart.png]]",
+                "links": [{ "target": "Release", "heading": "Notes", "block": null, "alias": "release" }],
+                "embeds": [{ "target": "chart.png", "heading": null, "block": null, "alias": null }],
+                "tags": ["work"],
+                "headings": [{ "level": 1, "text": "Goals" }],
+                "tasks": [{
+                    "status": " ",
+                    "complete": false,
+                    "text": "Ship [[Release#Notes|release]] ![[chart.png]]",
+                    "heading": "Goals",
+                    "line": 3,
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn export_json_uses_vault_relative_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("folder")).unwrap();
+        fs::write(dir.path().join("folder/Note.md"), "Text").unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let export = export_json(&vault).unwrap();
+        assert_eq!(export[0]["path"], "folder/Note.md");
+        assert_eq!(export[0]["properties"], Value::Null);
+    }
+}
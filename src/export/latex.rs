@@ -0,0 +1,370 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{parse_ast, Node, NodeKind},
+    callouts::parse_callouts,
+    render::wikilink_at,
+    Embed, ObsidianNote,
+};
+
+/// Defines what [`ObsidianNote::to_latex`] output relies on beyond the standard classes
+pub const LATEX_PREAMBLE: &str = r"\usepackage{amssymb}
+\usepackage{graphicx}
+\usepackage[normalem]{ulem}
+\usepackage{hyperref}
+\newenvironment{callout}[2]{\begin{quote}\textbf{#2}\par}{\end{quote}}
+";
+
+impl ObsidianNote {
+    /// The note body as a LaTeX fragment
+    ///
+    /// The fragment starts with a `\label` for the note, and each heading gets a label for the
+    /// note and heading, so `[[Note#Heading]]` becomes `\hyperref[...]` to a note compiled into
+    /// the same document. Callouts become `callout` environments taking the type and title,
+    /// and math is kept verbatim.
+    pub fn to_latex(&self) -> String {
+        let stem = self
+            .file_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let writer = Writer::new(&stem, &self.file_body);
+        format!(
+            "\\label{{{}}}\n\n{}",
+            label(&stem, None),
+            writer.text(&self.file_body)
+        )
+    }
+}
+
+/// A compilable `article` with the notes one after another
+pub fn latex_document(notes: &[ObsidianNote]) -> String {
+    let mut latex = format!("\\documentclass{{article}}\n{LATEX_PREAMBLE}\\begin{{document}}\n\n");
+    for note in notes {
+        latex.push_str(&note.to_latex());
+        latex.push('\n');
+    }
+    latex.push_str("\\end{document}\n");
+    latex
+}
+
+struct Writer<'a> {
+    stem: &'a str,
+    /// Rendered footnote definitions by label, placed at their references
+    footnotes: HashMap<String, String>,
+}
+
+impl<'a> Writer<'a> {
+    fn new(stem: &'a str, text: &str) -> Self {
+        let mut writer = Self {
+            stem,
+            footnotes: HashMap::new(),
+        };
+        let mut footnotes = HashMap::new();
+        for node in parse_ast(text) {
+            if let NodeKind::FootnoteDefinition { label } = &node.kind {
+                let mut content = String::new();
+                writer.blocks(text, &node.children, &mut content);
+                footnotes.insert(label.clone(), content.trim().to_string());
+            }
+        }
+        writer.footnotes = footnotes;
+        writer
+    }
+
+    fn text(&self, text: &str) -> String {
+        let mut latex = String::new();
+        let mut cursor = 0;
+
+        for callout in parse_callouts(text) {
+            let before = &text[cursor..callout.span.start];
+            self.blocks(before, &parse_ast(before), &mut latex);
+            cursor = callout.span.end;
+
+            let title = callout.title.unwrap_or_else(|| callout.kind.clone());
+            latex.push_str(&format!(
+                "\\begin{{callout}}{{{}}}{{{}}}\n",
+                escape(&callout.kind),
+                escape(&title)
+            ));
+            latex.push_str(self.text(&callout.body).trim_end());
+            latex.push_str("\n\\end{callout}\n\n");
+        }
+        let rest = &text[cursor..];
+        self.blocks(rest, &parse_ast(rest), &mut latex);
+
+        latex
+    }
+
+    fn blocks(&self, text: &str, nodes: &[Node], latex: &mut String) {
+        for node in nodes {
+            match &node.kind {
+                NodeKind::Heading { level } => {
+                    let command = match level {
+                        1 => "section",
+                        2 => "subsection",
+                        3 => "subsubsection",
+                        4 => "paragraph",
+                        _ => "subparagraph",
+                    };
+                    let heading = node.text();
+                    latex.push_str(&format!(
+                        "\\{command}{{{}}}\\label{{{}}}\n\n",
+                        self.inline(text, &node.children),
+                        label(self.stem, Some(&heading))
+                    ));
+                }
+                NodeKind::Paragraph => {
+                    latex.push_str(&self.inline(text, &node.children));
+                    latex.push_str("\n\n");
+                }
+                NodeKind::BlockQuote => {
+                    latex.push_str("\\begin{quote}\n");
+                    let mut inner = String::new();
+                    self.blocks(text, &node.children, &mut inner);
+                    latex.push_str(inner.trim_end());
+                    latex.push_str("\n\\end{quote}\n\n");
+                }
+                NodeKind::CodeBlock { .. } => {
+                    let code = node.text();
+                    latex.push_str("\\begin{verbatim}\n");
+                    latex.push_str(&code);
+                    if !code.ends_with('\n') {
+                        latex.push('\n');
+                    }
+                    latex.push_str("\\end{verbatim}\n\n");
+                }
+                NodeKind::List { start } => {
+                    let environment = if start.is_some() {
+                        "enumerate"
+                    } else {
+                        "itemize"
+                    };
+                    latex.push_str(&format!("\\begin{{{environment}}}\n"));
+                    for item in &node.children {
+                        let marker = match item.kind {
+                            NodeKind::ListItem {
+                                checked: Some(true),
+                            } => "\\item[$\\boxtimes$] ",
+                            NodeKind::ListItem {
+                                checked: Some(false),
+                            } => "\\item[$\\square$] ",
+                            _ => "\\item ",
+                        };
+                        let mut content = String::new();
+                        let split = item
+                            .children
+                            .iter()
+                            .position(|child| is_block(&child.kind))
+                            .unwrap_or(item.children.len());
+                        let (leading, rest) = item.children.split_at(split);
+                        content.push_str(&self.inline(text, leading));
+                        content.push('\n');
+                        self.blocks(text, rest, &mut content);
+                        latex.push_str(marker);
+                        latex.push_str(content.trim());
+                        latex.push('\n');
+                    }
+                    latex.push_str(&format!("\\end{{{environment}}}\n\n"));
+                }
+                NodeKind::Table => {
+                    let columns = node.children.first().map_or(0, |head| head.children.len());
+                    latex.push_str(&format!("\\begin{{tabular}}{{{}}}\n", "l".repeat(columns)));
+                    for (i, row) in node.children.iter().enumerate() {
+                        let cells: Vec<String> = row
+                            .children
+                            .iter()
+                            .map(|cell| self.inline(text, &cell.children))
+                            .collect();
+                        latex.push_str(&format!("{} \\\\\n", cells.join(" & ")));
+                        if i == 0 {
+                            latex.push_str("\\hline\n");
+                        }
+                    }
+                    latex.push_str("\\end{tabular}\n\n");
+                }
+                // Placed at their references
+                NodeKind::FootnoteDefinition { .. } => {}
+                NodeKind::HtmlBlock => {}
+                NodeKind::Rule => latex.push_str("\\noindent\\rule{\\linewidth}{0.4pt}\n\n"),
+                _ => {
+                    latex.push_str(&self.inline(text, std::slice::from_ref(node)));
+                    latex.push_str("\n\n");
+                }
+            }
+        }
+    }
+
+    fn inline(&self, text: &str, nodes: &[Node]) -> String {
+        let mut latex = String::new();
+        for node in nodes {
+            let children = || self.inline(text, &node.children);
+            match &node.kind {
+                NodeKind::Text(s) => latex.push_str(&escape(s)),
+                NodeKind::Code(code) => latex.push_str(&format!("\\texttt{{{}}}", escape(code))),
+                NodeKind::Emphasis => latex.push_str(&format!("\\emph{{{}}}", children())),
+                NodeKind::Strong => latex.push_str(&format!("\\textbf{{{}}}", children())),
+                NodeKind::Strikethrough => latex.push_str(&format!("\\sout{{{}}}", children())),
+                NodeKind::InlineMath(math) => latex.push_str(&format!("${math}$")),
+                NodeKind::DisplayMath(math) => latex.push_str(&format!("\\[{math}\\]")),
+                NodeKind::FootnoteReference(label) => {
+                    let footnote = self.footnotes.get(label).map_or("", String::as_str);
+                    latex.push_str(&format!("\\footnote{{{footnote}}}"));
+                }
+                NodeKind::SoftBreak => latex.push('\n'),
+                NodeKind::HardBreak => latex.push_str("\\\\\n"),
+                NodeKind::Link { wikilink: true, .. } => {
+                    let link = wikilink_at(text, node.span.clone());
+                    let target = if link.target.is_empty() {
+                        self.stem
+                    } else {
+                        link.target.rsplit('/').next().unwrap_or(&link.target)
+                    };
+                    let target = target.trim_end_matches(".md");
+                    latex.push_str(&format!(
+                        "\\hyperref[{}]{{{}}}",
+                        label(target, link.heading.as_deref()),
+                        children()
+                    ));
+                }
+                NodeKind::Image { wikilink: true, .. } => {
+                    match Embed::from_link(wikilink_at(text, node.span.clone())) {
+                        Embed::Image(link) => {
+                            latex.push_str(&format!("\\includegraphics{{{}}}", link.target))
+                        }
+                        embed => {
+                            let link = embed.link();
+                            let target = link.target.rsplit('/').next().unwrap_or_default();
+                            latex.push_str(&format!(
+                                "\\hyperref[{}]{{{}}}",
+                                label(target.trim_end_matches(".md"), link.heading.as_deref()),
+                                escape(&link.target)
+                            ));
+                        }
+                    }
+                }
+                NodeKind::Link { destination, .. } => latex.push_str(&format!(
+                    "\\href{{{}}}{{{}}}",
+                    destination.replace('%', "\\%").replace('#', "\\#"),
+                    children()
+                )),
+                NodeKind::Image { destination, .. } => {
+                    latex.push_str(&format!("\\includegraphics{{{destination}}}"))
+                }
+                NodeKind::Html(_) => {}
+                _ => latex.push_str(&children()),
+            }
+        }
+        latex
+    }
+}
+
+fn is_block(kind: &NodeKind) -> bool {
+    matches!(
+        kind,
+        NodeKind::Paragraph
+            | NodeKind::Heading { .. }
+            | NodeKind::BlockQuote
+            | NodeKind::CodeBlock { .. }
+            | NodeKind::HtmlBlock
+            | NodeKind::List { .. }
+            | NodeKind::Table
+            | NodeKind::Rule
+    )
+}
+
+/// A `\label` name for a note or one of its headings, using only characters safe in labels
+fn label(note: &str, heading: Option<&str>) -> String {
+    let clean = |s: &str| -> String {
+        s.chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect()
+    };
+    match heading {
+        Some(heading) => format!("{}:{}", clean(note), clean(heading)),
+        None => clean(note),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::path::Path;
+
+    fn note(body: &str) -> ObsidianNote {
+        ObsidianNote::parse(Path::new("Paper.md"), body.to_string()).unwrap()
+    }
+
+    #[test]
+    fn to_latex_converts_markup_and_links() {
+        let latex = note(indoc! {r"
+            # Results
+
+            Costs rose 50% with *care*[^1], see [[Method#Setup|the setup]] and $e^x$.
+
+            $$
+            \int_0^1 x\,dx
+            $$
+
+            [^1]: Mostly **labour**.
+        "})
+        .to_latex();
+
+        assert_eq!(
+            latex,
+            indoc! {r"
+                \label{Paper}
+
+                \section{Results}\label{Paper:Results}
+
+                Costs rose 50\% with \emph{care}\footnote{Mostly \textbf{labour}.}, see \hyperref[Method:Setup]{the setup} and $e^x$.
+
+                \[
+                \int_0^1 x\,dx
+                \]
+
+            "}
+        );
+    }
+
+    #[test]
+    fn to_latex_maps_callouts_to_environments() {
+        let latex = note(indoc! {"
+            > [!warning] Careful
+            > - [x] checked
+        "})
+        .to_latex();
+
+        assert_eq!(
+            latex,
+            indoc! {r"
+                \label{Paper}
+
+                \begin{callout}{warning}{Careful}
+                \begin{itemize}
+                \item[$\boxtimes$] checked
+                \end{itemize}
+                \end{callout}
+
+            "}
+        );
+    }
+}
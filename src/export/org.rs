@@ -0,0 +1,330 @@
+use crate::{
+    ast::{parse_ast, Node, NodeKind},
+    frontmatter_tags,
+    render::wikilink_at,
+    ObsidianNote, Properties,
+};
+
+impl ObsidianNote {
+    /// The note as an Emacs org-mode document
+    ///
+    /// Properties become `#+PROPERTY:` lines, with `tags` as `#+FILETAGS:`, and wikilinks become
+    /// `file:` links to the matching `.org` file.
+    pub fn to_org(&self) -> String {
+        let mut org = String::new();
+        let title = self.title();
+        if !title.is_empty() {
+            org.push_str(&format!("#+TITLE: {title}\n"));
+        }
+
+        if let Some(properties @ Properties::Mapping(mapping)) = &self.properties {
+            let tags: Vec<String> = frontmatter_tags(properties)
+                .into_iter()
+                .map(|tag| tag.name.replace(['/', '-'], "_"))
+                .collect();
+            if !tags.is_empty() {
+                org.push_str(&format!("#+FILETAGS: :{}:\n", tags.join(":")));
+            }
+
+            for (key, value) in mapping {
+                let Some(key) = key.as_str().filter(|key| !matches!(*key, "tags" | "tag")) else {
+                    continue;
+                };
+                if let Some(value) = property_value(value) {
+                    org.push_str(&format!("#+PROPERTY: {key} {value}\n"));
+                }
+            }
+        }
+
+        if !org.is_empty() {
+            org.push('\n');
+        }
+        org.push_str(&markdown_to_org(&self.file_body));
+        org
+    }
+}
+
+/// Converts a markdown body to org-mode markup
+pub fn markdown_to_org(text: &str) -> String {
+    let mut org = String::new();
+    blocks(text, &parse_ast(text), &mut org);
+    let org = org.trim_end();
+    if org.is_empty() {
+        String::new()
+    } else {
+        format!("{org}\n")
+    }
+}
+
+fn property_value(value: &Properties) -> Option<String> {
+    match value {
+        Properties::Null => None,
+        Properties::Bool(b) => Some(b.to_string()),
+        Properties::Number(n) => Some(n.to_string()),
+        Properties::String(s) => Some(s.replace('\n', " ")),
+        Properties::Sequence(values) => {
+            let values: Vec<String> = values.iter().filter_map(property_value).collect();
+            Some(values.join(" "))
+        }
+        Properties::Mapping(_) | Properties::Tagged(_) => serde_yaml::to_string(value)
+            .ok()
+            .map(|yaml| yaml.trim().replace('\n', " ")),
+    }
+}
+
+fn blocks(text: &str, nodes: &[Node], org: &mut String) {
+    for node in nodes {
+        match &node.kind {
+            NodeKind::Heading { level } => {
+                org.push_str(&format!(
+                    "{} {}\n\n",
+                    "*".repeat(*level as usize),
+                    inline(text, &node.children)
+                ));
+            }
+            NodeKind::Paragraph => {
+                org.push_str(&inline(text, &node.children));
+                org.push_str("\n\n");
+            }
+            NodeKind::BlockQuote => {
+                org.push_str("#+BEGIN_QUOTE\n");
+                let mut inner = String::new();
+                blocks(text, &node.children, &mut inner);
+                org.push_str(inner.trim_end());
+                org.push_str("\n#+END_QUOTE\n\n");
+            }
+            NodeKind::CodeBlock { info } => {
+                let code = node.text();
+                let language = info
+                    .as_deref()
+                    .and_then(|info| info.split_whitespace().next());
+                match language {
+                    Some(language) => org.push_str(&format!("#+BEGIN_SRC {language}\n")),
+                    None => org.push_str("#+BEGIN_EXAMPLE\n"),
+                }
+                org.push_str(&code);
+                if !code.ends_with('\n') {
+                    org.push('\n');
+                }
+                org.push_str(match language {
+                    Some(_) => "#+END_SRC\n\n",
+                    None => "#+END_EXAMPLE\n\n",
+                });
+            }
+            NodeKind::HtmlBlock => {
+                org.push_str("#+BEGIN_EXPORT html\n");
+                org.push_str(text[node.span.clone()].trim_end());
+                org.push_str("\n#+END_EXPORT\n\n");
+            }
+            NodeKind::List { start } => {
+                for (i, item) in node.children.iter().enumerate() {
+                    let marker = match start {
+                        Some(start) => format!("{}. ", start + i as u64),
+                        None => "- ".to_string(),
+                    };
+                    org.push_str(&list_item(text, item, &marker));
+                }
+                org.push('\n');
+            }
+            NodeKind::Table => {
+                for (i, row) in node.children.iter().enumerate() {
+                    let cells: Vec<String> = row
+                        .children
+                        .iter()
+                        .map(|cell| inline(text, &cell.children))
+                        .collect();
+                    org.push_str(&format!("| {} |\n", cells.join(" | ")));
+                    if i == 0 {
+                        let rule = vec!["---"; cells.len()].join("+");
+                        org.push_str(&format!("|{rule}|\n"));
+                    }
+                }
+                org.push('\n');
+            }
+            NodeKind::FootnoteDefinition { label } => {
+                let mut inner = String::new();
+                blocks(text, &node.children, &mut inner);
+                org.push_str(&format!("[fn:{label}] {}\n\n", inner.trim()));
+            }
+            NodeKind::Rule => org.push_str("-----\n\n"),
+            _ => {
+                org.push_str(&inline(text, std::slice::from_ref(node)));
+                org.push_str("\n\n");
+            }
+        }
+    }
+}
+
+/// A list item with its continuation lines indented under the marker
+fn list_item(text: &str, item: &Node, marker: &str) -> String {
+    let checkbox = match item.kind {
+        NodeKind::ListItem {
+            checked: Some(true),
+        } => "[X] ",
+        NodeKind::ListItem {
+            checked: Some(false),
+        } => "[ ] ",
+        _ => "",
+    };
+
+    // Tight items hold their inline content directly, loose ones in paragraphs
+    let mut content = String::new();
+    let split = item
+        .children
+        .iter()
+        .position(|child| is_block(&child.kind))
+        .unwrap_or(item.children.len());
+    let (leading, rest) = item.children.split_at(split);
+    if !leading.is_empty() {
+        content.push_str(&inline(text, leading));
+        content.push('\n');
+    }
+    blocks(text, rest, &mut content);
+
+    let indent = " ".repeat(marker.len());
+    let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+    let mut org = format!("{marker}{checkbox}{}\n", lines.next().unwrap_or_default());
+    for line in lines {
+        org.push_str(&format!("{indent}{line}\n"));
+    }
+    org
+}
+
+fn is_block(kind: &NodeKind) -> bool {
+    matches!(
+        kind,
+        NodeKind::Paragraph
+            | NodeKind::Heading { .. }
+            | NodeKind::BlockQuote
+            | NodeKind::CodeBlock { .. }
+            | NodeKind::HtmlBlock
+            | NodeKind::List { .. }
+            | NodeKind::Table
+            | NodeKind::FootnoteDefinition { .. }
+            | NodeKind::Rule
+    )
+}
+
+fn inline(text: &str, nodes: &[Node]) -> String {
+    let mut org = String::new();
+    for node in nodes {
+        let children = || inline(text, &node.children);
+        match &node.kind {
+            NodeKind::Text(s) => org.push_str(s),
+            NodeKind::Code(code) => org.push_str(&format!("~{code}~")),
+            NodeKind::Emphasis => org.push_str(&format!("/{}/", children())),
+            NodeKind::Strong => org.push_str(&format!("*{}*", children())),
+            NodeKind::Strikethrough => org.push_str(&format!("+{}+", children())),
+            NodeKind::InlineMath(math) => org.push_str(&format!("\\({math}\\)")),
+            NodeKind::DisplayMath(math) => org.push_str(&format!("\\[{math}\\]")),
+            NodeKind::Html(html) => org.push_str(&format!("@@html:{html}@@")),
+            NodeKind::FootnoteReference(label) => org.push_str(&format!("[fn:{label}]")),
+            NodeKind::SoftBreak | NodeKind::HardBreak => org.push('\n'),
+            NodeKind::Link { wikilink: true, .. } | NodeKind::Image { wikilink: true, .. } => {
+                let link = wikilink_at(text, node.span.clone());
+                let is_note = !link.target.contains('.') || link.target.ends_with(".md");
+                let mut target = if is_note {
+                    format!("file:{}.org", link.target.trim_end_matches(".md"))
+                } else {
+                    format!("file:{}", link.target)
+                };
+                if let Some(heading) = &link.heading {
+                    target.push_str(&format!("::*{heading}"));
+                }
+                match &link.alias {
+                    Some(alias) if matches!(node.kind, NodeKind::Link { .. }) => {
+                        org.push_str(&format!("[[{target}][{alias}]]"))
+                    }
+                    _ => org.push_str(&format!("[[{target}]]")),
+                }
+            }
+            NodeKind::Link { destination, .. } => {
+                let label = children();
+                if label.is_empty() || label == *destination {
+                    org.push_str(&format!("[[{destination}]]"));
+                } else {
+                    org.push_str(&format!("[[{destination}][{label}]]"));
+                }
+            }
+            NodeKind::Image { destination, .. } => org.push_str(&format!("[[{destination}]]")),
+            _ => org.push_str(&children()),
+        }
+    }
+    org
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::path::Path;
+
+    #[test]
+    fn to_org_maps_frontmatter_to_properties() {
+        let note = ObsidianNote::parse(
+            Path::new("Reading List.md"),
+            indoc! {"
+                ---
+                tags: [books, to-read]
+                status: active
+                authors: [Le Guin, Butler]
+                ---
+                Body
+            "}
+            .to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            note.to_org(),
+            indoc! {"
+                #+TITLE: Reading List
+                #+FILETAGS: :books:to_read:
+                #+PROPERTY: status active
+                #+PROPERTY: authors Le Guin Butler
+
+                Body
+            "}
+        );
+    }
+
+    #[test]
+    fn markdown_to_org_converts_markup_and_links() {
+        let org = markdown_to_org(indoc! {"
+            # Plan
+
+            Some *emphasis*, **bold**, `code` and [[Other Note#Part|a link]].
+
+            - [ ] open task
+            - [x] done task
+              with more
+
+            ```rust
+            fn main() {}
+            ```
+
+            > Quoted [site](https://example.com)
+        "});
+
+        assert_eq!(
+            org,
+            indoc! {"
+                * Plan
+
+                Some /emphasis/, *bold*, ~code~ and [[file:Other Note.org::*Part][a link]].
+
+                - [ ] open task
+                - [X] done task
+                  with more
+
+                #+BEGIN_SRC rust
+                fn main() {}
+                #+END_SRC
+
+                #+BEGIN_QUOTE
+                Quoted [[https://example.com][site]]
+                #+END_QUOTE
+            "}
+        );
+    }
+}
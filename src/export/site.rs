@@ -0,0 +1,302 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use percent_encoding::utf8_percent_encode;
+
+use crate::{
+    graph::escape_xml,
+    headings::heading_anchor,
+    rename::{path_to_link, DESTINATION},
+    resolver::relative_to,
+    vault::is_note,
+    LinkResolver, ObsidianNote, Renderer, Vault,
+};
+
+/// How note paths become page paths
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SlugStrategy {
+    /// Keep folder and file names, e.g. `Projects/My Note.html`
+    #[default]
+    Preserve,
+    /// Lowercase and hyphenate every component, e.g. `projects/my-note.html`
+    Kebab,
+}
+
+type NoteFilter<'a> = Box<dyn Fn(&ObsidianNote) -> bool + 'a>;
+
+#[derive(Default)]
+pub struct SiteOptions<'a> {
+    pub slug: SlugStrategy,
+    filter: Option<NoteFilter<'a>>,
+}
+
+impl<'a> SiteOptions<'a> {
+    pub fn slug(mut self, slug: SlugStrategy) -> Self {
+        self.slug = slug;
+        self
+    }
+
+    /// Only exports notes for which `filter` returns true. Links to other notes are rendered as
+    /// unresolved.
+    pub fn filter(mut self, filter: impl Fn(&ObsidianNote) -> bool + 'a) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+}
+
+/// What an export wrote, relative to the output folder
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SiteExport {
+    pub pages: Vec<PathBuf>,
+    pub attachments: Vec<PathBuf>,
+}
+
+/// Renders the vault's notes to an HTML tree under `out_dir`, with links between pages, copies
+/// of embedded and linked attachments, and an `index.html` listing every page
+pub fn export_vault(
+    vault: &Vault,
+    out_dir: &Path,
+    options: &SiteOptions,
+) -> crate::Result<SiteExport> {
+    let notes: Vec<ObsidianNote> = vault
+        .notes()
+        .filter(|note| match (note, &options.filter) {
+            (Ok(note), Some(filter)) => filter(note),
+            _ => true,
+        })
+        .collect::<crate::Result<_>>()?;
+    let resolver = LinkResolver::from_vault_notes(vault, &notes)?;
+
+    let mut taken = BTreeSet::new();
+    let pages: BTreeMap<&Path, PathBuf> = notes
+        .iter()
+        .map(|note| {
+            let page = page_path(vault.relative_path(&note.file_path), options.slug);
+            (note.file_path.as_path(), unique_page(page, &mut taken))
+        })
+        .collect();
+    let attachments = RefCell::new(BTreeSet::new());
+
+    for note in &notes {
+        let page = &pages[note.file_path.as_path()];
+        let page_dir = page.parent().unwrap_or(Path::new(""));
+
+        let renderer = Renderer::new().link_href(|link| {
+            let target = resolver.resolve_link(link, &note.file_path)?;
+            let output = match pages.get(target) {
+                Some(target_page) => target_page.clone(),
+                None if is_note(target) => return None,
+                None => {
+                    let attachment = vault.relative_path(target).to_path_buf();
+                    attachments.borrow_mut().insert(attachment.clone());
+                    attachment
+                }
+            };
+
+            let mut href = href(&relative_to(&output, page_dir));
+            if let Some(heading) = &link.heading {
+                href.push('#');
+                href.push_str(&heading_anchor(heading));
+            }
+            Some(href)
+        });
+
+        let title = note.title();
+        let index = href(&relative_to(Path::new("index.html"), page_dir));
+        let html = page_html(
+            &title,
+            &format!(
+                "<nav><a href=\"{index}\">Index</a></nav>\n<main>\n{}</main>",
+                note.render_html(&renderer)
+            ),
+        );
+        write(&out_dir.join(page), &html)?;
+    }
+
+    let attachments = attachments.into_inner();
+    for attachment in &attachments {
+        let destination = out_dir.join(attachment);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(vault.path.join(attachment), destination)?;
+    }
+
+    let mut index = String::from("<main>\n<ul>\n");
+    for note in &notes {
+        let page = &pages[note.file_path.as_path()];
+        index.push_str(&format!(
+            "<li><a href=\"{}\">{}</a></li>\n",
+            href(page),
+            escape_xml(&note.title())
+        ));
+    }
+    index.push_str("</ul>\n</main>");
+    write(&out_dir.join("index.html"), &page_html("Index", &index))?;
+
+    Ok(SiteExport {
+        pages: pages.into_values().collect(),
+        attachments: attachments.into_iter().collect(),
+    })
+}
+
+fn page_path(note: &Path, slug: SlugStrategy) -> PathBuf {
+    let page = note.with_extension("html");
+    match slug {
+        SlugStrategy::Preserve => page,
+        SlugStrategy::Kebab => {
+            let mut slugged: PathBuf = page
+                .with_extension("")
+                .components()
+                .map(|component| kebab(&component.as_os_str().to_string_lossy()))
+                .collect();
+            slugged.set_extension("html");
+            slugged
+        }
+    }
+}
+
+/// Numbers `page` as `name-2.html`, `name-3.html`, … if an earlier note already took it, as
+/// slugging can give different notes the same page
+fn unique_page(page: PathBuf, taken: &mut BTreeSet<String>) -> PathBuf {
+    let stem = page.file_stem().unwrap_or_default().to_string_lossy();
+    let unique = (1..)
+        .map(|n| match n {
+            1 => page.clone(),
+            n => page.with_file_name(format!("{stem}-{n}.html")),
+        })
+        .find(|page| !taken.contains(&path_to_link(page).to_lowercase()))
+        .unwrap_or(page.clone());
+    taken.insert(path_to_link(&unique).to_lowercase());
+    unique
+}
+
+fn kebab(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn href(path: &Path) -> String {
+    utf8_percent_encode(&path_to_link(path), DESTINATION).to_string()
+}
+
+fn page_html(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{body}\n</body>\n</html>\n",
+        escape_xml(title)
+    )
+}
+
+fn write(path: &Path, contents: &str) -> crate::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::vault_with_files;
+
+    fn vault() -> (tempfile::TempDir, Vault) {
+        vault_with_files(&[
+            (
+                "Home.md",
+                "Go to [[Projects/Big Plan#Goals|the plan]] ![[diagram.png]]",
+            ),
+            (
+                "Projects/Big Plan.md",
+                "---\ndraft: false\n---\n## Goals\nBack [[Home]]",
+            ),
+            ("Private.md", "---\ndraft: true\n---\nSecret"),
+            ("diagram.png", "png"),
+            ("unused.pdf", "pdf"),
+        ])
+    }
+
+    #[test]
+    fn export_vault_writes_linked_pages_and_attachments() {
+        let (_dir, vault) = vault();
+        let out = tempfile::tempdir().unwrap();
+
+        let export = export_vault(&vault, out.path(), &SiteOptions::default()).unwrap();
+        assert_eq!(
+            export.pages,
+            vec![
+                PathBuf::from("Home.html"),
+                PathBuf::from("Private.html"),
+                PathBuf::from("Projects/Big Plan.html"),
+            ]
+        );
+        assert_eq!(export.attachments, vec![PathBuf::from("diagram.png")]);
+        assert!(out.path().join("diagram.png").is_file());
+
+        let home = fs::read_to_string(out.path().join("Home.html")).unwrap();
+        assert!(home.contains(
+            "<a href=\"Projects/Big%20Plan.html#goals\" class=\"internal-link\">the plan</a>"
+        ));
+        assert!(home.contains("<img src=\"diagram.png\""));
+
+        let plan = fs::read_to_string(out.path().join("Projects/Big Plan.html")).unwrap();
+        assert!(plan.contains("<h2 id=\"goals\">Goals</h2>"));
+        assert!(plan.contains("<a href=\"../Home.html\" class=\"internal-link\">Home</a>"));
+        assert!(plan.contains("<a href=\"../index.html\">Index</a>"));
+    }
+
+    #[test]
+    fn export_vault_filters_notes_and_slugs_paths() {
+        let (_dir, vault) = vault();
+        let out = tempfile::tempdir().unwrap();
+        let options = SiteOptions::default()
+            .slug(SlugStrategy::Kebab)
+            .filter(|note| {
+                note.properties
+                    .as_ref()
+                    .is_some_and(|p| p["draft"] == false)
+            });
+
+        let export = export_vault(&vault, out.path(), &options).unwrap();
+        assert_eq!(export.pages, vec![PathBuf::from("projects/big-plan.html")]);
+
+        let plan = fs::read_to_string(out.path().join("projects/big-plan.html")).unwrap();
+        assert!(plan.contains("<a class=\"internal-link is-unresolved\">Home</a>"));
+
+        let index = fs::read_to_string(out.path().join("index.html")).unwrap();
+        assert!(index.contains("<li><a href=\"projects/big-plan.html\">Big Plan</a></li>"));
+        assert!(!index.contains("Private"));
+    }
+
+    #[test]
+    fn export_vault_numbers_colliding_slugs() {
+        let (_dir, vault) = vault_with_files(&[
+            ("My Note.md", "First, see [[my-note]]"),
+            ("my-note.md", "Second, see [[My Note]]"),
+        ]);
+        let out = tempfile::tempdir().unwrap();
+
+        let options = SiteOptions::default().slug(SlugStrategy::Kebab);
+        let export = export_vault(&vault, out.path(), &options).unwrap();
+        assert_eq!(
+            export.pages,
+            vec![
+                PathBuf::from("my-note.html"),
+                PathBuf::from("my-note-2.html")
+            ]
+        );
+
+        let first = fs::read_to_string(out.path().join("my-note.html")).unwrap();
+        assert!(first.contains("First"));
+        assert!(first.contains("<a href=\"my-note-2.html\" class=\"internal-link\">my-note</a>"));
+        let second = fs::read_to_string(out.path().join("my-note-2.html")).unwrap();
+        assert!(second.contains("<a href=\"my-note.html\" class=\"internal-link\">My Note</a>"));
+    }
+}
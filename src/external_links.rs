@@ -0,0 +1,166 @@
+use std::ops::Range;
+
+use crate::{
+    code::{code_ranges, in_ranges},
+    links::{parse_markdown_links, scan_wikilinks, url_scheme},
+    ObsidianNote,
+};
+
+/// A link out of the vault: a markdown link with a URL scheme, a `<https://...>` autolink or a
+/// bare `http(s)://` URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalLink {
+    pub url: String,
+    /// The markdown link's text, `None` for autolinks and bare URLs
+    pub text: Option<String>,
+    pub is_embed: bool,
+    /// Byte range of the whole link within the parsed text
+    pub span: Range<usize>,
+}
+
+impl ObsidianNote {
+    pub fn external_links(&self) -> Vec<ExternalLink> {
+        parse_external_links(&self.file_body)
+    }
+}
+
+/// Finds external links outside code, in order
+pub fn parse_external_links(text: &str) -> Vec<ExternalLink> {
+    let code = code_ranges(text);
+    let markdown_links = parse_markdown_links(text);
+    let mut skip: Vec<Range<usize>> = markdown_links.iter().map(|l| l.span.clone()).collect();
+    skip.extend(code.iter().cloned());
+    skip.extend(scan_wikilinks(text).into_iter().map(|(_, link)| link.span));
+
+    let mut links: Vec<ExternalLink> = markdown_links
+        .into_iter()
+        .filter(|link| link.is_external())
+        .map(|link| ExternalLink {
+            url: link.destination,
+            text: Some(link.text),
+            is_embed: link.is_embed,
+            span: link.span,
+        })
+        .collect();
+
+    let mut cursor = 0;
+    while cursor < text.len() {
+        let found = if in_ranges(&skip, cursor) {
+            None
+        } else {
+            autolink_at(text, cursor).or_else(|| bare_url_at(text, cursor))
+        };
+        match found {
+            Some(link) => {
+                cursor = link.span.end;
+                links.push(link);
+            }
+            None => cursor += text[cursor..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+
+    links.sort_by_key(|link| link.span.start);
+    links
+}
+
+/// `<scheme:...>`
+fn autolink_at(text: &str, start: usize) -> Option<ExternalLink> {
+    let rest = text[start..].strip_prefix('<')?;
+    let len = rest.find(|c: char| c == '>' || c == '<' || c.is_whitespace())?;
+    let url = &rest[..len];
+    (rest[len..].starts_with('>') && url_scheme(url).is_some()).then(|| ExternalLink {
+        url: url.to_string(),
+        text: None,
+        is_embed: false,
+        span: start..start + len + 2,
+    })
+}
+
+/// `http://` or `https://` up to whitespace, leaving off trailing punctuation and unbalanced
+/// closing parentheses the way GitHub's autolinks do
+fn bare_url_at(text: &str, start: usize) -> Option<ExternalLink> {
+    let rest = &text[start..];
+    let after_word = !text[..start]
+        .chars()
+        .next_back()
+        .is_some_and(char::is_alphanumeric);
+    let is_url = ["http://", "https://"].iter().any(|scheme| {
+        rest.get(..scheme.len())
+            .is_some_and(|s| s.eq_ignore_ascii_case(scheme))
+    });
+    if !after_word || !is_url {
+        return None;
+    }
+
+    let mut url = &rest[..rest
+        .find(|c: char| c.is_whitespace() || c == '<')
+        .unwrap_or(rest.len())];
+    loop {
+        let trimmed =
+            url.trim_end_matches(['.', ',', ':', ';', '!', '?', '*', '_', '~', '"', '\'']);
+        let unbalanced =
+            trimmed.ends_with(')') && trimmed.matches(')').count() > trimmed.matches('(').count();
+        let trimmed = if unbalanced {
+            &trimmed[..trimmed.len() - 1]
+        } else {
+            trimmed
+        };
+        if trimmed.len() == url.len() {
+            break;
+        }
+        url = trimmed;
+    }
+    (!url.ends_with("://")).then(|| ExternalLink {
+        url: url.to_string(),
+        text: None,
+        is_embed: false,
+        span: start..start + url.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn parse_external_links_finds_links_autolinks_and_bare_urls() {
+        let text = indoc! {r"
+            See [the docs](https://example.com/docs) and ![logo](https://example.com/a.png).
+            Visit https://en.wikipedia.org/wiki/Rust_(programming_language), or <mailto:me@example.com>
+            (also http://example.org/page). [local](Note.md) `https://in.code` [[https://not.this]]
+            ```
+            https://in.block
+            ```
+        "};
+
+        let links = parse_external_links(text);
+        let summary: Vec<(&str, Option<&str>, &str)> = links
+            .iter()
+            .map(|l| (l.url.as_str(), l.text.as_deref(), &text[l.span.clone()]))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "https://example.com/docs",
+                    Some("the docs"),
+                    "[the docs](https://example.com/docs)"
+                ),
+                (
+                    "https://example.com/a.png",
+                    Some("logo"),
+                    "![logo](https://example.com/a.png)"
+                ),
+                (
+                    "https://en.wikipedia.org/wiki/Rust_(programming_language)",
+                    None,
+                    "https://en.wikipedia.org/wiki/Rust_(programming_language)"
+                ),
+                ("mailto:me@example.com", None, "<mailto:me@example.com>"),
+                ("http://example.org/page", None, "http://example.org/page"),
+            ]
+        );
+        assert!(links[1].is_embed);
+    }
+}
@@ -0,0 +1,248 @@
+use std::ops::Range;
+
+use crate::{
+    code::{code_ranges, in_ranges},
+    ObsidianNote,
+};
+
+/// A `[^label]` reference to a footnote defined elsewhere in the note
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FootnoteReference {
+    pub label: String,
+    pub span: Range<usize>,
+}
+
+/// A `[^label]: text` definition, with any indented continuation lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FootnoteDefinition {
+    pub label: String,
+    /// The definition's text with continuation lines unindented
+    pub text: String,
+    pub span: Range<usize>,
+}
+
+/// An inline `^[text]` footnote, which needs no definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineFootnote {
+    pub text: String,
+    pub span: Range<usize>,
+}
+
+/// Every footnote in a note, each kind in order
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Footnotes {
+    pub references: Vec<FootnoteReference>,
+    pub definitions: Vec<FootnoteDefinition>,
+    pub inline: Vec<InlineFootnote>,
+}
+
+impl Footnotes {
+    /// The definition a reference points to. Labels match case-insensitively, and the first
+    /// definition of a label wins.
+    pub fn definition(&self, label: &str) -> Option<&FootnoteDefinition> {
+        self.definitions
+            .iter()
+            .find(|definition| same_label(&definition.label, label))
+    }
+
+    /// References without a definition, which Obsidian renders as plain text
+    pub fn undefined_references(&self) -> Vec<&FootnoteReference> {
+        self.references
+            .iter()
+            .filter(|reference| self.definition(&reference.label).is_none())
+            .collect()
+    }
+
+    /// Definitions no reference points to, which Obsidian doesn't show
+    pub fn unused_definitions(&self) -> Vec<&FootnoteDefinition> {
+        self.definitions
+            .iter()
+            .filter(|definition| {
+                !self
+                    .references
+                    .iter()
+                    .any(|reference| same_label(&reference.label, &definition.label))
+            })
+            .collect()
+    }
+}
+
+impl ObsidianNote {
+    pub fn footnotes(&self) -> Footnotes {
+        parse_footnotes(&self.file_body)
+    }
+}
+
+/// Finds footnote references, definitions and inline footnotes outside code
+pub fn parse_footnotes(text: &str) -> Footnotes {
+    let code = code_ranges(text);
+    let mut footnotes = Footnotes::default();
+
+    let mut line_start = 0;
+    let mut skip_until = 0;
+    for line in text.split_inclusive('\n') {
+        if line_start >= skip_until && !in_ranges(&code, line_start) {
+            if let Some(definition) = definition_at(text, line_start) {
+                skip_until = definition.span.end;
+                footnotes.definitions.push(definition);
+            }
+        }
+        line_start += line.len();
+    }
+
+    let mut cursor = 0;
+    while let Some(offset) = text[cursor..].find(['[', '^']) {
+        let start = cursor + offset;
+        cursor = start + 1;
+        if in_ranges(&code, start) {
+            continue;
+        }
+
+        if let Some(rest) = text[start..].strip_prefix("^[") {
+            let Some(len) = closing_bracket(rest) else {
+                continue;
+            };
+            let end = start + 2 + len + 1;
+            footnotes.inline.push(InlineFootnote {
+                text: rest[..len].trim().to_string(),
+                span: start..end,
+            });
+            cursor = end;
+        } else if let Some((label, end)) = label_at(text, start) {
+            let is_definition = footnotes.definitions.iter().any(|definition| {
+                definition.span.start + leading_spaces(text, definition.span.start) == start
+            });
+            if !is_definition {
+                footnotes.references.push(FootnoteReference {
+                    label: label.to_string(),
+                    span: start..end,
+                });
+            }
+            cursor = end;
+        }
+    }
+
+    footnotes
+}
+
+/// A definition starting on the line at `start`, taking in the indented lines that follow it
+fn definition_at(text: &str, start: usize) -> Option<FootnoteDefinition> {
+    let indent = leading_spaces(text, start);
+    if indent > 3 {
+        return None;
+    }
+    let (label, label_end) = label_at(text, start + indent)?;
+    let first = text[label_end..].strip_prefix(':')?;
+    let first_end = first
+        .find('\n')
+        .map_or(text.len(), |len| label_end + 1 + len);
+
+    let mut lines = vec![text[label_end + 1..first_end].trim().to_string()];
+    let mut end = first_end;
+    let mut cursor = first_end;
+    while cursor < text.len() {
+        let line_start = cursor + 1;
+        let line_end = text[line_start..]
+            .find('\n')
+            .map_or(text.len(), |len| line_start + len);
+        let line = &text[line_start..line_end];
+        let continuation = line
+            .strip_prefix('\t')
+            .or_else(|| line.strip_prefix("    "));
+        match continuation {
+            Some(continuation) => {
+                lines.push(continuation.trim_end().to_string());
+                end = line_end;
+            }
+            None if line.trim().is_empty() => lines.push(String::new()),
+            None => break,
+        }
+        cursor = line_end;
+    }
+
+    let text_lines = lines.len() - lines.iter().rev().take_while(|l| l.is_empty()).count();
+    Some(FootnoteDefinition {
+        label: label.to_string(),
+        text: lines[..text_lines].join("\n"),
+        span: start..end,
+    })
+}
+
+/// `[^label]` at `start`, returning the label and the end of the closing bracket
+fn label_at(text: &str, start: usize) -> Option<(&str, usize)> {
+    let rest = text[start..].strip_prefix("[^")?;
+    let len = rest.find(|c: char| c == ']' || c.is_whitespace() || c == '[')?;
+    (len > 0 && rest[len..].starts_with(']')).then(|| (&rest[..len], start + 2 + len + 1))
+}
+
+/// The length of `text` up to the `]` that closes an already opened bracket, on the same line
+fn closing_bracket(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' if depth == 0 => return Some(i),
+            ']' => depth -= 1,
+            '\n' => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn leading_spaces(text: &str, start: usize) -> usize {
+    text[start..].len() - text[start..].trim_start_matches(' ').len()
+}
+
+fn same_label(a: &str, b: &str) -> bool {
+    a.to_lowercase() == b.to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn parse_footnotes_finds_all_three_kinds() {
+        let text = indoc! {r"
+            A claim[^1] and another[^Note], plus an aside^[with a [[link]]].
+            `[^code]` isn't one, and [^missing] has no definition.
+
+            [^1]: The source.
+            [^note]: A longer note
+                over two lines.
+
+                With a second paragraph.
+            [^unused]: Never referenced.
+        "};
+
+        let footnotes = parse_footnotes(text);
+        let labels: Vec<&str> = footnotes
+            .references
+            .iter()
+            .map(|r| r.label.as_str())
+            .collect();
+        assert_eq!(labels, vec!["1", "Note", "missing"]);
+        assert_eq!(&text[footnotes.references[0].span.clone()], "[^1]");
+
+        assert_eq!(
+            footnotes.definition("NOTE").unwrap().text,
+            "A longer note\nover two lines.\n\nWith a second paragraph."
+        );
+        assert_eq!(footnotes.definitions[0].text, "The source.");
+        assert_eq!(
+            &text[footnotes.definitions[0].span.clone()],
+            "[^1]: The source."
+        );
+        assert_eq!(footnotes.inline[0].text, "with a [[link]]");
+
+        let undefined: Vec<&str> = footnotes
+            .undefined_references()
+            .iter()
+            .map(|r| r.label.as_str())
+            .collect();
+        assert_eq!(undefined, vec!["missing"]);
+        assert_eq!(footnotes.unused_definitions()[0].label, "unused");
+    }
+}
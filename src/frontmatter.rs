@@ -0,0 +1,481 @@
+use std::ops::Range;
+
+use crate::{obsidian_note::parse_properties, Error, FrontmatterFormat, ObsidianNote, Properties};
+
+impl ObsidianNote {
+    /// Sets a top-level property, editing raw YAML frontmatter in place so the other keys keep
+    /// their order, quoting and comments
+    pub fn set_property(&mut self, key: &str, value: impl Into<Properties>) -> crate::Result<()> {
+        let value = value.into();
+        let raw = match self.frontmatter_format {
+            FrontmatterFormat::Yaml => {
+                let raw = self.frontmatter.as_deref().unwrap_or_default();
+                Some(set_raw_property(raw, key, &value)?)
+            }
+            _ => None,
+        };
+
+        let properties = self
+            .properties
+            .get_or_insert_with(|| Properties::Mapping(serde_yaml::Mapping::new()));
+        let Some(mapping) = properties.as_mapping_mut() else {
+            return Err(Error::InvalidFrontmatter {
+                path: self.file_path.clone(),
+                message: "frontmatter is not a mapping".to_string(),
+            });
+        };
+        mapping.insert(Properties::from(key), value);
+        if raw.is_some() {
+            self.frontmatter = raw;
+        }
+
+        Ok(())
+    }
+
+    /// Applies `update` to an existing property, returning whether the property was present
+    pub fn update_property(
+        &mut self,
+        key: &str,
+        update: impl FnOnce(&Properties) -> Properties,
+    ) -> crate::Result<bool> {
+        let Some(current) = self.properties.as_ref().and_then(|p| p.get(key)) else {
+            return Ok(false);
+        };
+
+        let value = update(current);
+        self.set_property(key, value)?;
+        Ok(true)
+    }
+
+    pub fn remove_property(&mut self, key: &str) -> Option<Properties> {
+        let removed = self
+            .properties
+            .as_mut()
+            .and_then(|p| p.as_mapping_mut())
+            .and_then(|mapping| mapping.shift_remove(key))?;
+
+        if let Some(raw) = self.raw_yaml() {
+            self.frontmatter = Some(remove_raw_property(raw, key));
+        }
+
+        Some(removed)
+    }
+
+    /// Renames a property in place, returning whether it was present. Fails if a property
+    /// named `to` already exists.
+    pub fn rename_property(&mut self, from: &str, to: &str) -> crate::Result<bool> {
+        let Some(mapping) = self.properties.as_mut().and_then(|p| p.as_mapping_mut()) else {
+            return Ok(false);
+        };
+        if !mapping.contains_key(from) {
+            return Ok(false);
+        }
+        if from == to {
+            return Ok(true);
+        }
+        if mapping.contains_key(to) {
+            return Err(Error::InvalidFrontmatter {
+                path: self.file_path.clone(),
+                message: format!("property `{to}` already exists"),
+            });
+        }
+
+        *mapping = std::mem::take(mapping)
+            .into_iter()
+            .map(|(key, value)| match key.as_str() {
+                Some(key) if key == from => (Properties::from(to), value),
+                _ => (key, value),
+            })
+            .collect();
+        if let Some(raw) = self.raw_yaml() {
+            self.frontmatter = Some(rename_raw_property(raw, from, to)?);
+        }
+
+        Ok(true)
+    }
+
+    /// The raw frontmatter, if it's YAML that can be edited line by line. Other formats are
+    /// re-emitted from `properties` when written.
+    fn raw_yaml(&self) -> Option<&str> {
+        match self.frontmatter_format {
+            FrontmatterFormat::Yaml => self.frontmatter.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// A top-level `key: value` entry in raw YAML, including any continuation lines
+struct Entry {
+    /// The entry's lines, including the trailing newline
+    lines: Range<usize>,
+    /// The inline value on the key's line, excluding any trailing comment
+    value: Range<usize>,
+}
+
+fn find_entry(raw: &str, key: &str) -> Option<Entry> {
+    let mut offset = 0;
+    let mut found: Option<Entry> = None;
+
+    for line in raw.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        if let Some(entry) = &mut found {
+            if is_continuation(line) {
+                entry.lines.end = offset;
+                continue;
+            }
+            break;
+        }
+
+        let Some((line_key, value_start)) = entry_key(line) else {
+            continue;
+        };
+        if line_key == key {
+            let content = line.trim_end_matches(['\n', '\r']);
+            let value = &content[value_start..];
+            let value_len = value.len() - comment_len(value);
+            let leading = value.len() - value.trim_start().len();
+
+            found = Some(Entry {
+                lines: line_start..offset,
+                value: line_start + value_start + leading..line_start + value_start + value_len,
+            });
+        }
+    }
+
+    // Trailing blank lines belong to whatever follows, not to this entry
+    found.map(|mut entry| {
+        let trimmed = raw[entry.lines.clone()].trim_end().len();
+        let line_end = raw[entry.lines.start + trimmed..entry.lines.end]
+            .find('\n')
+            .map_or(entry.lines.end, |i| entry.lines.start + trimmed + i + 1);
+        entry.lines.end = line_end;
+        entry
+    })
+}
+
+/// The key of a top-level entry line, and the offset its value starts at
+fn entry_key(line: &str) -> Option<(String, usize)> {
+    if line.starts_with([' ', '\t', '#', '-']) {
+        return None;
+    }
+
+    let line = line.trim_end_matches(['\n', '\r']);
+    let (key, rest) = match line.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let close = line[1..].find(quote)? + 1;
+            (&line[1..close], &line[close + 1..])
+        }
+        _ => {
+            let colon = line
+                .find(": ")
+                .or_else(|| line.ends_with(':').then(|| line.len() - 1))?;
+            (&line[..colon], &line[colon..])
+        }
+    };
+
+    let rest = rest.strip_prefix(':')?;
+    if !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+    Some((key.trim().to_string(), line.len() - rest.len()))
+}
+
+fn is_continuation(line: &str) -> bool {
+    line.starts_with([' ', '\t'])
+        || line.starts_with("- ")
+        || line.trim_end() == "-"
+        || line.trim().is_empty()
+}
+
+fn comment_len(value: &str) -> usize {
+    let trimmed = value.trim_start();
+    let search_from = match trimmed.chars().next() {
+        Some(quote @ ('"' | '\'')) => trimmed[1..].find(quote).map_or(0, |i| i + 2),
+        _ => 0,
+    };
+    let offset = value.len() - trimmed.len() + search_from;
+
+    match value[offset..].find(" #") {
+        Some(i) => value.len() - (offset + i),
+        None => 0,
+    }
+}
+
+/// Where a top-level property's entry starts in raw YAML
+pub(crate) fn property_offset(raw: &str, key: &str) -> Option<usize> {
+    find_entry(raw, key).map(|entry| entry.lines.start)
+}
+
+pub(crate) fn set_raw_property(raw: &str, key: &str, value: &Properties) -> crate::Result<String> {
+    let Some(entry) = find_entry(raw, key) else {
+        let mut raw = raw.trim_end().to_string();
+        if !raw.is_empty() {
+            raw.push('\n');
+        }
+        raw.push_str(&emit_entry(key, value)?);
+        return Ok(raw.trim_end().to_string());
+    };
+
+    let single_line = entry.lines.end - entry.lines.start
+        == raw[entry.lines.clone()]
+            .find('\n')
+            .map_or(entry.lines.len(), |i| i + 1);
+    let old_value = &raw[entry.value.clone()];
+
+    let edited = match render_scalar(value, old_value)? {
+        Some(scalar) if single_line && !old_value.is_empty() => {
+            format!(
+                "{}{scalar}{}",
+                &raw[..entry.value.start],
+                &raw[entry.value.end..]
+            )
+        }
+        _ => format!(
+            "{}{}{}",
+            &raw[..entry.lines.start],
+            emit_entry(key, value)?,
+            &raw[entry.lines.end..]
+        ),
+    };
+
+    Ok(edited.trim_end().to_string())
+}
+
+pub(crate) fn remove_raw_property(raw: &str, key: &str) -> String {
+    match find_entry(raw, key) {
+        Some(entry) => format!("{}{}", &raw[..entry.lines.start], &raw[entry.lines.end..])
+            .trim_end()
+            .to_string(),
+        None => raw.to_string(),
+    }
+}
+
+/// `raw` with only the top-level entries that differ from `properties` rewritten, or `None` if
+/// they can't be patched in place, such as when either isn't a mapping
+pub(crate) fn patch_raw_properties(raw: &str, properties: &Properties) -> Option<String> {
+    let parsed = parse_mapping(raw)?;
+    if &parsed == properties {
+        return Some(raw.to_string());
+    }
+    let (old, new) = (parsed.as_mapping()?, properties.as_mapping()?);
+
+    let mut patched = raw.to_string();
+    for key in old.keys().filter(|key| !new.contains_key(*key)) {
+        patched = remove_raw_property(&patched, key.as_str()?);
+    }
+    for (key, value) in new
+        .iter()
+        .filter(|(key, value)| old.get(*key) != Some(*value))
+    {
+        patched = set_raw_property(&patched, key.as_str()?, value).ok()?;
+    }
+
+    (parse_mapping(&patched)? == *properties).then_some(patched)
+}
+
+/// Raw YAML's properties, treating empty YAML as an empty mapping
+fn parse_mapping(raw: &str) -> Option<Properties> {
+    let properties = parse_properties(raw).ok()?;
+    Some(properties.unwrap_or_else(|| Properties::Mapping(serde_yaml::Mapping::new())))
+}
+
+fn rename_raw_property(raw: &str, from: &str, to: &str) -> crate::Result<String> {
+    let Some(entry) = find_entry(raw, from) else {
+        return Ok(raw.to_string());
+    };
+    let line = &raw[entry.lines.clone()];
+    let Some((_, value_start)) = entry_key(line) else {
+        return Ok(raw.to_string());
+    };
+    // `value_start` is just past the colon
+    let colon = entry.lines.start + value_start - 1;
+    let key = serde_yaml::to_string(&Properties::from(to))?;
+
+    Ok(format!(
+        "{}{}{}",
+        &raw[..entry.lines.start],
+        key.trim_end(),
+        &raw[colon..]
+    ))
+}
+
+fn emit_entry(key: &str, value: &Properties) -> crate::Result<String> {
+    let mut mapping = serde_yaml::Mapping::new();
+    mapping.insert(Properties::from(key), value.clone());
+    Ok(serde_yaml::to_string(&mapping)?)
+}
+
+/// Renders a scalar on a single line, keeping the quote style of the value it replaces
+fn render_scalar(value: &Properties, old_value: &str) -> crate::Result<Option<String>> {
+    let rendered = match value {
+        Properties::String(s) if s.contains('\n') => return Ok(None),
+        Properties::String(s) if old_value.starts_with('\'') => {
+            format!("'{}'", s.replace('\'', "''"))
+        }
+        Properties::String(s) if old_value.starts_with('"') => {
+            format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+        Properties::Null | Properties::Bool(_) | Properties::Number(_) | Properties::String(_) => {
+            serde_yaml::to_string(value)?.trim_end().to_string()
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(rendered))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::path::PathBuf;
+
+    fn note(contents: &str) -> ObsidianNote {
+        ObsidianNote::parse(&PathBuf::from("a-note.md"), contents.to_string()).unwrap()
+    }
+
+    #[test]
+    fn set_property_preserves_order_quotes_and_comments() {
+        let mut note = note(indoc! {r#"
+            ---
+            # Managed by hand
+            title: "My Note"
+            status: 'draft' # update me
+            tags:
+              - one
+            ---
+            Body
+        "#});
+        note.set_property("status", "done").unwrap();
+
+        assert_eq!(note.properties.as_ref().unwrap()["status"], "done");
+        assert_eq!(
+            note.to_string(),
+            indoc! {r#"
+                ---
+                # Managed by hand
+                title: "My Note"
+                status: 'done' # update me
+                tags:
+                  - one
+                ---
+                Body
+            "#}
+        );
+    }
+
+    #[test]
+    fn set_property_appends_new_keys() {
+        let mut note = note(indoc! {r"
+            ---
+            b: 1
+            a: 2
+            ---
+        "});
+        note.set_property("c", vec!["x", "y"]).unwrap();
+
+        assert_eq!(
+            note.frontmatter.as_deref(),
+            Some("b: 1\na: 2\nc:\n- x\n- y")
+        );
+    }
+
+    #[test]
+    fn set_property_creates_frontmatter() {
+        let mut note = note("Body\n");
+        note.set_property("status", "done").unwrap();
+
+        assert_eq!(note.to_string(), "---\nstatus: done\n---\nBody\n");
+    }
+
+    #[test]
+    fn set_property_leaves_note_unchanged_on_error() {
+        let mut note = note("---\nstatus: draft\n---\nBody\n");
+        note.properties = Some(Properties::Sequence(vec![Properties::from("draft")]));
+
+        assert!(note.set_property("status", "done").is_err());
+        assert_eq!(note.frontmatter.as_deref(), Some("status: draft"));
+        assert!(note.properties.unwrap().as_sequence().is_some());
+    }
+
+    #[test]
+    fn set_property_replaces_block_values() {
+        let mut note = note(indoc! {r"
+            ---
+            tags:
+            - one
+            - two
+            after: true
+            ---
+        "});
+        note.set_property("tags", "single").unwrap();
+
+        assert_eq!(
+            note.frontmatter.as_deref(),
+            Some("tags: single\nafter: true")
+        );
+    }
+
+    #[test]
+    fn update_and_remove_property() {
+        let mut note = note(indoc! {r"
+            ---
+            count: 1
+            keep: yes
+            ---
+        "});
+
+        let updated = note
+            .update_property("count", |v| Properties::from(v.as_i64().unwrap() + 1))
+            .unwrap();
+        assert!(updated);
+        assert!(!note.update_property("missing", |v| v.clone()).unwrap());
+
+        assert_eq!(note.remove_property("count"), Some(Properties::from(2)));
+        assert_eq!(note.frontmatter.as_deref(), Some("keep: yes"));
+    }
+
+    #[test]
+    fn rename_property_keeps_position_and_value() {
+        let mut note = note(indoc! {r"
+            ---
+            first: 1
+            'old key': [a, b] # list
+            last: 3
+            ---
+        "});
+
+        assert!(note.rename_property("old key", "new").unwrap());
+        assert!(!note.rename_property("missing", "other").unwrap());
+        assert!(note.rename_property("first", "last").is_err());
+
+        assert_eq!(
+            note.frontmatter.as_deref(),
+            Some("first: 1\nnew: [a, b] # list\nlast: 3")
+        );
+        let keys: Vec<&str> = note
+            .properties
+            .as_ref()
+            .and_then(|p| p.as_mapping())
+            .unwrap()
+            .keys()
+            .filter_map(|k| k.as_str())
+            .collect();
+        assert_eq!(keys, vec!["first", "new", "last"]);
+    }
+
+    #[test]
+    fn property_edits_keep_aliases_in_sync() {
+        let mut note = note("---\naliases: Ada\n---\n");
+        assert_eq!(note.aliases(), vec!["Ada"]);
+
+        note.set_property("aliases", vec!["Ada", "Countess"])
+            .unwrap();
+        assert_eq!(note.aliases(), vec!["Ada", "Countess"]);
+
+        note.remove_property("aliases");
+        assert!(note.aliases().is_empty());
+    }
+}
@@ -0,0 +1,253 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{links::scan_links, vault::is_note, LinkResolver, ObsidianNote, Vault};
+
+/// A directed graph of notes, with an edge for every pair of linked notes
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Graph {
+    pub nodes: Vec<PathBuf>,
+    pub edges: Vec<Edge>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    /// Index into [`Graph::nodes`]
+    pub source: usize,
+    pub target: usize,
+    /// How many links from the source point at the target
+    pub count: usize,
+}
+
+impl Graph {
+    pub fn from_notes(notes: &[ObsidianNote], resolver: &LinkResolver) -> Self {
+        let nodes: Vec<PathBuf> = notes.iter().map(|n| n.file_path.clone()).collect();
+        let index: HashMap<&Path, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, path)| (path.as_path(), i))
+            .collect();
+
+        let mut counts: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        for (source, note) in notes.iter().enumerate() {
+            for (_, link) in scan_links(&note.file_body) {
+                let target = resolver
+                    .resolve_link(&link, &note.file_path)
+                    .filter(|path| is_note(path))
+                    .and_then(|path| index.get(path));
+                if let Some(&target) = target.filter(|&&t| t != source) {
+                    *counts.entry((source, target)).or_default() += 1;
+                }
+            }
+        }
+
+        let edges = counts
+            .into_iter()
+            .map(|((source, target), count)| Edge {
+                source,
+                target,
+                count,
+            })
+            .collect();
+        Self { nodes, edges }
+    }
+
+    fn id(&self, node: usize) -> String {
+        self.nodes[node].to_string_lossy().into_owned()
+    }
+
+    fn label(&self, node: usize) -> String {
+        let path = &self.nodes[node];
+        path.file_stem()
+            .unwrap_or(path.as_os_str())
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Graphviz DOT, with nodes labelled by note name
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph vault {\n");
+        for node in 0..self.nodes.len() {
+            let _ = writeln!(
+                dot,
+                "  \"{}\" [label=\"{}\"];",
+                escape_dot(&self.id(node)),
+                escape_dot(&self.label(node))
+            );
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                dot,
+                "  \"{}\" -> \"{}\" [weight={}];",
+                escape_dot(&self.id(edge.source)),
+                escape_dot(&self.id(edge.target)),
+                edge.count
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+            "  <key id=\"count\" for=\"edge\" attr.name=\"count\" attr.type=\"int\"/>\n",
+            "  <graph id=\"vault\" edgedefault=\"directed\">\n",
+        ));
+        for node in 0..self.nodes.len() {
+            let _ = writeln!(
+                xml,
+                "    <node id=\"{}\"><data key=\"label\">{}</data></node>",
+                escape_xml(&self.id(node)),
+                escape_xml(&self.label(node))
+            );
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                xml,
+                "    <edge source=\"{}\" target=\"{}\"><data key=\"count\">{}</data></edge>",
+                escape_xml(&self.id(edge.source)),
+                escape_xml(&self.id(edge.target)),
+                edge.count
+            );
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+
+    /// An adjacency map from each note to the notes it links to
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut adjacency: BTreeMap<String, Vec<String>> = (0..self.nodes.len())
+            .map(|node| (self.id(node), Vec::new()))
+            .collect();
+        for edge in &self.edges {
+            if let Some(targets) = adjacency.get_mut(&self.id(edge.source)) {
+                targets.push(self.id(edge.target));
+            }
+        }
+
+        serde_json::json!(adjacency)
+    }
+}
+
+impl Vault {
+    /// The link graph, with nodes identified by their path relative to the vault root
+    pub fn graph(&self) -> crate::Result<Graph> {
+        let notes = self.notes().collect::<crate::Result<Vec<_>>>()?;
+        let resolver = LinkResolver::from_vault_notes(self, &notes)?;
+        let mut graph = Graph::from_notes(&notes, &resolver);
+
+        for node in &mut graph.nodes {
+            *node = self.relative_path(node).to_path_buf();
+        }
+        Ok(graph)
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+pub(crate) fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> Graph {
+        let notes: Vec<ObsidianNote> = [
+            ("a.md", "[[b]] [[b|again]] [[c]] ![[image.png]]"),
+            ("b.md", "[[a]]"),
+            ("c \"quoted\".md", ""),
+        ]
+        .iter()
+        .map(|(path, body)| ObsidianNote::parse(Path::new(path), body.to_string()).unwrap())
+        .collect();
+        let mut resolver = LinkResolver::from_notes(&notes);
+        resolver.add_alias("c", Path::new("c \"quoted\".md"));
+
+        Graph::from_notes(&notes, &resolver)
+    }
+
+    #[test]
+    fn from_notes_adds_edges_for_markdown_links() {
+        let notes: Vec<ObsidianNote> = [
+            ("a.md", "[B](b.md) [again](b.md#Top)"),
+            ("b.md", "[A](a.md)"),
+        ]
+        .iter()
+        .map(|(path, body)| ObsidianNote::parse(Path::new(path), body.to_string()).unwrap())
+        .collect();
+        let graph = Graph::from_notes(&notes, &LinkResolver::from_notes(&notes));
+
+        let edges: Vec<(usize, usize, usize)> = graph
+            .edges
+            .iter()
+            .map(|edge| (edge.source, edge.target, edge.count))
+            .collect();
+        assert_eq!(edges, vec![(0, 1, 2), (1, 0, 1)]);
+    }
+
+    #[test]
+    fn from_notes_counts_edges_between_notes() {
+        let graph = graph();
+        assert_eq!(
+            graph.edges,
+            vec![
+                Edge {
+                    source: 0,
+                    target: 1,
+                    count: 2
+                },
+                Edge {
+                    source: 0,
+                    target: 2,
+                    count: 1
+                },
+                Edge {
+                    source: 1,
+                    target: 0,
+                    count: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn to_dot_escapes_names() {
+        let dot = graph().to_dot();
+        assert!(dot.starts_with("digraph vault {\n"));
+        assert!(dot.contains("  \"a.md\" -> \"b.md\" [weight=2];\n"));
+        assert!(dot.contains("  \"c \\\"quoted\\\".md\" [label=\"c \\\"quoted\\\"\"];\n"));
+    }
+
+    #[test]
+    fn to_graphml_emits_nodes_and_edges() {
+        let xml = graph().to_graphml();
+        assert!(xml.contains("<node id=\"c &quot;quoted&quot;.md\">"));
+        assert!(xml.contains("<edge source=\"b.md\" target=\"a.md\"><data key=\"count\">1</data>"));
+    }
+
+    #[test]
+    fn to_json_emits_adjacency() {
+        assert_eq!(
+            graph().to_json(),
+            serde_json::json!({
+                "a.md": ["b.md", "c \"quoted\".md"],
+                "b.md": ["a.md"],
+                "c \"quoted\".md": [],
+            })
+        );
+    }
+}
@@ -0,0 +1,372 @@
+use std::{
+    collections::HashSet,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    code::{fenced_ranges, in_ranges},
+    LinkResolver, ObsidianNote, Vault, WikiLink,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heading {
+    pub level: u8,
+    pub text: String,
+    /// Byte range of the heading line, excluding its newline
+    pub span: Range<usize>,
+    /// Index of the enclosing heading in the outline
+    pub parent: Option<usize>,
+    /// Indices of the headings directly nested under this one
+    pub children: Vec<usize>,
+}
+
+/// A heading and everything under it, up to the next heading of the same or a higher level
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub heading: Heading,
+    /// The section's text, starting with its heading line, without trailing blank lines
+    pub text: String,
+    /// Byte range of the section within the note's body
+    pub span: Range<usize>,
+}
+
+/// A section a link such as `[[Note#Heading]]` points to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionRef {
+    /// The note the section is in
+    pub note: PathBuf,
+    pub section: Section,
+}
+
+impl ObsidianNote {
+    pub fn headings(&self) -> Vec<Heading> {
+        parse_headings(&self.file_body)
+    }
+
+    /// The HTML anchor of each heading in [`ObsidianNote::headings`], unique within the note
+    pub fn heading_anchors(&self) -> Vec<String> {
+        let mut anchors = HeadingAnchors::default();
+        self.headings()
+            .iter()
+            .map(|heading| anchors.next(&heading.text))
+            .collect()
+    }
+
+    /// The section a link's heading selects, as in `[[Note#Heading]]`. Nested selectors such
+    /// as `Heading#Subheading` pick a subheading within the first heading's section.
+    pub fn section(&self, heading: &str) -> Option<Section> {
+        let headings = self.headings();
+        let index = find_nested_heading(&headings, heading)?;
+        let level = headings[index].level;
+        let end = headings[index + 1..]
+            .iter()
+            .find(|next| next.level <= level)
+            .map_or(self.file_body.len(), |next| next.span.start);
+
+        let heading = headings[index].clone();
+        let span = heading.span.start..end;
+        Some(Section {
+            text: self.file_body[span.clone()].trim_end().to_string(),
+            heading,
+            span,
+        })
+    }
+}
+
+impl Vault {
+    /// The section a link from `source` references, reading the note it resolves to. `None`
+    /// when the link has no heading or either the note or the heading doesn't exist.
+    pub fn resolve_heading(
+        &self,
+        resolver: &LinkResolver,
+        link: &WikiLink,
+        source: &Path,
+    ) -> crate::Result<Option<SectionRef>> {
+        let Some(heading) = &link.heading else {
+            return Ok(None);
+        };
+        let Some(path) = resolver.resolve_link(link, source) else {
+            return Ok(None);
+        };
+
+        let note = ObsidianNote::read_from_path_with(path, self.read_options)?;
+        Ok(note.section(heading).map(|section| SectionRef {
+            note: note.file_path,
+            section,
+        }))
+    }
+}
+
+/// The heading as Obsidian writes it after the `#` in `[[Note#Heading]]`. Characters links
+/// can't hold, such as `#`, `|`, `^` and `:`, become spaces, and runs of spaces collapse.
+pub fn heading_link_text(text: &str) -> String {
+    text.replace("%%", " ")
+        .replace(['#', '^', '|', ':', '[', ']', '\\'], " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The `id` a heading gets in exported HTML: the link text lowercased, with spaces as hyphens
+/// and other punctuation and emoji dropped
+pub fn heading_anchor(text: &str) -> String {
+    let mut anchor = String::new();
+    for c in heading_link_text(text).to_lowercase().chars() {
+        if c.is_alphanumeric() || c == '_' {
+            anchor.push(c);
+        } else if (c.is_whitespace() || c == '-') && !anchor.is_empty() && !anchor.ends_with('-') {
+            anchor.push('-');
+        }
+    }
+
+    match anchor.trim_end_matches('-') {
+        "" => "heading".to_string(),
+        anchor => anchor.to_string(),
+    }
+}
+
+/// Hands out heading anchors for one note, numbering repeats as `heading-1`, `heading-2`...
+#[derive(Debug, Default, Clone)]
+pub struct HeadingAnchors {
+    used: HashSet<String>,
+}
+
+impl HeadingAnchors {
+    pub fn next(&mut self, text: &str) -> String {
+        let base = heading_anchor(text);
+        let mut anchor = base.clone();
+        for n in 1.. {
+            if self.used.insert(anchor.clone()) {
+                break;
+            }
+            anchor = format!("{base}-{n}");
+        }
+        anchor
+    }
+}
+
+pub fn parse_headings(text: &str) -> Vec<Heading> {
+    let code = fenced_ranges(text);
+    let mut headings: Vec<Heading> = Vec::new();
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        if in_ranges(&code, start) {
+            continue;
+        }
+
+        let content = line.trim_end_matches(['\n', '\r']);
+        let Some((level, heading_text)) = atx_heading(content) else {
+            continue;
+        };
+
+        let parent = headings.iter().rposition(|h| h.level < level);
+        let index = headings.len();
+        if let Some(parent) = parent {
+            headings[parent].children.push(index);
+        }
+
+        headings.push(Heading {
+            level,
+            text: heading_text.to_string(),
+            span: start..start + content.len(),
+            parent,
+            children: Vec::new(),
+        });
+    }
+
+    headings
+}
+
+/// The heading a selector such as `## Log` picks, or any heading titled `Log` without the `#`s
+pub(crate) fn find_heading(headings: &[Heading], selector: &str) -> Option<usize> {
+    let selector = selector.trim();
+    let (level, text) = match atx_heading(selector) {
+        Some((level, text)) => (Some(level), text),
+        None => (None, selector),
+    };
+    headings
+        .iter()
+        .position(|heading| heading.text == text && level.is_none_or(|l| l == heading.level))
+}
+
+/// The heading a link's `Heading#Subheading` selects, matching headings as Obsidian writes
+/// them in links and ignoring case. Each later part must be nested under the one before.
+pub(crate) fn find_nested_heading(headings: &[Heading], selector: &str) -> Option<usize> {
+    let matches = |heading: &Heading, part: &str| {
+        heading_link_text(&heading.text).to_lowercase() == heading_link_text(part).to_lowercase()
+    };
+
+    let mut parts = selector.split('#').filter(|part| !part.trim().is_empty());
+    let first = parts.next()?;
+    let mut index = headings.iter().position(|h| matches(h, first))?;
+    for part in parts {
+        let level = headings[index].level;
+        let within = headings[index + 1..]
+            .iter()
+            .take_while(|next| next.level > level);
+        index += 1 + within.clone().position(|h| matches(h, part))?;
+    }
+    Some(index)
+}
+
+/// The level and text of an ATX heading line such as `## Heading ##`
+pub(crate) fn atx_heading(line: &str) -> Option<(u8, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+
+    let trimmed = &line[indent..];
+    let level = trimmed.len() - trimmed.trim_start_matches('#').len();
+    let rest = &trimmed[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+
+    // An optional closing sequence of `#`s must be separated by whitespace
+    let text = rest.trim();
+    let without_closing = text.trim_end_matches('#');
+    let text = if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+        without_closing.trim_end()
+    } else {
+        text
+    };
+
+    Some((level as u8, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn parse_headings_builds_outline() {
+        let headings = parse_headings(indoc! {r"
+            # Title
+            ## First
+            ### Nested
+            ## Second
+            # Another
+        "});
+
+        let outline: Vec<(u8, &str, Option<usize>)> = headings
+            .iter()
+            .map(|h| (h.level, h.text.as_str(), h.parent))
+            .collect();
+        assert_eq!(
+            outline,
+            vec![
+                (1, "Title", None),
+                (2, "First", Some(0)),
+                (3, "Nested", Some(1)),
+                (2, "Second", Some(0)),
+                (1, "Another", None),
+            ]
+        );
+        assert_eq!(headings[0].children, vec![1, 3]);
+    }
+
+    #[test]
+    fn parse_headings_records_spans() {
+        let text = "Intro\n## Heading ##\nBody";
+        let headings = parse_headings(text);
+
+        assert_eq!(headings[0].text, "Heading");
+        assert_eq!(&text[headings[0].span.clone()], "## Heading ##");
+    }
+
+    #[test]
+    fn heading_anchors_match_obsidian_and_stay_unique() {
+        assert_eq!(
+            heading_link_text("Intro: Part #2 | draft"),
+            "Intro Part 2 draft"
+        );
+        assert_eq!(
+            heading_anchor("Intro: Part #2 | draft"),
+            "intro-part-2-draft"
+        );
+        assert_eq!(heading_anchor("🚀 Launch plan!"), "launch-plan");
+        assert_eq!(heading_anchor("Café — Übersicht"), "café-übersicht");
+        assert_eq!(heading_anchor("🎉"), "heading");
+
+        let mut anchors = HeadingAnchors::default();
+        let ids: Vec<String> = ["Notes", "Notes 1", "Notes", "notes"]
+            .iter()
+            .map(|text| anchors.next(text))
+            .collect();
+        assert_eq!(ids, vec!["notes", "notes-1", "notes-2", "notes-3"]);
+    }
+
+    #[test]
+    fn section_selects_nested_headings() {
+        let note = ObsidianNote::parse(
+            Path::new("Note.md"),
+            indoc! {r"
+                # Plan
+                Intro
+                ## Goals
+                Ship it
+                ### Stretch
+                Celebrate
+
+                ## Risks
+                Delays
+                # Goals
+                Elsewhere
+            "}
+            .to_string(),
+        )
+        .unwrap();
+
+        let goals = note.section("Plan#Goals").unwrap();
+        assert_eq!(goals.text, "## Goals\nShip it\n### Stretch\nCelebrate");
+        assert_eq!(goals.heading.level, 2);
+        assert_eq!(note.section("goals").unwrap().heading.level, 2);
+        assert_eq!(
+            note.section("Plan#Stretch").unwrap().text,
+            "### Stretch\nCelebrate"
+        );
+        assert_eq!(note.section("Risks").unwrap().text, "## Risks\nDelays");
+        assert_eq!(note.section("Risks#Stretch"), None);
+    }
+
+    #[test]
+    fn resolve_heading_follows_links_into_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Note.md"), "# A\none\n# B: Part 2\ntwo\n").unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+        let resolver = vault.link_resolver().unwrap();
+        let source = dir.path().join("Index.md");
+        let links = crate::links::scan_wikilinks("[[Note#B Part 2]] [[Note#C]]");
+
+        let found = vault
+            .resolve_heading(&resolver, &links[0].1, &source)
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.note, dir.path().join("Note.md"));
+        assert_eq!(found.section.text, "# B: Part 2\ntwo");
+        assert_eq!(
+            vault
+                .resolve_heading(&resolver, &links[1].1, &source)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_headings_ignores_tags_and_code() {
+        let headings = parse_headings(indoc! {r"
+            #tag
+            ```
+            # Not a heading
+            ```
+            ####### Too deep
+        "});
+        assert!(headings.is_empty());
+    }
+}
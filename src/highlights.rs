@@ -0,0 +1,140 @@
+use std::ops::Range;
+
+use crate::{
+    code::{code_ranges, in_ranges},
+    headings::atx_heading,
+    math_ranges, parse_headings, ObsidianNote,
+};
+
+/// An `==highlighted==` span, with what an annotation export needs to place it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Highlight {
+    /// The text between the `==`s
+    pub text: String,
+    /// The paragraph the highlight sits in, trimmed
+    pub context: String,
+    /// The nearest heading above the highlight
+    pub heading: Option<String>,
+    /// Byte range including the `==`s
+    pub span: Range<usize>,
+}
+
+impl ObsidianNote {
+    pub fn highlights(&self) -> Vec<Highlight> {
+        parse_highlights(&self.file_body)
+    }
+}
+
+/// Finds highlights outside code and math. A highlight stays on one line and has to hug its
+/// `==`s, so `a == b` isn't one.
+pub fn parse_highlights(text: &str) -> Vec<Highlight> {
+    let mut skip = code_ranges(text);
+    skip.extend(math_ranges(text));
+    let headings = parse_headings(text);
+    let mut highlights = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(offset) = text[cursor..].find("==") {
+        let start = cursor + offset;
+        cursor = start + 1;
+        if in_ranges(&skip, start) {
+            continue;
+        }
+
+        let inner_start = start + 2;
+        let line_end = text[inner_start..]
+            .find('\n')
+            .map_or(text.len(), |len| inner_start + len);
+        let Some(len) = text[inner_start..line_end].find("==") else {
+            continue;
+        };
+        let inner = &text[inner_start..inner_start + len];
+        if inner.is_empty()
+            || inner.starts_with(char::is_whitespace)
+            || inner.ends_with(char::is_whitespace)
+        {
+            continue;
+        }
+
+        let end = inner_start + len + 2;
+        let heading = headings
+            .iter()
+            .take_while(|heading| heading.span.start < start)
+            .last()
+            .map(|heading| heading.text.clone());
+        highlights.push(Highlight {
+            text: inner.to_string(),
+            context: paragraph(text, start..end).trim().to_string(),
+            heading,
+            span: start..end,
+        });
+        cursor = end;
+    }
+
+    highlights
+}
+
+/// The lines around `span` up to a blank line or heading on either side
+fn paragraph(text: &str, span: Range<usize>) -> &str {
+    let is_break = |line: &str| {
+        let line = line.trim_end_matches(['\n', '\r']);
+        line.trim().is_empty() || atx_heading(line).is_some()
+    };
+
+    let mut start = text[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    while start > 0 {
+        let previous = text[..start - 1].rfind('\n').map_or(0, |i| i + 1);
+        if is_break(&text[previous..start]) {
+            break;
+        }
+        start = previous;
+    }
+
+    let mut end = text[span.end..]
+        .find('\n')
+        .map_or(text.len(), |i| span.end + i);
+    while end < text.len() {
+        let next = text[end + 1..]
+            .find('\n')
+            .map_or(text.len(), |i| end + 1 + i);
+        if is_break(&text[end + 1..next]) {
+            break;
+        }
+        end = next;
+    }
+
+    &text[start..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn parse_highlights_keeps_context_and_heading() {
+        let text = indoc! {r"
+            Intro with ==no heading==.
+
+            # Reading
+            The author argues that
+            ==habits compound== over time.
+
+            Not `==code==`, $a==b$ or x == y.
+        "};
+
+        let highlights = parse_highlights(text);
+        assert_eq!(highlights.len(), 2);
+        assert_eq!(highlights[0].text, "no heading");
+        assert_eq!(highlights[0].heading, None);
+
+        let highlight = &highlights[1];
+        assert_eq!(highlight.text, "habits compound");
+        assert_eq!(highlight.heading.as_deref(), Some("Reading"));
+        assert_eq!(
+            highlight.context,
+            "The author argues that\n==habits compound== over time."
+        );
+        assert_eq!(&text[highlight.span.clone()], "==habits compound==");
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{Error, ObsidianNote, Properties};
+
+pub mod enex;
+pub mod notion;
+pub mod roam;
+
+/// Notes and attachments converted from another app, with paths relative to the vault root
+#[derive(Debug, Default, PartialEq)]
+pub struct Import {
+    pub notes: Vec<ObsidianNote>,
+    pub attachments: Vec<ImportedAttachment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedAttachment {
+    pub path: PathBuf,
+    pub contents: AttachmentContents,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttachmentContents {
+    /// A file to copy
+    File(PathBuf),
+    Bytes(Vec<u8>),
+}
+
+impl Import {
+    /// Writes every note and attachment under `vault_dir`, refusing to overwrite existing files
+    pub fn write_to(&self, vault_dir: &Path) -> crate::Result<()> {
+        let paths = self
+            .notes
+            .iter()
+            .map(|note| &note.file_path)
+            .chain(self.attachments.iter().map(|attachment| &attachment.path));
+        for path in paths {
+            let path = vault_dir.join(path);
+            if path.exists() {
+                return Err(Error::AlreadyExists(path));
+            }
+        }
+
+        for note in &self.notes {
+            let path = vault_dir.join(&note.file_path);
+            create_parent(&path)?;
+            note.write_to_path(&path)?;
+        }
+        for attachment in &self.attachments {
+            let path = vault_dir.join(&attachment.path);
+            create_parent(&path)?;
+            match &attachment.contents {
+                AttachmentContents::File(source) => {
+                    fs::copy(source, &path)?;
+                }
+                AttachmentContents::Bytes(bytes) => fs::write(&path, bytes)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds a note from properties and a markdown body
+pub(crate) fn build_note(
+    path: PathBuf,
+    properties: serde_yaml::Mapping,
+    body: &str,
+) -> crate::Result<ObsidianNote> {
+    let mut contents = String::new();
+    if !properties.is_empty() {
+        contents.push_str("---\n");
+        contents.push_str(&serde_yaml::to_string(&Properties::Mapping(properties))?);
+        contents.push_str("---\n");
+    }
+    contents.push_str(body.trim());
+    contents.push('\n');
+
+    ObsidianNote::parse(&path, contents)
+}
+
+fn create_parent(path: &Path) -> crate::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(())
+}
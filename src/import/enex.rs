@@ -0,0 +1,573 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use base64::Engine;
+use quick_xml::{events::Event, Reader, XmlVersion};
+use serde_yaml::{Mapping, Value};
+
+use super::{build_note, AttachmentContents, Import, ImportedAttachment};
+
+/// Where attachments go, relative to the vault root
+const ATTACHMENTS: &str = "attachments";
+
+/// Converts an Evernote ENEX export into notes
+///
+/// Each note's ENML is converted to markdown, its resources become attachments embedded where
+/// the `<en-media>` was, and its created and updated times, tags, and source URL become the
+/// `created`, `updated`, `tags`, and `source` properties.
+pub fn import_enex(enex: &str) -> crate::Result<Import> {
+    let document = parse_xml(enex)?;
+    let mut import = Import::default();
+    let mut note_paths = HashSet::new();
+    let mut attachment_paths = HashSet::new();
+
+    let notes = document
+        .elements("en-export")
+        .flat_map(|export| export.elements("note"));
+    for note in notes {
+        let title = note.child("title").map(Element::text).unwrap_or_default();
+        let name = sanitize(title.trim());
+        let name = if name.is_empty() { "Untitled" } else { &name };
+        let path = unique(&mut note_paths, Path::new(""), name, "md");
+
+        // `<en-media>` refers to resources by the MD5 hash of their data
+        let mut media = HashMap::new();
+        for resource in note.elements("resource") {
+            let data = resource
+                .child("data")
+                .map(Element::text)
+                .unwrap_or_default();
+            let data: String = data.split_whitespace().collect();
+            let bytes = base64::engine::general_purpose::STANDARD.decode(data)?;
+
+            let file_name = resource
+                .child("resource-attributes")
+                .and_then(|attributes| attributes.child("file-name"))
+                .map(|name| sanitize(&name.text()))
+                .filter(|name| !name.is_empty());
+            let hash = format!("{:x}", md5::compute(&bytes));
+            let file_name = file_name.unwrap_or_else(|| {
+                let mime = resource
+                    .child("mime")
+                    .map(Element::text)
+                    .unwrap_or_default();
+                let extension = mime.rsplit('/').next().unwrap_or("bin");
+                format!("{hash}.{extension}")
+            });
+            let (stem, extension) = file_name.rsplit_once('.').unwrap_or((&file_name, ""));
+            let path = unique(
+                &mut attachment_paths,
+                Path::new(ATTACHMENTS),
+                stem,
+                extension,
+            );
+
+            media.insert(hash, path.clone());
+            import.attachments.push(ImportedAttachment {
+                path,
+                contents: AttachmentContents::Bytes(bytes),
+            });
+        }
+
+        let body = match note.child("content") {
+            Some(content) => {
+                let enml = parse_xml(&content.text())?;
+                let mut markdown = String::new();
+                Enml { media: &media }.render(&enml.children, &mut markdown);
+                tidy(&markdown)
+            }
+            None => String::new(),
+        };
+
+        let mut properties = Mapping::new();
+        for (element, property) in [("created", "created"), ("updated", "updated")] {
+            if let Some(time) = note.child(element).map(|time| timestamp(&time.text())) {
+                properties.insert(property.into(), time.into());
+            }
+        }
+        let tags: Vec<Value> = note
+            .elements("tag")
+            .map(|tag| tag.text().trim().replace(' ', "-").into())
+            .collect();
+        if !tags.is_empty() {
+            properties.insert("tags".into(), Value::Sequence(tags));
+        }
+        let source = note
+            .child("note-attributes")
+            .and_then(|attributes| attributes.child("source-url"))
+            .map(Element::text);
+        if let Some(source) = source.filter(|source| !source.is_empty()) {
+            properties.insert("source".into(), source.into());
+        }
+
+        import.notes.push(build_note(path, properties, &body)?);
+    }
+
+    Ok(import)
+}
+
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Content>,
+}
+
+#[derive(Debug)]
+enum Content {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    fn elements<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter_map(move |child| match child {
+            Content::Element(element) if element.name == name => Some(element),
+            _ => None,
+        })
+    }
+
+    fn child<'a>(&'a self, name: &'a str) -> Option<&'a Element> {
+        self.elements(name).next()
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn text(&self) -> String {
+        self.children
+            .iter()
+            .map(|child| match child {
+                Content::Element(element) => element.text(),
+                Content::Text(text) => text.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Reads a document into a tree under an unnamed root
+fn parse_xml(xml: &str) -> crate::Result<Element> {
+    let mut reader = Reader::from_str(xml);
+    let mut open = vec![Element::default()];
+
+    loop {
+        let text = match reader.read_event()? {
+            Event::Start(start) => {
+                open.push(element(&start)?);
+                continue;
+            }
+            Event::Empty(start) => {
+                let element = element(&start)?;
+                if let Some(parent) = open.last_mut() {
+                    parent.children.push(Content::Element(element));
+                }
+                continue;
+            }
+            Event::End(_) => {
+                if open.len() > 1 {
+                    let element = open.pop().unwrap_or_default();
+                    if let Some(parent) = open.last_mut() {
+                        parent.children.push(Content::Element(element));
+                    }
+                }
+                continue;
+            }
+            Event::Text(text) => text.xml10_content().into_owned(),
+            Event::CData(data) => data.into_inner().into_owned(),
+            Event::GeneralRef(reference) => match reference.resolve_char_ref()? {
+                Some(c) => c.to_string(),
+                None => entity(&reference.into_inner())
+                    .unwrap_or_default()
+                    .to_string(),
+            },
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        if let Some(parent) = open.last_mut() {
+            match parent.children.last_mut() {
+                Some(Content::Text(previous)) => previous.push_str(&text),
+                _ => parent.children.push(Content::Text(text)),
+            }
+        }
+    }
+
+    // Close anything left open by a truncated document
+    while open.len() > 1 {
+        let element = open.pop().unwrap_or_default();
+        if let Some(parent) = open.last_mut() {
+            parent.children.push(Content::Element(element));
+        }
+    }
+    Ok(open.pop().unwrap_or_default())
+}
+
+fn element(start: &quick_xml::events::BytesStart) -> crate::Result<Element> {
+    let mut attributes = Vec::new();
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(quick_xml::Error::from)?;
+        let value = attribute
+            .normalized_value_with(XmlVersion::Implicit1_0, 1, entity)?
+            .into_owned();
+        attributes.push((attribute.key.as_ref().to_string(), value));
+    }
+    Ok(Element {
+        name: start.name().as_ref().to_string(),
+        attributes,
+        children: Vec::new(),
+    })
+}
+
+/// The named entities ENML's DTD defines that are likely to appear in notes
+fn entity(name: &str) -> Option<&'static str> {
+    match name {
+        "amp" => Some("&"),
+        "lt" => Some("<"),
+        "gt" => Some(">"),
+        "quot" => Some("\""),
+        "apos" => Some("'"),
+        "nbsp" => Some(" "),
+        _ => None,
+    }
+}
+
+struct Enml<'a> {
+    /// Attachment paths by resource hash
+    media: &'a HashMap<String, PathBuf>,
+}
+
+impl Enml<'_> {
+    fn render(&self, nodes: &[Content], out: &mut String) {
+        for node in nodes {
+            match node {
+                Content::Text(text) => {
+                    // Runs of whitespace are a single space, as in HTML
+                    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if text.starts_with(char::is_whitespace) && !ends_with_space(out) {
+                        out.push(' ');
+                    }
+                    out.push_str(&collapsed);
+                    if !collapsed.is_empty() && text.ends_with(char::is_whitespace) {
+                        out.push(' ');
+                    }
+                }
+                Content::Element(element) => self.render_element(element, out),
+            }
+        }
+    }
+
+    fn render_element(&self, element: &Element, out: &mut String) {
+        let inline = |out: &mut String, marker: &str| {
+            let mut inner = String::new();
+            self.render(&element.children, &mut inner);
+            if !inner.trim().is_empty() {
+                out.push_str(&format!("{marker}{}{marker}", inner.trim()));
+            }
+        };
+
+        match element.name.as_str() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = element.name[1..].parse().unwrap_or(1);
+                let mut inner = String::new();
+                self.render(&element.children, &mut inner);
+                block(out, &format!("{} {}", "#".repeat(level), inner.trim()));
+            }
+            "div"
+                if element
+                    .attribute("style")
+                    .is_some_and(|style| style.contains("-en-codeblock")) =>
+            {
+                block(out, &format!("```\n{}\n```", lines(element).trim_end()));
+            }
+            "pre" => block(out, &format!("```\n{}\n```", element.text().trim_end())),
+            "br" => out.push('\n'),
+            "hr" => block(out, "---"),
+            "b" | "strong" => inline(out, "**"),
+            "i" | "em" => inline(out, "*"),
+            "s" | "strike" | "del" => inline(out, "~~"),
+            "code" => inline(out, "`"),
+            "a" => {
+                let mut inner = String::new();
+                self.render(&element.children, &mut inner);
+                match element.attribute("href") {
+                    Some(href) => out.push_str(&format!("[{}]({href})", inner.trim())),
+                    None => out.push_str(&inner),
+                }
+            }
+            "img" => {
+                if let Some(src) = element.attribute("src") {
+                    out.push_str(&format!("![]({src})"));
+                }
+            }
+            "en-todo" => {
+                let checked = element.attribute("checked") == Some("true");
+                out.push_str(if checked { "- [x] " } else { "- [ ] " });
+            }
+            "en-media" => {
+                let path = element
+                    .attribute("hash")
+                    .and_then(|hash| self.media.get(hash));
+                if let Some(path) = path {
+                    out.push_str(&format!("![[{}]]", crate::rename::path_to_link(path)));
+                }
+            }
+            "ul" | "ol" => {
+                let mut list = String::new();
+                for (i, item) in element.elements("li").enumerate() {
+                    let marker = if element.name == "ol" {
+                        format!("{}. ", i + 1)
+                    } else {
+                        "- ".to_string()
+                    };
+                    let mut inner = String::new();
+                    self.render(&item.children, &mut inner);
+                    let inner = tidy(&inner);
+                    let mut item_lines = inner.lines().filter(|line| !line.is_empty());
+                    list.push_str(&marker);
+                    list.push_str(item_lines.next().unwrap_or_default());
+                    list.push('\n');
+                    for line in item_lines {
+                        list.push_str(&format!("\t{line}\n"));
+                    }
+                }
+                block(out, list.trim_end());
+            }
+            "table" => block(out, &self.table(element)),
+            "div" | "p" | "blockquote" | "en-note" | "section" | "article" => {
+                let mut inner = String::new();
+                self.render(&element.children, &mut inner);
+                if element.name == "blockquote" {
+                    let quoted: Vec<String> = tidy(&inner)
+                        .lines()
+                        .map(|line| format!("> {line}"))
+                        .collect();
+                    block(out, &quoted.join("\n"));
+                } else {
+                    block(out, &inner);
+                }
+            }
+            _ => self.render(&element.children, out),
+        }
+    }
+
+    fn table(&self, table: &Element) -> String {
+        let mut rows = Vec::new();
+        let mut stack = vec![table];
+        while let Some(element) = stack.pop() {
+            if element.name == "tr" {
+                let cells: Vec<String> = element
+                    .children
+                    .iter()
+                    .filter_map(|child| match child {
+                        Content::Element(cell) if cell.name == "td" || cell.name == "th" => {
+                            let mut inner = String::new();
+                            self.render(&cell.children, &mut inner);
+                            Some(tidy(&inner).replace('\n', " ").replace('|', "\\|"))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                rows.push(cells);
+                continue;
+            }
+            for child in element.children.iter().rev() {
+                if let Content::Element(child) = child {
+                    stack.push(child);
+                }
+            }
+        }
+
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut markdown = Vec::new();
+        for (i, row) in rows.iter().enumerate() {
+            let mut cells = row.clone();
+            cells.resize(columns, String::new());
+            markdown.push(format!("| {} |", cells.join(" | ")));
+            if i == 0 {
+                markdown.push(format!("|{}", "---|".repeat(columns)));
+            }
+        }
+        markdown.join("\n")
+    }
+}
+
+fn ends_with_space(out: &str) -> bool {
+    out.is_empty() || out.ends_with([' ', '\n'])
+}
+
+fn block(out: &mut String, markdown: &str) {
+    out.push_str("\n\n");
+    out.push_str(markdown);
+    out.push_str("\n\n");
+}
+
+/// The text of a code block, with each child `<div>` on its own line
+fn lines(element: &Element) -> String {
+    element
+        .children
+        .iter()
+        .map(|child| match child {
+            Content::Element(element) if element.name == "div" => {
+                let text = element.text();
+                if text.ends_with('\n') {
+                    text
+                } else {
+                    text + "\n"
+                }
+            }
+            Content::Element(element) if element.name == "br" => "\n".to_string(),
+            Content::Element(element) => element.text(),
+            Content::Text(text) => text.clone(),
+        })
+        .collect()
+}
+
+/// Trims trailing spaces, collapses runs of blank lines, and trims the ends
+fn tidy(markdown: &str) -> String {
+    let mut tidied = String::new();
+    let mut blank = false;
+    for line in markdown.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            blank = true;
+            continue;
+        }
+        if blank && !tidied.is_empty() {
+            tidied.push('\n');
+        }
+        blank = false;
+        tidied.push_str(line);
+        tidied.push('\n');
+    }
+    tidied.trim_end().to_string()
+}
+
+/// `20230115T101500Z` as `2023-01-15T10:15:00Z`
+fn timestamp(time: &str) -> String {
+    let time = time.trim();
+    if time.len() < 15 || !time.is_char_boundary(15) || time.as_bytes()[8] != b'T' {
+        return time.to_string();
+    }
+    format!(
+        "{}-{}-{}T{}:{}:{}{}",
+        &time[0..4],
+        &time[4..6],
+        &time[6..8],
+        &time[9..11],
+        &time[11..13],
+        &time[13..15],
+        &time[15..]
+    )
+}
+
+/// Replaces characters that aren't allowed in file names
+fn sanitize(name: &str) -> String {
+    name.replace(
+        [
+            '/', '\\', ':', '*', '?', '"', '<', '>', '|', '#', '^', '[', ']',
+        ],
+        "-",
+    )
+}
+
+/// `dir/stem.extension`, or `dir/stem 1.extension` and so on if that's already taken
+fn unique(taken: &mut HashSet<PathBuf>, dir: &Path, stem: &str, extension: &str) -> PathBuf {
+    let mut n = 0;
+    loop {
+        let stem = if n == 0 {
+            stem.to_string()
+        } else {
+            format!("{stem} {n}")
+        };
+        let mut path = dir.join(stem);
+        if !extension.is_empty() {
+            path.set_extension(extension);
+        }
+        if taken.insert(path.clone()) {
+            return path;
+        }
+        n += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::{formatdoc, indoc};
+
+    fn export() -> String {
+        let hash = format!("{:x}", md5::compute("png"));
+        formatdoc! {r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <!DOCTYPE en-export SYSTEM "http://xml.evernote.com/pub/evernote-export3.dtd">
+            <en-export>
+            <note>
+                <title>Trip: Plans</title>
+                <content><![CDATA[<?xml version="1.0" encoding="UTF-8"?>
+            <!DOCTYPE en-note SYSTEM "http://xml.evernote.com/pub/enml2.dtd">
+            <en-note><h2>Packing</h2><div><en-todo checked="true"/>Passport</div><div><en-todo/>Sun&nbsp;cream</div>
+            <ul><li>Some <b>bold</b> text</li><li><a href="https://example.com">Link</a></li></ul>
+            <div><en-media hash="{hash}" type="image/png"/></div></en-note>]]></content>
+                <created>20230115T101500Z</created>
+                <tag>travel</tag>
+                <tag>to do</tag>
+                <note-attributes><source-url>https://example.com/trip</source-url></note-attributes>
+                <resource>
+                    <data encoding="base64">
+                    cG5n
+                    </data>
+                    <mime>image/png</mime>
+                    <resource-attributes><file-name>map.png</file-name></resource-attributes>
+                </resource>
+            </note>
+            </en-export>
+        "#}
+    }
+
+    #[test]
+    fn import_enex_converts_enml_to_markdown() {
+        let import = import_enex(&export()).unwrap();
+        let note = &import.notes[0];
+
+        assert_eq!(note.file_path, PathBuf::from("Trip- Plans.md"));
+        assert_eq!(
+            note.file_body,
+            indoc! {"
+                ## Packing
+
+                - [x] Passport
+
+                - [ ] Sun cream
+
+                - Some **bold** text
+                - [Link](https://example.com)
+
+                ![[attachments/map.png]]"}
+        );
+        assert_eq!(
+            import.attachments,
+            vec![ImportedAttachment {
+                path: PathBuf::from("attachments/map.png"),
+                contents: AttachmentContents::Bytes(b"png".to_vec()),
+            }]
+        );
+    }
+
+    #[test]
+    fn import_enex_maps_metadata_to_properties() {
+        let import = import_enex(&export()).unwrap();
+        let properties = import.notes[0].properties.as_ref().unwrap();
+
+        assert_eq!(properties["created"], "2023-01-15T10:15:00Z");
+        assert_eq!(
+            properties["tags"],
+            Value::Sequence(vec!["travel".into(), "to-do".into()])
+        );
+        assert_eq!(properties["source"], "https://example.com/trip");
+    }
+}
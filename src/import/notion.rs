@@ -0,0 +1,294 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_yaml::{Mapping, Value};
+use walkdir::WalkDir;
+
+use super::{build_note, AttachmentContents, Import, ImportedAttachment};
+use crate::{
+    apply_edits, parse_markdown_links, rename::path_to_link, resolver::normalize, TextEdit,
+};
+
+/// Converts an unzipped Notion "Markdown & CSV" export into notes
+///
+/// The IDs Notion appends to file and folder names are dropped, relative links between exported
+/// files become wikilinks and embeds, and each database row's page gets the row's CSV columns as
+/// properties.
+pub fn import_notion(export_dir: &Path) -> crate::Result<Import> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(export_dir).sort_by_file_name() {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            let relative = entry
+                .path()
+                .strip_prefix(export_dir)
+                .unwrap_or(entry.path());
+            files.push(relative.to_path_buf());
+        }
+    }
+
+    // Pages that share a name once their IDs are gone keep the original name
+    let mut taken = HashSet::new();
+    let cleaned: HashMap<&Path, PathBuf> = files
+        .iter()
+        .map(|file| {
+            let clean = clean_path(file);
+            let path = if taken.insert(clean.clone()) {
+                clean
+            } else {
+                file.clone()
+            };
+            (file.as_path(), path)
+        })
+        .collect();
+
+    let mut rows: HashMap<PathBuf, Vec<(String, String)>> = HashMap::new();
+    for file in files.iter().filter(|file| has_extension(file, "csv")) {
+        let database = cleaned[file.as_path()].with_extension("");
+        let mut reader = csv::Reader::from_path(export_dir.join(file))?;
+        let headers = reader.headers()?.clone();
+        for record in reader.records() {
+            let record = record?;
+            let Some(title) = record.get(0).filter(|title| !title.is_empty()) else {
+                continue;
+            };
+            let columns = headers
+                .iter()
+                .zip(record.iter())
+                .skip(1)
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(column, value)| (column.to_string(), value.to_string()))
+                .collect();
+            rows.insert(database.join(format!("{title}.md")), columns);
+        }
+    }
+
+    let mut import = Import::default();
+    for file in &files {
+        let path = cleaned[file.as_path()].clone();
+        if has_extension(file, "md") {
+            let contents = fs::read_to_string(export_dir.join(file))?;
+            let columns = rows.remove(&path).unwrap_or_default();
+            let title = path.file_stem().unwrap_or_default().to_string_lossy();
+            let body = convert_links(strip_header(&contents, &title, &columns), file, &cleaned);
+
+            let properties: Mapping = columns
+                .into_iter()
+                .map(|(column, value)| (Value::String(column), Value::String(value)))
+                .collect();
+            import.notes.push(build_note(path, properties, &body)?);
+        } else if !has_extension(file, "csv") {
+            import.attachments.push(ImportedAttachment {
+                path,
+                contents: AttachmentContents::File(export_dir.join(file)),
+            });
+        }
+    }
+
+    Ok(import)
+}
+
+fn clean_path(path: &Path) -> PathBuf {
+    path.components()
+        .map(|component| strip_id(&component.as_os_str().to_string_lossy()))
+        .collect()
+}
+
+/// Drops the ` 0123…cdef` ID Notion appends to a name, keeping any extension
+fn strip_id(name: &str) -> String {
+    if let Some(stripped) = without_id(name) {
+        return stripped.to_string();
+    }
+    if let Some((stem, extension)) = name.rsplit_once('.') {
+        // Databases are also exported as `Name <id>_all.csv`, with every row
+        let stripped = without_id(stem).or_else(|| without_id(stem.strip_suffix("_all")?));
+        if let Some(stripped) = stripped {
+            return format!("{stripped}.{extension}");
+        }
+    }
+    name.to_string()
+}
+
+fn without_id(stem: &str) -> Option<&str> {
+    let (name, id) = stem.rsplit_once(' ')?;
+    (id.len() == 32 && id.bytes().all(|b| b.is_ascii_hexdigit())).then_some(name)
+}
+
+/// The text after the `# Title` heading Notion starts pages with, and the `Column: value` lines
+/// it writes under the heading of database rows
+fn strip_header<'a>(text: &'a str, title: &str, columns: &[(String, String)]) -> &'a str {
+    let start = text.trim_start();
+    let (heading, mut rest) = start.split_once('\n').unwrap_or((start, ""));
+    if heading.strip_prefix("# ").map(str::trim) != Some(title) {
+        return text;
+    }
+
+    rest = rest.trim_start();
+    loop {
+        let (line, after) = rest.split_once('\n').unwrap_or((rest, ""));
+        let is_column = line
+            .split_once(": ")
+            .is_some_and(|(key, _)| columns.iter().any(|(column, _)| column == key));
+        if !is_column {
+            return rest;
+        }
+        rest = after;
+    }
+}
+
+/// Rewrites relative markdown links to exported files as wikilinks to their cleaned paths
+fn convert_links(text: &str, note: &Path, cleaned: &HashMap<&Path, PathBuf>) -> String {
+    let dir = note.parent().unwrap_or(Path::new(""));
+    let edits: Vec<TextEdit> = parse_markdown_links(text)
+        .into_iter()
+        .filter_map(|link| {
+            let target = cleaned.get(normalize(&dir.join(link.path()?)).as_path())?;
+            // Databases aren't imported as files, only as their rows' properties
+            if has_extension(target, "csv") {
+                return None;
+            }
+            let (target, name) = if has_extension(target, "md") {
+                (target.with_extension(""), target.file_stem())
+            } else {
+                (target.clone(), target.file_name())
+            };
+            let target = path_to_link(&target);
+            let name = name.unwrap_or_default().to_string_lossy();
+
+            let replacement = if link.is_embed {
+                format!("![[{target}]]")
+            } else if link.text.is_empty() || link.text == name || link.text == target {
+                format!("[[{target}]]")
+            } else {
+                format!("[[{target}|{}]]", link.text)
+            };
+            Some(TextEdit::new(link.span, replacement))
+        })
+        .collect();
+
+    apply_edits(text, &edits)
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Vault;
+    use indoc::indoc;
+
+    const ID: &str = "0123456789abcdef0123456789abcdef";
+    const ROW_ID: &str = "fedcba9876543210fedcba9876543210";
+
+    fn export() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                format!("Home {ID}.md"),
+                format!(
+                    indoc! {"
+                        # Home
+
+                        See [the tasks](Tasks%20{id}.csv) and [Write docs](Tasks%20{id}/Write%20docs%20{row}.md).
+
+                        ![diagram](Home%20{id}/diagram.png)
+                        [Example](https://example.com)
+                    "},
+                    id = ID,
+                    row = ROW_ID
+                ),
+            ),
+            (
+                format!("Tasks {ID}.csv"),
+                "Name,Status,Due\nWrite docs,In progress,\n".to_string(),
+            ),
+            (
+                format!("Tasks {ID}/Write docs {ROW_ID}.md"),
+                format!(
+                    indoc! {"
+                        # Write docs
+
+                        Status: In progress
+
+                        Back to [Home](../Home%20{id}.md)
+                    "},
+                    id = ID
+                ),
+            ),
+            (format!("Home {ID}/diagram.png"), "png".to_string()),
+        ];
+        for (path, contents) in files {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn import_notion_cleans_names_and_converts_links() {
+        let export = export();
+        let import = import_notion(export.path()).unwrap();
+
+        let paths: Vec<_> = import.notes.iter().map(|n| n.file_path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("Home.md"),
+                PathBuf::from("Tasks/Write docs.md")
+            ]
+        );
+        assert_eq!(
+            import.notes[0].file_body,
+            indoc! {"
+                See [the tasks](Tasks%200123456789abcdef0123456789abcdef.csv) and [[Tasks/Write docs]].
+
+                ![[Home/diagram.png]]
+                [Example](https://example.com)"}
+        );
+        assert_eq!(
+            import.attachments,
+            vec![ImportedAttachment {
+                path: PathBuf::from("Home/diagram.png"),
+                contents: AttachmentContents::File(
+                    export.path().join(format!("Home {ID}/diagram.png"))
+                ),
+            }]
+        );
+    }
+
+    #[test]
+    fn import_notion_maps_database_columns_to_properties() {
+        let export = export();
+        let import = import_notion(export.path()).unwrap();
+
+        let row = &import.notes[1];
+        let properties = row.properties.as_ref().unwrap();
+        assert_eq!(properties["Status"], "In progress");
+        assert!(properties.get("Due").is_none());
+        assert_eq!(row.file_body, "Back to [[Home]]");
+    }
+
+    #[test]
+    fn import_writes_into_a_vault() {
+        let export = export();
+        let vault_dir = tempfile::tempdir().unwrap();
+        let import = import_notion(export.path()).unwrap();
+
+        import.write_to(vault_dir.path()).unwrap();
+        assert!(vault_dir.path().join("Home/diagram.png").is_file());
+        let vault = Vault::open(vault_dir.path()).unwrap();
+        assert_eq!(vault.notes().count(), 2);
+
+        assert!(matches!(
+            import.write_to(vault_dir.path()),
+            Err(crate::Error::AlreadyExists(_))
+        ));
+    }
+}
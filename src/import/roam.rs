@@ -0,0 +1,213 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use serde::Deserialize;
+use serde_yaml::Mapping;
+
+use super::{build_note, Import};
+
+#[derive(Debug, Deserialize)]
+struct Page {
+    title: String,
+    #[serde(default)]
+    children: Vec<Block>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Block {
+    #[serde(default)]
+    string: String,
+    uid: Option<String>,
+    #[serde(default)]
+    children: Vec<Block>,
+    heading: Option<usize>,
+}
+
+/// Converts a Roam Research JSON export into one note per page
+///
+/// Blocks become nested list items, top-level heading blocks become headings, and `((uid))`
+/// references become links to a `^block-id` added to the referenced block. Page titles with `/`
+/// are placed in folders, as Roam namespaces are.
+pub fn import_roam(json: &str) -> crate::Result<Import> {
+    let pages: Vec<Page> = serde_json::from_str(json)?;
+
+    let mut blocks = HashMap::new();
+    let mut referenced = HashSet::new();
+    for page in &pages {
+        let mut stack: Vec<&Block> = page.children.iter().collect();
+        while let Some(block) = stack.pop() {
+            if let Some(uid) = &block.uid {
+                blocks.insert(uid.as_str(), page.title.as_str());
+            }
+            referenced.extend(block_refs(&block.string).into_iter().map(|(_, uid)| uid));
+            stack.extend(&block.children);
+        }
+    }
+    let refs = Refs { blocks, referenced };
+
+    let mut import = Import::default();
+    for page in &pages {
+        let mut body = String::new();
+        refs.push_blocks(&mut body, &page.children, 0);
+        let path = PathBuf::from(format!("{}.md", page.title));
+        import.notes.push(build_note(path, Mapping::new(), &body)?);
+    }
+
+    Ok(import)
+}
+
+struct Refs<'a> {
+    /// The title of the page each block is on
+    blocks: HashMap<&'a str, &'a str>,
+    referenced: HashSet<&'a str>,
+}
+
+impl Refs<'_> {
+    fn push_blocks(&self, out: &mut String, blocks: &[Block], depth: usize) {
+        for block in blocks {
+            let mut text = self.convert(&block.string);
+            if let Some(uid) = block
+                .uid
+                .as_deref()
+                .filter(|uid| self.referenced.contains(uid))
+            {
+                text.push_str(" ^");
+                text.push_str(&block_id(uid));
+            }
+
+            match block.heading.filter(|&level| depth == 0 && level > 0) {
+                Some(level) => {
+                    out.push_str(&format!("{} {text}\n", "#".repeat(level)));
+                    self.push_blocks(out, &block.children, 0);
+                }
+                None => {
+                    let indent = "\t".repeat(depth);
+                    let mut lines = text.lines();
+                    out.push_str(&format!("{indent}- {}\n", lines.next().unwrap_or_default()));
+                    for line in lines {
+                        out.push_str(&format!("{indent}  {line}\n"));
+                    }
+                    self.push_blocks(out, &block.children, depth + 1);
+                }
+            }
+        }
+    }
+
+    /// Rewrites Roam-only syntax in a block's text
+    fn convert(&self, text: &str) -> String {
+        let mut converted = String::new();
+        let mut cursor = 0;
+        for (span, uid) in block_refs(text) {
+            let Some(page) = self.blocks.get(uid) else {
+                continue;
+            };
+            let mut start = span.start;
+            let mut end = span.end;
+            // `{{embed: ((uid))}}` and `{{[[embed]]: ((uid))}}`
+            let before = text[cursor..start].trim_end();
+            let embed = ["{{embed:", "{{[[embed]]:"]
+                .iter()
+                .find(|prefix| before.ends_with(*prefix))
+                .filter(|_| text[end..].starts_with("}}"));
+            if let Some(prefix) = embed {
+                start = cursor + before.len() - prefix.len();
+                end += 2;
+            }
+
+            converted.push_str(&text[cursor..start]);
+            let bang = if embed.is_some() { "!" } else { "" };
+            converted.push_str(&format!("{bang}[[{page}#^{}]]", block_id(uid)));
+            cursor = end;
+        }
+        converted.push_str(&text[cursor..]);
+
+        converted
+            .replace("{{[[TODO]]}}", "[ ]")
+            .replace("{{[[DONE]]}}", "[x]")
+            .replace("^^", "==")
+    }
+}
+
+/// The `((uid))` references in a block's text
+fn block_refs(text: &str) -> Vec<(std::ops::Range<usize>, &str)> {
+    let mut refs = Vec::new();
+    let mut cursor = 0;
+    while let Some(offset) = text[cursor..].find("((") {
+        let start = cursor + offset;
+        cursor = start + 2;
+        let Some(len) = text[cursor..].find("))") else {
+            break;
+        };
+        let uid = &text[cursor..cursor + len];
+        if !uid.is_empty()
+            && uid
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            cursor += len + 2;
+            refs.push((start..cursor, uid));
+        }
+    }
+    refs
+}
+
+/// Obsidian block IDs only allow letters, digits, and `-`
+fn block_id(uid: &str) -> String {
+    uid.replace('_', "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    const EXPORT: &str = r#"[
+        {
+            "title": "Project",
+            "children": [
+                {"string": "Overview", "uid": "h1", "heading": 2, "children": [
+                    {"string": "{{[[TODO]]}} Plan with [[Alice]]", "uid": "a_1", "children": [
+                        {"string": "First line\nsecond line", "uid": "a2"}
+                    ]}
+                ]}
+            ]
+        },
+        {
+            "title": "Journal/Monday",
+            "children": [
+                {"string": "See ((a_1)) and ((missing))", "uid": "b1"},
+                {"string": "{{embed: ((a2))}}", "uid": "b2"}
+            ]
+        }
+    ]"#;
+
+    #[test]
+    fn import_roam_nests_blocks_and_headings() {
+        let import = import_roam(EXPORT).unwrap();
+        let project = &import.notes[0];
+
+        assert_eq!(project.file_path, PathBuf::from("Project.md"));
+        assert_eq!(
+            project.file_body,
+            indoc! {"
+                ## Overview
+                - [ ] Plan with [[Alice]] ^a-1
+                \t- First line
+                \t  second line ^a2"}
+        );
+    }
+
+    #[test]
+    fn import_roam_converts_block_refs_and_embeds() {
+        let import = import_roam(EXPORT).unwrap();
+        let journal = &import.notes[1];
+
+        assert_eq!(journal.file_path, PathBuf::from("Journal/Monday.md"));
+        assert_eq!(
+            journal.file_body,
+            "- See [[Project#^a-1]] and ((missing))\n- ![[Project#^a2]]"
+        );
+    }
+}
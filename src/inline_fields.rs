@@ -0,0 +1,232 @@
+use std::ops::Range;
+
+use crate::{
+    code::{code_ranges, in_ranges},
+    ObsidianNote, Properties,
+};
+
+/// A Dataview inline field, either `key:: value` on its own line or `[key:: value]` /
+/// `(key:: value)` within text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineField {
+    pub key: String,
+    pub value: String,
+    pub span: Range<usize>,
+}
+
+impl InlineField {
+    /// The value as a YAML scalar, so numbers and booleans compare like frontmatter values
+    pub fn typed_value(&self) -> Properties {
+        let value = self.value.trim();
+        if let Ok(int) = value.parse::<i64>() {
+            Properties::from(int)
+        } else if let Ok(float) = value.parse::<f64>() {
+            Properties::from(float)
+        } else if let Ok(boolean) = value.parse::<bool>() {
+            Properties::from(boolean)
+        } else {
+            Properties::from(value)
+        }
+    }
+}
+
+impl ObsidianNote {
+    pub fn inline_fields(&self) -> Vec<InlineField> {
+        parse_inline_fields(&self.file_body)
+    }
+
+    /// Frontmatter properties combined with inline fields. Keys that appear more than once
+    /// collect their values into a list, as Dataview does.
+    pub fn metadata(&self) -> Properties {
+        let mut metadata = match &self.properties {
+            Some(Properties::Mapping(mapping)) => mapping.clone(),
+            _ => serde_yaml::Mapping::new(),
+        };
+
+        for field in self.inline_fields() {
+            let key = Properties::from(field.key.as_str());
+            let value = field.typed_value();
+            match metadata.get_mut(&key) {
+                Some(Properties::Sequence(values)) => values.push(value),
+                Some(existing) => {
+                    let first = std::mem::take(existing);
+                    *existing = Properties::Sequence(vec![first, value]);
+                }
+                None => {
+                    metadata.insert(key, value);
+                }
+            }
+        }
+
+        Properties::Mapping(metadata)
+    }
+}
+
+pub fn parse_inline_fields(text: &str) -> Vec<InlineField> {
+    let code = code_ranges(text);
+    let mut fields = Vec::new();
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        if in_ranges(&code, start) {
+            continue;
+        }
+
+        let content = line.trim_end_matches(['\n', '\r']);
+        let bracketed = bracketed_fields(content, start, &code);
+        if bracketed.is_empty() {
+            fields.extend(line_field(content, start));
+        } else {
+            fields.extend(bracketed);
+        }
+    }
+
+    fields
+}
+
+/// A `key:: value` field taking up the whole line, after any list or quote marker
+fn line_field(line: &str, line_start: usize) -> Option<InlineField> {
+    let marker_len = line.len()
+        - line
+            .trim_start_matches(|c: char| c.is_whitespace() || matches!(c, '>' | '-' | '*' | '+'))
+            .len();
+    let content = &line[marker_len..];
+
+    let (key, value) = content.split_once("::")?;
+    let key = clean_key(key)?;
+    Some(InlineField {
+        key,
+        value: value.trim().to_string(),
+        span: line_start + marker_len..line_start + line.len(),
+    })
+}
+
+fn bracketed_fields(line: &str, line_start: usize, code: &[Range<usize>]) -> Vec<InlineField> {
+    let mut fields = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(offset) = line[cursor..].find(['[', '(']) {
+        let start = cursor + offset;
+        cursor = start + 1;
+        if in_ranges(code, line_start + start) {
+            continue;
+        }
+
+        let (open, close) = if line[start..].starts_with('[') {
+            ('[', ']')
+        } else {
+            ('(', ')')
+        };
+        let mut depth = 0;
+        let Some(len) = line[start + 1..].find(|c| {
+            if c == open {
+                depth += 1;
+            } else if c == close {
+                if depth == 0 {
+                    return true;
+                }
+                depth -= 1;
+            }
+            false
+        }) else {
+            continue;
+        };
+
+        let inner = &line[start + 1..start + 1 + len];
+        let Some((key, value)) = inner.split_once("::") else {
+            continue;
+        };
+        let Some(key) = clean_key(key) else {
+            continue;
+        };
+
+        let end = start + len + 2;
+        fields.push(InlineField {
+            key,
+            value: value.trim().to_string(),
+            span: line_start + start..line_start + end,
+        });
+        cursor = end;
+    }
+
+    fields
+}
+
+/// The key without surrounding whitespace or emphasis, rejecting text that isn't a key
+fn clean_key(key: &str) -> Option<String> {
+    let key = key.trim().trim_matches(['*', '_']).trim();
+    if key.is_empty() || key.contains(['[', ']', '(', ')', '`']) {
+        return None;
+    }
+    Some(key.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::path::Path;
+
+    fn keys_and_values(fields: &[InlineField]) -> Vec<(&str, &str)> {
+        fields
+            .iter()
+            .map(|f| (f.key.as_str(), f.value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_inline_fields_reads_line_fields() {
+        let fields = parse_inline_fields(indoc! {r"
+            Status:: active
+            - **Due Date**:: 2024-03-01
+            Regular text: not a field
+        "});
+        assert_eq!(
+            keys_and_values(&fields),
+            vec![("Status", "active"), ("Due Date", "2024-03-01")]
+        );
+    }
+
+    #[test]
+    fn parse_inline_fields_reads_bracketed_fields() {
+        let text = "I rated it [rating:: 9] and (hidden:: yes) with [[a link]]";
+        let fields = parse_inline_fields(text);
+
+        assert_eq!(
+            keys_and_values(&fields),
+            vec![("rating", "9"), ("hidden", "yes")]
+        );
+        assert_eq!(&text[fields[0].span.clone()], "[rating:: 9]");
+    }
+
+    #[test]
+    fn parse_inline_fields_skips_code() {
+        let fields = parse_inline_fields("`key:: value`\n```\nother:: value\n```");
+        assert!(fields.is_empty());
+    }
+
+    #[test]
+    fn metadata_merges_frontmatter_and_fields() {
+        let note = ObsidianNote::parse(
+            Path::new("a.md"),
+            indoc! {r"
+                ---
+                status: draft
+                ---
+                status:: reviewed
+                rating:: 4
+            "}
+            .to_string(),
+        )
+        .unwrap();
+        let metadata = note.metadata();
+
+        assert_eq!(
+            metadata["status"],
+            Properties::Sequence(vec!["draft".into(), "reviewed".into()])
+        );
+        assert_eq!(metadata["rating"], Properties::from(4));
+    }
+}
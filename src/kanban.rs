@@ -0,0 +1,319 @@
+use std::ops::Range;
+
+use chrono::{NaiveDate, NaiveTime};
+
+use crate::{headings::atx_heading, parse_inline_tags, ObsidianNote, TextEdit};
+
+/// A board from the Kanban community plugin, stored as a note with `kanban-plugin` in its
+/// frontmatter
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Kanban {
+    pub lanes: Vec<Lane>,
+    /// Cards under `## Archive`, after the `***` break
+    pub archive: Vec<Card>,
+    /// The board's settings from its `%% kanban:settings` block
+    pub settings: Option<serde_json::Value>,
+}
+
+/// A column of the board, written as a heading
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Lane {
+    pub title: String,
+    /// Whether cards moved here are marked done, from a `**Complete**` line
+    pub complete: bool,
+    pub cards: Vec<Card>,
+}
+
+/// A `- [ ]` list item in a lane
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Card {
+    /// The card's text, metadata included, with continuation lines unindented
+    pub text: String,
+    pub checked: bool,
+}
+
+impl Card {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            checked: false,
+        }
+    }
+
+    /// The date from `@{2024-05-01}`
+    pub fn due(&self) -> Option<NaiveDate> {
+        let (_, date) = self.date_marker()?;
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+    }
+
+    /// The time from `@@{14:30}`
+    pub fn time(&self) -> Option<NaiveTime> {
+        let start = self.text.find("@@{")? + 3;
+        let len = self.text[start..].find('}')?;
+        NaiveTime::parse_from_str(&self.text[start..start + len], "%H:%M").ok()
+    }
+
+    /// Replaces the card's `@{date}`, or removes it for `None`
+    pub fn set_due(&mut self, due: Option<NaiveDate>) {
+        if let Some((range, _)) = self.date_marker() {
+            let before = self.text[..range.start].trim_end();
+            let after = self.text[range.end..].trim_start();
+            self.text = [before, after]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+        if let Some(due) = due {
+            self.text = format!("{} @{{{}}}", self.text, due.format("%Y-%m-%d"));
+        }
+    }
+
+    /// The card's tags, without their `#`
+    pub fn tags(&self) -> Vec<String> {
+        parse_inline_tags(&self.text)
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect()
+    }
+
+    /// The byte range of `@{...}`, skipping `@@{...}` times, and the text inside it
+    fn date_marker(&self) -> Option<(Range<usize>, &str)> {
+        let start = self
+            .text
+            .match_indices("@{")
+            .map(|(i, _)| i)
+            .find(|&i| !self.text[..i].ends_with('@'))?;
+        let len = self.text[start + 2..].find('}')?;
+        let end = start + 2 + len + 1;
+        Some((start..end, &self.text[start + 2..end - 1]))
+    }
+
+    fn to_markdown(&self) -> String {
+        let check = if self.checked { 'x' } else { ' ' };
+        format!("- [{check}] {}", self.text.replace('\n', "\n    "))
+    }
+}
+
+impl Kanban {
+    /// Reads a board from a note body
+    pub fn parse(body: &str) -> crate::Result<Self> {
+        let mut board = Self::default();
+        let mut in_archive = false;
+        let mut lines = body.lines();
+
+        while let Some(line) = lines.next() {
+            let trimmed = line.trim();
+            if trimmed == "%% kanban:settings" {
+                let json: Vec<&str> = lines
+                    .by_ref()
+                    .take_while(|line| line.trim() != "%%")
+                    .filter(|line| !line.trim_start().starts_with("```"))
+                    .collect();
+                board.settings = Some(serde_json::from_str(&json.join("\n"))?);
+                continue;
+            }
+            if trimmed == "***" {
+                in_archive = true;
+                continue;
+            }
+            if let Some((_, title)) = atx_heading(line) {
+                if !in_archive {
+                    board.lanes.push(Lane {
+                        title: title.to_string(),
+                        ..Lane::default()
+                    });
+                }
+                continue;
+            }
+
+            let cards = if in_archive {
+                &mut board.archive
+            } else if let Some(lane) = board.lanes.last_mut() {
+                if trimmed == "**Complete**" {
+                    lane.complete = true;
+                    continue;
+                }
+                &mut lane.cards
+            } else {
+                continue;
+            };
+
+            if let Some(card) = card(line) {
+                cards.push(card);
+            } else if let (Some(card), Some(continuation)) = (cards.last_mut(), continuation(line))
+            {
+                card.text.push('\n');
+                card.text.push_str(continuation);
+            }
+        }
+
+        Ok(board)
+    }
+
+    pub fn lane(&self, title: &str) -> Option<&Lane> {
+        self.lanes.iter().find(|lane| lane.title == title)
+    }
+
+    pub fn lane_mut(&mut self, title: &str) -> Option<&mut Lane> {
+        self.lanes.iter_mut().find(|lane| lane.title == title)
+    }
+
+    /// The board as the plugin writes it, for use as a note body
+    pub fn to_markdown(&self) -> String {
+        let cards = |cards: &[Card]| {
+            cards
+                .iter()
+                .map(Card::to_markdown)
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let mut markdown = String::new();
+        for lane in &self.lanes {
+            markdown.push_str(&format!("## {}\n\n", lane.title));
+            if lane.complete {
+                markdown.push_str("**Complete**\n");
+            }
+            markdown.push_str(&cards(&lane.cards));
+            markdown.push_str("\n\n\n");
+        }
+        if !self.archive.is_empty() {
+            markdown.push_str("***\n\n## Archive\n\n");
+            markdown.push_str(&cards(&self.archive));
+            markdown.push_str("\n\n");
+        }
+        if let Some(settings) = &self.settings {
+            markdown.push_str(&format!("%% kanban:settings\n```\n{settings}\n```\n%%"));
+        }
+        markdown
+    }
+}
+
+impl ObsidianNote {
+    pub fn is_kanban(&self) -> bool {
+        self.properties
+            .as_ref()
+            .is_some_and(|properties| properties.get("kanban-plugin").is_some())
+    }
+
+    /// The note's board, or `None` if it isn't a Kanban board
+    pub fn kanban(&self) -> crate::Result<Option<Kanban>> {
+        if !self.is_kanban() {
+            return Ok(None);
+        }
+        Kanban::parse(&self.file_body)
+            .map(Some)
+            .map_err(|err| err.in_file(&self.file_path))
+    }
+
+    /// Replaces the body with `board`, keeping the frontmatter
+    pub fn set_kanban(&mut self, board: &Kanban) -> crate::Result<()> {
+        let body = 0..self.file_body.len();
+        self.edit_body(&[TextEdit::new(body, board.to_markdown())])
+    }
+}
+
+fn card(line: &str) -> Option<Card> {
+    let rest = line.strip_prefix("- [")?;
+    let mut chars = rest.chars();
+    let status = chars.next()?;
+    let text = chars.as_str().strip_prefix("] ")?;
+    Some(Card {
+        text: text.trim_end().to_string(),
+        checked: status != ' ',
+    })
+}
+
+fn continuation(line: &str) -> Option<&str> {
+    let text = line
+        .strip_prefix('\t')
+        .or_else(|| line.strip_prefix("    "))?;
+    Some(text.trim_end()).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::path::Path;
+
+    const BOARD: &str = indoc! {r#"
+        ---
+
+        kanban-plugin: board
+
+        ---
+
+        ## Todo
+
+        - [ ] Write report @{2024-05-01} #work
+        - [ ] Call Ada @{2024-05-02} @@{14:30}
+            about the #project/launch
+
+
+        ## Done
+
+        **Complete**
+        - [x] Ship v1
+
+
+        ***
+
+        ## Archive
+
+        - [x] Old card
+
+        %% kanban:settings
+        ```
+        {"kanban-plugin":"board"}
+        ```
+        %%"#};
+
+    #[test]
+    fn kanban_reads_lanes_and_card_metadata() {
+        let note = ObsidianNote::parse(Path::new("Board.md"), BOARD.to_string()).unwrap();
+        let board = note.kanban().unwrap().unwrap();
+
+        let titles: Vec<&str> = board.lanes.iter().map(|l| l.title.as_str()).collect();
+        assert_eq!(titles, vec!["Todo", "Done"]);
+        assert!(board.lane("Done").unwrap().complete);
+        assert_eq!(
+            board.archive,
+            vec![Card {
+                text: "Old card".to_string(),
+                checked: true
+            }]
+        );
+        assert_eq!(board.settings.as_ref().unwrap()["kanban-plugin"], "board");
+
+        let todo = &board.lane("Todo").unwrap().cards;
+        assert_eq!(todo[0].due(), NaiveDate::from_ymd_opt(2024, 5, 1));
+        assert_eq!(todo[0].tags(), vec!["work"]);
+        assert_eq!(todo[1].due(), NaiveDate::from_ymd_opt(2024, 5, 2));
+        assert_eq!(todo[1].time(), NaiveTime::from_hms_opt(14, 30, 0));
+        assert_eq!(todo[1].tags(), vec!["project/launch"]);
+        assert!(todo[1].text.ends_with("\nabout the #project/launch"));
+    }
+
+    #[test]
+    fn set_kanban_writes_edits_back() {
+        let mut note = ObsidianNote::parse(Path::new("Board.md"), BOARD.to_string()).unwrap();
+        let mut board = note.kanban().unwrap().unwrap();
+        assert_eq!(note.file_body, board.to_markdown());
+
+        let mut card = board.lane_mut("Todo").unwrap().cards.remove(0);
+        card.checked = true;
+        card.set_due(NaiveDate::from_ymd_opt(2024, 6, 1));
+        board.lane_mut("Done").unwrap().cards.push(card);
+        note.set_kanban(&board).unwrap();
+
+        let board = note.kanban().unwrap().unwrap();
+        let done = &board.lane("Done").unwrap().cards;
+        assert_eq!(done[1].text, "Write report #work @{2024-06-01}");
+        assert!(done[1].checked);
+        assert!(note
+            .to_string()
+            .starts_with("---\n\nkanban-plugin: board\n\n---\n"));
+    }
+}
@@ -1,3 +1,138 @@
+pub mod appearance;
+pub mod ast;
+#[cfg(feature = "tokio")]
+pub mod async_io;
+pub mod attachments;
+pub mod backlinks;
+pub mod blocks;
+pub mod bookmarks;
+pub mod broken_links;
+pub mod bulk;
+#[cfg(feature = "sqlite")]
+pub mod cache;
+pub mod callouts;
+pub mod canvas;
+pub mod code;
+pub mod comments;
+pub mod config;
+pub mod conflicts;
+mod create;
+pub mod daily;
+pub mod diagrams;
+pub mod edit;
+pub mod embeds;
+pub mod error;
+pub mod excalidraw;
+pub mod excluded;
+pub mod export;
+pub mod external_links;
+pub mod footnotes;
+mod frontmatter;
+pub mod graph;
+pub mod headings;
+pub mod highlights;
+pub mod import;
+pub mod inline_fields;
+pub mod kanban;
+pub mod link_counts;
+pub mod link_syntax;
+pub mod links;
+pub mod math;
+pub mod mentions;
+pub mod moment;
 pub mod obsidian_note;
+pub mod orphans;
+#[cfg(feature = "rayon")]
+mod parallel;
+pub mod plugins;
+pub mod query;
+pub mod quotes;
+mod rename;
+pub mod render;
+pub mod resolver;
+pub mod schema;
+pub mod search;
+pub mod split;
+pub mod stats;
+pub mod tables;
+pub mod tags;
+pub mod tasks;
+pub mod templater;
+pub mod templates;
+#[cfg(test)]
+mod test_support;
+pub mod title;
+pub mod toc;
+pub mod transaction;
+pub mod trash;
+pub mod uri;
+pub mod vault;
+#[cfg(feature = "watch")]
+pub mod watch;
+pub mod workspace;
+pub mod writer;
+pub mod zettel;
 
+pub use crate::appearance::*;
+pub use crate::ast::*;
+#[cfg(feature = "tokio")]
+pub use crate::async_io::*;
+pub use crate::backlinks::*;
+pub use crate::blocks::*;
+pub use crate::bookmarks::*;
+pub use crate::broken_links::*;
+pub use crate::bulk::*;
+#[cfg(feature = "sqlite")]
+pub use crate::cache::*;
+pub use crate::callouts::*;
+pub use crate::canvas::*;
+pub use crate::code::*;
+pub use crate::comments::*;
+pub use crate::config::*;
+pub use crate::conflicts::*;
+pub use crate::daily::*;
+pub use crate::diagrams::*;
+pub use crate::edit::*;
+pub use crate::embeds::*;
+pub use crate::error::*;
+pub use crate::excalidraw::*;
+pub use crate::excluded::*;
+pub use crate::external_links::*;
+pub use crate::footnotes::*;
+pub use crate::graph::*;
+pub use crate::headings::*;
+pub use crate::highlights::*;
+pub use crate::inline_fields::*;
+pub use crate::kanban::*;
+pub use crate::link_counts::*;
+pub use crate::link_syntax::*;
+pub use crate::links::*;
+pub use crate::math::*;
+pub use crate::mentions::*;
+pub use crate::moment::*;
 pub use crate::obsidian_note::*;
+pub use crate::orphans::*;
+pub use crate::plugins::*;
+pub use crate::query::*;
+pub use crate::quotes::*;
+pub use crate::render::*;
+pub use crate::resolver::*;
+pub use crate::schema::*;
+pub use crate::search::*;
+pub use crate::stats::*;
+pub use crate::tables::*;
+pub use crate::tags::*;
+pub use crate::tasks::*;
+pub use crate::templater::*;
+pub use crate::templates::*;
+pub use crate::title::*;
+pub use crate::toc::*;
+pub use crate::transaction::*;
+pub use crate::trash::*;
+pub use crate::uri::*;
+pub use crate::vault::*;
+#[cfg(feature = "watch")]
+pub use crate::watch::*;
+pub use crate::workspace::*;
+pub use crate::writer::*;
+pub use crate::zettel::*;
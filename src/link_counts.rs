@@ -0,0 +1,101 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::{links::scan_links, LinkResolver, Vault};
+
+/// Link counts keyed by source note then target, shaped like the `resolvedLinks` and
+/// `unresolvedLinks` maps in Obsidian's metadata cache. Source paths are relative to the vault.
+pub type LinkCounts<T> = BTreeMap<PathBuf, BTreeMap<T, usize>>;
+
+impl Vault {
+    /// How many times each note links or embeds each file that exists, by vault-relative path.
+    /// Every note has an entry, even if it has no links.
+    pub fn resolved_links(&self) -> crate::Result<LinkCounts<PathBuf>> {
+        Ok(self.link_counts()?.0)
+    }
+
+    /// How many times each note links to each target that doesn't exist, by link text. Every note
+    /// has an entry, even if all its links resolve.
+    pub fn unresolved_links(&self) -> crate::Result<LinkCounts<String>> {
+        Ok(self.link_counts()?.1)
+    }
+
+    fn link_counts(&self) -> crate::Result<(LinkCounts<PathBuf>, LinkCounts<String>)> {
+        let notes = self.notes().collect::<crate::Result<Vec<_>>>()?;
+        let resolver = LinkResolver::from_vault_notes(self, &notes)?;
+        let mut resolved = LinkCounts::new();
+        let mut unresolved = LinkCounts::new();
+
+        for note in &notes {
+            let source = self.relative_path(&note.file_path).to_path_buf();
+            let resolved = resolved.entry(source.clone()).or_default();
+            let unresolved = unresolved.entry(source).or_default();
+            for (_, link) in scan_links(&note.file_body) {
+                match resolver.resolve_link(&link, &note.file_path) {
+                    Some(path) => {
+                        *resolved
+                            .entry(self.relative_path(path).to_path_buf())
+                            .or_default() += 1
+                    }
+                    None => *unresolved.entry(link.target).or_default() += 1,
+                }
+            }
+        }
+
+        Ok((resolved, unresolved))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn link_counts_split_resolved_and_unresolved_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        for (path, contents) in [
+            (
+                "a.md",
+                "[[b]] [[b|again]] ![[image.png]] [[#Top]] [[Missing]] [m](missing.md) [[missing]]",
+            ),
+            ("sub/b.md", "[Up](../a.md) [web](https://example.com)"),
+            ("image.png", ""),
+        ] {
+            fs::write(dir.path().join(path), contents).unwrap();
+        }
+        let vault = Vault::open(dir.path()).unwrap();
+
+        assert_eq!(
+            vault.resolved_links().unwrap(),
+            BTreeMap::from([
+                (
+                    PathBuf::from("a.md"),
+                    BTreeMap::from([
+                        (PathBuf::from("a.md"), 1),
+                        (PathBuf::from("image.png"), 1),
+                        (PathBuf::from("sub/b.md"), 2),
+                    ])
+                ),
+                (
+                    PathBuf::from("sub/b.md"),
+                    BTreeMap::from([(PathBuf::from("a.md"), 1)])
+                ),
+            ])
+        );
+        assert_eq!(
+            vault.unresolved_links().unwrap(),
+            BTreeMap::from([
+                (
+                    PathBuf::from("a.md"),
+                    BTreeMap::from([
+                        ("Missing".to_string(), 1),
+                        ("missing".to_string(), 1),
+                        ("missing.md".to_string(), 1),
+                    ])
+                ),
+                (PathBuf::from("sub/b.md"), BTreeMap::new()),
+            ])
+        );
+    }
+}
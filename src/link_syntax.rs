@@ -0,0 +1,330 @@
+use std::path::{Path, PathBuf};
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode};
+
+use crate::{
+    edit::TextEdit,
+    links::{parse_markdown_links, scan_wikilinks},
+    rename::{link_name, path_to_link, DESTINATION},
+    resolver::relative_to,
+    vault::is_note,
+    FileChange, LinkResolver, NewLinkFormat, ObsidianNote, Vault, WikiLink,
+};
+
+/// The two ways to write an internal link
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LinkSyntax {
+    /// `[[Note#Heading|text]]`
+    #[default]
+    Wikilink,
+    /// `[text](Note.md#Heading)`
+    Markdown,
+}
+
+/// How new links are written, from the vault's `useMarkdownLinks` and `newLinkFormat` settings
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LinkStyle {
+    pub syntax: LinkSyntax,
+    pub format: NewLinkFormat,
+}
+
+impl LinkStyle {
+    /// A link or embed from `source` to `target`, showing `text` when it isn't empty and differs
+    /// from the link's own
+    pub(crate) fn link(
+        &self,
+        vault: &Vault,
+        resolver: &LinkResolver,
+        target: &Path,
+        source: &Path,
+        text: &str,
+        is_embed: bool,
+    ) -> String {
+        let bang = if is_embed { "!" } else { "" };
+        match self.syntax {
+            LinkSyntax::Wikilink => {
+                let link = self.wikilink_target(vault, resolver, target, source);
+                if text.is_empty() || text == link {
+                    format!("{bang}[[{link}]]")
+                } else {
+                    format!("{bang}[[{link}|{text}]]")
+                }
+            }
+            LinkSyntax::Markdown => {
+                let path = path_to_link(&self.path(vault, resolver, target, source));
+                let destination = utf8_percent_encode(&path, DESTINATION);
+                let text = match text {
+                    "" if !is_embed => target.file_stem().unwrap_or_default().to_string_lossy(),
+                    text => text.into(),
+                };
+                format!("{bang}[{text}]({destination})")
+            }
+        }
+    }
+
+    /// The target of a wikilink from `source` to `target`, which leaves out `.md`
+    pub(crate) fn wikilink_target(
+        &self,
+        vault: &Vault,
+        resolver: &LinkResolver,
+        target: &Path,
+        source: &Path,
+    ) -> String {
+        let path = self.path(vault, resolver, target, source);
+        path_to_link(&if is_note(target) {
+            path.with_extension("")
+        } else {
+            path
+        })
+    }
+
+    fn path(
+        &self,
+        vault: &Vault,
+        resolver: &LinkResolver,
+        target: &Path,
+        source: &Path,
+    ) -> PathBuf {
+        let shares_name = || {
+            let name = link_name(target);
+            resolver
+                .files()
+                .iter()
+                .any(|file| file != target && link_name(file) == name)
+        };
+        match self.format {
+            NewLinkFormat::Shortest if !shares_name() => {
+                PathBuf::from(target.file_name().unwrap_or_default())
+            }
+            NewLinkFormat::Shortest | NewLinkFormat::Absolute => {
+                vault.relative_path(target).to_path_buf()
+            }
+            NewLinkFormat::Relative => {
+                relative_to(target, source.parent().unwrap_or(Path::new("")))
+            }
+        }
+    }
+}
+
+impl Vault {
+    pub fn link_style(&self) -> crate::Result<LinkStyle> {
+        Ok(self.config()?.app.link_style())
+    }
+
+    /// Rewrites every internal link and embed outside code in `syntax`, for moving a vault to or
+    /// from other markdown tools. Returns the paths of the notes that changed.
+    ///
+    /// Markdown links use paths relative to the linking note. Wikilinks use the shortest target
+    /// that's unique in the vault, and markdown links that don't resolve are left alone.
+    pub fn convert_links(&self, syntax: LinkSyntax) -> crate::Result<Vec<PathBuf>> {
+        let notes = self.notes().collect::<crate::Result<Vec<_>>>()?;
+        let resolver = LinkResolver::from_vault_notes(self, &notes)?;
+
+        let mut changes = Vec::new();
+        for mut note in notes {
+            let edits = match syntax {
+                LinkSyntax::Markdown => to_markdown_links(&note, &resolver),
+                LinkSyntax::Wikilink => to_wikilinks(self, &note, &resolver),
+            };
+            if edits.is_empty() {
+                continue;
+            }
+            note.edit_body(&edits)?;
+            changes.push(FileChange::Write {
+                path: note.file_path.clone(),
+                contents: note.to_string(),
+            });
+        }
+
+        let updated = changes
+            .iter()
+            .map(|change| change.path().to_path_buf())
+            .collect();
+        self.writer.apply(changes)?;
+        Ok(updated)
+    }
+}
+
+fn to_markdown_links(note: &ObsidianNote, resolver: &LinkResolver) -> Vec<TextEdit> {
+    let source_dir = note.file_path.parent().unwrap_or(Path::new(""));
+
+    scan_wikilinks(&note.file_body)
+        .into_iter()
+        .map(|(is_embed, link)| {
+            let path = if link.target.is_empty() {
+                String::new()
+            } else {
+                match resolver.resolve_link(&link, &note.file_path) {
+                    Some(target) => path_to_link(&relative_to(target, source_dir)),
+                    None if Path::new(&link.target).extension().is_some() => link.target.clone(),
+                    None => format!("{}.md", link.target),
+                }
+            };
+            let mut destination = utf8_percent_encode(&path, DESTINATION).to_string();
+            if let Some(fragment) = fragment(&link) {
+                destination.push('#');
+                destination.extend(utf8_percent_encode(&fragment, DESTINATION));
+            }
+
+            let text = match &link.alias {
+                Some(alias) => alias.clone(),
+                None if is_embed => String::new(),
+                None => display_text(&link),
+            };
+            let text = text.replace('[', "\\[").replace(']', "\\]");
+            let bang = if is_embed { "!" } else { "" };
+            TextEdit::new(link.span, format!("{bang}[{text}]({destination})"))
+        })
+        .collect()
+}
+
+fn to_wikilinks(vault: &Vault, note: &ObsidianNote, resolver: &LinkResolver) -> Vec<TextEdit> {
+    let mut edits = Vec::new();
+
+    for link in parse_markdown_links(&note.file_body) {
+        if link.is_external() {
+            continue;
+        }
+        let target = match link.path() {
+            Some(path) => match resolver.resolve(&path, &note.file_path) {
+                Some(target) => {
+                    LinkStyle::default().wikilink_target(vault, resolver, target, &note.file_path)
+                }
+                None => continue,
+            },
+            None if link.destination.starts_with('#') => String::new(),
+            None => continue,
+        };
+
+        let fragment = link.fragment().filter(|f| !f.is_empty());
+        let fragment = fragment.map(|f| percent_decode_str(f).decode_utf8_lossy());
+        let mut inner = target.clone();
+        let mut display = target.clone();
+        if let Some(fragment) = fragment {
+            inner = format!("{inner}#{fragment}");
+            display = if display.is_empty() {
+                fragment.into_owned()
+            } else {
+                format!("{display} > {fragment}")
+            };
+        }
+        let text = link.text.replace("\\[", "[").replace("\\]", "]");
+        let keep_text = if link.is_embed {
+            !text.is_empty()
+        } else {
+            ![target.as_str(), &inner, &display, ""].contains(&text.as_str())
+        };
+        if keep_text {
+            inner = format!("{inner}|{text}");
+        }
+
+        let bang = if link.is_embed { "!" } else { "" };
+        edits.push(TextEdit::new(link.span, format!("{bang}[[{inner}]]")));
+    }
+
+    edits
+}
+
+/// The `#Heading` or `#^block` part of a wikilink, without the `#`
+fn fragment(link: &WikiLink) -> Option<String> {
+    match (&link.heading, &link.block) {
+        (_, Some(block)) => Some(format!("^{block}")),
+        (Some(heading), None) => Some(heading.clone()),
+        (None, None) => None,
+    }
+}
+
+/// What Obsidian shows for a wikilink without an alias, as in `Note > Heading`
+fn display_text(link: &WikiLink) -> String {
+    let parts = [Some(link.target.clone()), fragment(link)];
+    parts
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" > ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::vault_with_files;
+    use crate::AppConfig;
+    use std::fs;
+
+    #[test]
+    fn convert_links_round_trips() {
+        let wikilinks = "[[My Note]] [[My Note#Some Heading|see here]] ![[photo.png|200]] [[#Top]] [[Missing]] `[[Code]]`\n";
+        let (dir, vault) = vault_with_files(&[
+            ("notes/source.md", wikilinks),
+            ("notes/sub/My Note.md", ""),
+            ("photo.png", ""),
+        ]);
+
+        let updated = vault.convert_links(LinkSyntax::Markdown).unwrap();
+        assert_eq!(updated, vec![dir.path().join("notes/source.md")]);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("notes/source.md")).unwrap(),
+            "[My Note](sub/My%20Note.md) [see here](sub/My%20Note.md#Some%20Heading) ![200](../photo.png) [Top](#Top) [Missing](Missing.md) `[[Code]]`\n"
+        );
+
+        vault.convert_links(LinkSyntax::Wikilink).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("notes/source.md")).unwrap(),
+            wikilinks.replace("[[Missing]]", "[Missing](Missing.md)")
+        );
+    }
+
+    #[test]
+    fn link_style_follows_app_config() {
+        let (dir, vault) =
+            vault_with_files(&[("a/Note.md", ""), ("b/Note.md", ""), ("b/My Photo.png", "")]);
+        let resolver = vault.link_resolver().unwrap();
+        let source = dir.path().join("a/Note.md");
+        let link = |style: LinkStyle, target: &str, text: &str, is_embed: bool| {
+            style.link(
+                &vault,
+                &resolver,
+                &dir.path().join(target),
+                &source,
+                text,
+                is_embed,
+            )
+        };
+
+        let shortest = LinkStyle::default();
+        assert_eq!(link(shortest, "b/Note.md", "", false), "[[b/Note]]");
+        assert_eq!(
+            link(shortest, "b/My Photo.png", "", true),
+            "![[My Photo.png]]"
+        );
+
+        let app: AppConfig =
+            serde_json::from_str(r#"{"useMarkdownLinks": true, "newLinkFormat": "relative"}"#)
+                .unwrap();
+        let relative = app.link_style();
+        assert_eq!(
+            link(relative, "b/My Photo.png", "", true),
+            "![](../b/My%20Photo.png)"
+        );
+        assert_eq!(
+            link(relative, "b/Note.md", "note", false),
+            "[note](../b/Note.md)"
+        );
+    }
+
+    #[test]
+    fn wikilinks_use_paths_for_shared_names() {
+        let (dir, vault) = vault_with_files(&[
+            ("a/Note.md", ""),
+            ("b/Note.md", ""),
+            ("source.md", "[Note](b/Note.md) [other](<a/Note.md>)"),
+        ]);
+
+        vault.convert_links(LinkSyntax::Wikilink).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("source.md")).unwrap(),
+            "[[b/Note|Note]] [[a/Note|other]]"
+        );
+    }
+}
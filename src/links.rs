@@ -0,0 +1,367 @@
+use std::ops::Range;
+
+use percent_encoding::percent_decode_str;
+
+use crate::{
+    code::{code_ranges, in_ranges},
+    ObsidianNote,
+};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WikiLink {
+    pub target: String,
+    pub heading: Option<String>,
+    /// Block reference, from either `[[Note#^id]]` or `[[Note^id]]`
+    pub block: Option<String>,
+    pub alias: Option<String>,
+    /// Byte range of the whole `[[...]]` (and leading `!` for embeds) within the parsed text
+    pub span: Range<usize>,
+}
+
+impl WikiLink {
+    fn parse(inner: &str, span: Range<usize>) -> Self {
+        let (destination, alias) = match inner.split_once('|') {
+            Some((destination, alias)) => (destination, Some(alias.trim().to_string())),
+            None => (inner, None),
+        };
+        let (destination, block) = match destination.split_once('^') {
+            Some((destination, block)) => (destination, Some(block.trim().to_string())),
+            None => (destination, None),
+        };
+        let (target, heading) = match destination.split_once('#') {
+            Some((target, "")) => (target, None),
+            Some((target, heading)) => (target, Some(heading.trim().to_string())),
+            None => (destination, None),
+        };
+
+        Self {
+            target: target.trim().to_string(),
+            heading,
+            block,
+            alias,
+            span,
+        }
+    }
+}
+
+/// A standard markdown link or image, `[text](destination)` or `![alt](destination)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownLink {
+    pub text: String,
+    /// The destination as written, without surrounding `<>`
+    pub destination: String,
+    pub is_embed: bool,
+    pub span: Range<usize>,
+    pub destination_span: Range<usize>,
+}
+
+impl MarkdownLink {
+    /// Whether the destination has a URL scheme such as `https:` or `mailto:`
+    pub fn is_external(&self) -> bool {
+        url_scheme(&self.destination).is_some()
+    }
+
+    /// The percent-decoded destination without its `#fragment`, for links to vault files
+    pub fn path(&self) -> Option<String> {
+        if self.is_external() {
+            return None;
+        }
+
+        let path = self.destination.split('#').next().unwrap_or_default();
+        let decoded = percent_decode_str(path).decode_utf8_lossy().into_owned();
+        Some(decoded).filter(|p| !p.is_empty())
+    }
+
+    pub fn fragment(&self) -> Option<&str> {
+        self.destination
+            .split_once('#')
+            .map(|(_, fragment)| fragment)
+    }
+
+    /// The wikilink an internal link is equivalent to, with its text as the alias
+    pub fn to_wikilink(&self) -> Option<WikiLink> {
+        let fragment = self
+            .fragment()
+            .map(|fragment| {
+                percent_decode_str(fragment)
+                    .decode_utf8_lossy()
+                    .into_owned()
+            })
+            .filter(|fragment| !fragment.is_empty());
+        let (heading, block) = match fragment {
+            Some(fragment) => match fragment.strip_prefix('^') {
+                Some(block) => (None, Some(block.to_string())),
+                None => (Some(fragment), None),
+            },
+            None => (None, None),
+        };
+
+        Some(WikiLink {
+            target: self.path()?,
+            heading,
+            block,
+            alias: Some(self.text.clone()).filter(|text| !text.is_empty()),
+            span: self.span.clone(),
+        })
+    }
+}
+
+impl ObsidianNote {
+    pub fn links(&self) -> Vec<WikiLink> {
+        parse_wikilinks(&self.file_body)
+    }
+
+    pub fn markdown_links(&self) -> Vec<MarkdownLink> {
+        parse_markdown_links(&self.file_body)
+    }
+}
+
+pub fn parse_wikilinks(text: &str) -> Vec<WikiLink> {
+    scan_wikilinks(text)
+        .into_iter()
+        .filter_map(|(is_embed, link)| (!is_embed).then_some(link))
+        .collect()
+}
+
+/// Every wikilink and embed, then every internal markdown link as a wikilink, flagging embeds
+pub(crate) fn scan_links(text: &str) -> Vec<(bool, WikiLink)> {
+    let markdown_links = parse_markdown_links(text)
+        .into_iter()
+        .filter_map(|link| Some((link.is_embed, link.to_wikilink()?)));
+    scan_wikilinks(text)
+        .into_iter()
+        .chain(markdown_links)
+        .collect()
+}
+
+/// Finds every `[[...]]` outside code, flagging the ones preceded by `!` as embeds
+pub(crate) fn scan_wikilinks(text: &str) -> Vec<(bool, WikiLink)> {
+    let code = code_ranges(text);
+    let mut links = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(offset) = text[cursor..].find("[[") {
+        let start = cursor + offset;
+        let inner_start = start + 2;
+        if in_ranges(&code, start) {
+            cursor = inner_start;
+            continue;
+        }
+        let Some(inner_len) = text[inner_start..].find("]]") else {
+            break;
+        };
+        let inner = &text[inner_start..inner_start + inner_len];
+        let end = inner_start + inner_len + 2;
+
+        // Links can't span lines, and a nested `[[` means this one was never closed
+        if inner.contains('\n') || inner.contains("[[") {
+            cursor = inner_start;
+            continue;
+        }
+
+        if !inner.trim().is_empty() {
+            let is_embed = text[..start].ends_with('!');
+            let span_start = if is_embed { start - 1 } else { start };
+            links.push((is_embed, WikiLink::parse(inner, span_start..end)));
+        }
+        cursor = end;
+    }
+
+    links
+}
+
+/// Finds markdown links and images outside code
+pub fn parse_markdown_links(text: &str) -> Vec<MarkdownLink> {
+    let code = code_ranges(text);
+    let mut links = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(offset) = text[cursor..].find('[') {
+        let start = cursor + offset;
+        cursor = start + 1;
+        if in_ranges(&code, start) {
+            continue;
+        }
+
+        // Skip wikilinks and footnotes
+        if text[cursor..].starts_with(['[', '^']) || text[..start].ends_with('[') {
+            continue;
+        }
+        let Some(link) = markdown_link_at(text, start) else {
+            continue;
+        };
+
+        cursor = link.span.end;
+        links.push(link);
+    }
+
+    links
+}
+
+fn markdown_link_at(text: &str, start: usize) -> Option<MarkdownLink> {
+    let text_start = start + 1;
+    let mut depth = 0;
+    let text_len = text[text_start..].find(|c| {
+        match c {
+            '[' => depth += 1,
+            ']' if depth == 0 => return true,
+            ']' => depth -= 1,
+            _ => {}
+        }
+        c == '\n'
+    })?;
+    let text_end = text_start + text_len;
+    if !text[text_end..].starts_with("](") {
+        return None;
+    }
+
+    let open = text_end + 2;
+    let rest = &text[open..];
+    let (destination_span, close) = if let Some(inner) = rest.strip_prefix('<') {
+        let len = inner.find(['>', '\n'])?;
+        inner[len..].starts_with('>').then_some(())?;
+        let after = open + 1 + len + 1;
+        (open + 1..open + 1 + len, after + text[after..].find(')')?)
+    } else {
+        let mut depth = 0;
+        let len = rest.find(|c: char| {
+            match c {
+                '(' => depth += 1,
+                ')' if depth == 0 => return true,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            c.is_whitespace()
+        })?;
+        let after = open + len;
+        let close = after + text[after..].find([')', '\n'])?;
+        (open..after, close)
+    };
+    if !text[close..].starts_with(')') {
+        return None;
+    }
+
+    let is_embed = text[..start].ends_with('!');
+    Some(MarkdownLink {
+        text: text[text_start..text_end].to_string(),
+        destination: text[destination_span.clone()].to_string(),
+        is_embed,
+        span: if is_embed { start - 1 } else { start }..close + 1,
+        destination_span,
+    })
+}
+
+pub(crate) fn url_scheme(destination: &str) -> Option<&str> {
+    let (scheme, _) = destination.split_once(':')?;
+    let valid = scheme.len() > 1
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then_some(scheme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::path::PathBuf;
+
+    #[test]
+    fn link_parsers_skip_code() {
+        let text = "[[Real]] `[[Inline]]` [a](a.md)\n```\n[[Fenced]] [b](b.md)\n```\n";
+        assert_eq!(parse_wikilinks(text).len(), 1);
+        assert_eq!(parse_markdown_links(text).len(), 1);
+    }
+
+    #[test]
+    fn parse_wikilinks_handles_plain_links() {
+        let links = parse_wikilinks("See [[Some Note]] for details");
+        assert_eq!(
+            links,
+            vec![WikiLink {
+                target: "Some Note".to_string(),
+                heading: None,
+                block: None,
+                alias: None,
+                span: 4..17,
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_wikilinks_handles_aliases_and_headings() {
+        let links = parse_wikilinks("[[Target|Display]] and [[Target#Heading|Display]]");
+
+        assert_eq!(links[0].target, "Target");
+        assert_eq!(links[0].alias.as_deref(), Some("Display"));
+        assert_eq!(links[0].heading, None);
+
+        assert_eq!(links[1].target, "Target");
+        assert_eq!(links[1].heading.as_deref(), Some("Heading"));
+        assert_eq!(links[1].alias.as_deref(), Some("Display"));
+    }
+
+    #[test]
+    fn parse_wikilinks_handles_same_note_headings() {
+        let links = parse_wikilinks("[[#Heading]]");
+        assert_eq!(links[0].target, "");
+        assert_eq!(links[0].heading.as_deref(), Some("Heading"));
+    }
+
+    #[test]
+    fn parse_wikilinks_handles_block_references() {
+        let links = parse_wikilinks("[[Note#^abc123]] [[Note^def456]]");
+        assert_eq!(links[0].target, "Note");
+        assert_eq!(links[0].heading, None);
+        assert_eq!(links[0].block.as_deref(), Some("abc123"));
+        assert_eq!(links[1].block.as_deref(), Some("def456"));
+    }
+
+    #[test]
+    fn parse_wikilinks_skips_embeds_and_unclosed_links() {
+        let links = parse_wikilinks("![[image.png]] [[unclosed\n[[Closed]]");
+        let targets: Vec<&str> = links.iter().map(|l| l.target.as_str()).collect();
+        assert_eq!(targets, vec!["Closed"]);
+    }
+
+    #[test]
+    fn parse_markdown_links_handles_links_and_images() {
+        let text =
+            "[Note](My%20Note.md#Heading) ![alt](<images/a b.png>) [site](https://example.com)";
+        let links = parse_markdown_links(text);
+
+        assert_eq!(links[0].text, "Note");
+        assert_eq!(links[0].path().as_deref(), Some("My Note.md"));
+        assert_eq!(links[0].fragment(), Some("Heading"));
+        assert!(!links[0].is_external());
+
+        assert!(links[1].is_embed);
+        assert_eq!(links[1].path().as_deref(), Some("images/a b.png"));
+        assert_eq!(&text[links[1].span.clone()], "![alt](<images/a b.png>)");
+
+        assert!(links[2].is_external());
+        assert_eq!(links[2].path(), None);
+    }
+
+    #[test]
+    fn parse_markdown_links_skips_wikilinks_and_footnotes() {
+        let links = parse_markdown_links("[[Note]] [^1] [not a link] [x](a.md \"Title\")");
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].destination, "a.md");
+    }
+
+    #[test]
+    fn links_reads_from_body() {
+        let note_content = indoc! {r"
+            ---
+            related: foo
+            ---
+            Links to [[Other]]
+        "};
+        let note =
+            ObsidianNote::parse(&PathBuf::from("a-note.md"), note_content.to_string()).unwrap();
+
+        assert_eq!(note.links()[0].target, "Other");
+    }
+}
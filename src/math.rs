@@ -0,0 +1,125 @@
+use std::ops::Range;
+
+use crate::{
+    code::{code_ranges, in_ranges},
+    ObsidianNote,
+};
+
+/// A LaTeX math segment, `$inline$` or `$$display$$`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Math {
+    /// The TeX between the dollar signs, trimmed
+    pub tex: String,
+    pub display: bool,
+    /// Byte range including the dollar signs
+    pub span: Range<usize>,
+}
+
+impl ObsidianNote {
+    pub fn math(&self) -> Vec<Math> {
+        parse_math(&self.file_body)
+    }
+}
+
+/// Finds math outside code. Escaped `\$` doesn't count, and inline math has to hug its dollar
+/// signs, so prices like `$5 and $10` aren't math.
+pub fn parse_math(text: &str) -> Vec<Math> {
+    let code = code_ranges(text);
+    let mut math = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(offset) = text[cursor..].find('$') {
+        let start = cursor + offset;
+        cursor = start + 1;
+        if in_ranges(&code, start) || is_escaped(text, start) {
+            continue;
+        }
+
+        let found = if text[start..].starts_with("$$") {
+            display_at(text, start, &code)
+        } else {
+            inline_at(text, start, &code)
+        };
+        if let Some(segment) = found {
+            cursor = segment.span.end;
+            math.push(segment);
+        }
+    }
+
+    math
+}
+
+/// Byte ranges of math, for transforms that should leave it alone
+pub(crate) fn math_ranges(text: &str) -> Vec<Range<usize>> {
+    parse_math(text).into_iter().map(|math| math.span).collect()
+}
+
+fn display_at(text: &str, start: usize, code: &[Range<usize>]) -> Option<Math> {
+    let inner_start = start + 2;
+    let inner_end = closing(text, inner_start, "$$", code)?;
+    Some(Math {
+        tex: text[inner_start..inner_end].trim().to_string(),
+        display: true,
+        span: start..inner_end + 2,
+    })
+}
+
+fn inline_at(text: &str, start: usize, code: &[Range<usize>]) -> Option<Math> {
+    let inner_start = start + 1;
+    if text[inner_start..].starts_with(char::is_whitespace) {
+        return None;
+    }
+    let line_end = text[inner_start..]
+        .find('\n')
+        .map_or(text.len(), |len| inner_start + len);
+    let inner_end = closing(&text[..line_end], inner_start, "$", code)?;
+
+    let inner = &text[inner_start..inner_end];
+    let followed_by_digit = text[inner_end + 1..].starts_with(|c: char| c.is_ascii_digit());
+    (!inner.is_empty() && !inner.ends_with(char::is_whitespace) && !followed_by_digit).then(|| {
+        Math {
+            tex: inner.to_string(),
+            display: false,
+            span: start..inner_end + 1,
+        }
+    })
+}
+
+/// The first unescaped `delimiter` from `from` that isn't in code
+fn closing(text: &str, from: usize, delimiter: &str, code: &[Range<usize>]) -> Option<usize> {
+    text[from..]
+        .match_indices(delimiter)
+        .map(|(offset, _)| from + offset)
+        .find(|&end| !is_escaped(text, end) && !in_ranges(code, end))
+}
+
+fn is_escaped(text: &str, position: usize) -> bool {
+    let backslashes = text[..position].len() - text[..position].trim_end_matches('\\').len();
+    backslashes % 2 == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn parse_math_finds_inline_and_display_math() {
+        let text = indoc! {r"
+            Euler: $e^{i\pi} + 1 = 0$, which costs $5 and $10.
+            Escaped \$x$ and `$code$` aren't math.
+            $$
+            \int_0^1 x\,dx
+            $$
+        "};
+
+        let math = parse_math(text);
+        let summary: Vec<(&str, bool)> = math.iter().map(|m| (m.tex.as_str(), m.display)).collect();
+        assert_eq!(
+            summary,
+            vec![("e^{i\\pi} + 1 = 0", false), ("\\int_0^1 x\\,dx", true)]
+        );
+        assert_eq!(&text[math[0].span.clone()], "$e^{i\\pi} + 1 = 0$");
+        assert!(text[math[1].span.clone()].starts_with("$$\n"));
+    }
+}
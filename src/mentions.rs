@@ -0,0 +1,156 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    code::{code_ranges, in_ranges},
+    links::{parse_markdown_links, scan_wikilinks},
+    Error, LinkResolver, ObsidianNote, TextEdit, Vault,
+};
+
+/// A note's name or alias appearing in another note's text without a link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnlinkedMention {
+    pub source: PathBuf,
+    /// The note that's mentioned
+    pub target: PathBuf,
+    /// The mention as written in the source
+    pub text: String,
+    /// Byte range of the mention within the source note's body
+    pub span: Range<usize>,
+}
+
+impl Vault {
+    /// Mentions of a note's file name, title or aliases in other notes, matched as whole words
+    /// and case-insensitively like Obsidian's unlinked mentions pane. Text inside links, embeds,
+    /// code and tags doesn't count.
+    pub fn unlinked_mentions(&self, note: impl AsRef<Path>) -> crate::Result<Vec<UnlinkedMention>> {
+        let target = self.path.join(note);
+        let notes = self.notes().collect::<crate::Result<Vec<_>>>()?;
+        let Some(target_note) = notes.iter().find(|note| note.file_path == target) else {
+            return Err(Error::NoteNotFound(target));
+        };
+        let names = mention_names(target_note);
+
+        let mut mentions = Vec::new();
+        for note in notes.iter().filter(|note| note.file_path != target) {
+            for span in find_mentions(&note.file_body, &names) {
+                mentions.push(UnlinkedMention {
+                    source: note.file_path.clone(),
+                    target: target.clone(),
+                    text: note.file_body[span.clone()].to_string(),
+                    span,
+                });
+            }
+        }
+        Ok(mentions)
+    }
+
+    /// Turns a mention into a link to its target, in the vault's link style, and writes the source
+    /// note. The mention's text is kept as the link's text when it differs. Returns `false` if the source changed so the
+    /// mention is no longer there.
+    pub fn link_mention(&self, mention: &UnlinkedMention) -> crate::Result<bool> {
+        let mut note = ObsidianNote::read_from_path_with(&mention.source, self.read_options)?;
+        if note.file_body.get(mention.span.clone()) != Some(mention.text.as_str()) {
+            return Ok(false);
+        }
+
+        let resolver = LinkResolver::from_vault(self)?;
+        let link = self.link_style()?.link(
+            self,
+            &resolver,
+            &mention.target,
+            &mention.source,
+            &mention.text,
+            false,
+        );
+
+        note.edit_body(&[TextEdit::new(mention.span.clone(), link)])?;
+        self.writer.write(&note.file_path, note.to_string())?;
+        Ok(true)
+    }
+}
+
+/// The file name, title and aliases a note can be mentioned by, longest first
+fn mention_names(note: &ObsidianNote) -> Vec<String> {
+    let stem = note.file_path.file_stem().unwrap_or_default();
+    let mut names: Vec<String> = [stem.to_string_lossy().into_owned(), note.title()]
+        .into_iter()
+        .chain(note.aliases())
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect();
+    names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+    names.dedup();
+    names
+}
+
+/// Whole-word, case-insensitive occurrences of lowercased `names` outside links, code and tags
+fn find_mentions(text: &str, names: &[String]) -> Vec<Range<usize>> {
+    let mut excluded = code_ranges(text);
+    excluded.extend(scan_wikilinks(text).into_iter().map(|(_, link)| link.span));
+    excluded.extend(parse_markdown_links(text).into_iter().map(|link| link.span));
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+
+    let mut mentions = Vec::new();
+    let mut cursor = 0;
+    while cursor < text.len() {
+        let before = text[..cursor].chars().next_back();
+        let found = (!is_word(before) && before != Some('#') && !in_ranges(&excluded, cursor))
+            .then(|| {
+                names.iter().find_map(|name| {
+                    let end = cursor + name.len();
+                    let candidate = text.get(cursor..end)?;
+                    let matches = candidate.to_lowercase() == *name
+                        && !is_word(text[end..].chars().next())
+                        && !in_ranges(&excluded, end - 1);
+                    matches.then_some(cursor..end)
+                })
+            })
+            .flatten();
+
+        match found {
+            Some(span) => {
+                cursor = span.end;
+                mentions.push(span);
+            }
+            None => cursor += text[cursor..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    mentions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn unlinked_mentions_skip_links_code_and_partial_words() {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, contents) in [
+            ("Rust.md", "---\naliases: [Ferris]\n---\nAbout Rust"),
+            (
+                "notes.md",
+                "rust and Ferris! [[Rust]] `Rust` #rust Rusty trust [text](Rust.md) RUST",
+            ),
+        ] {
+            fs::write(dir.path().join(path), contents).unwrap();
+        }
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let mentions = vault.unlinked_mentions("Rust.md").unwrap();
+        let texts: Vec<&str> = mentions.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, vec!["rust", "Ferris", "RUST"]);
+        assert_eq!(mentions[1].span, 9..15);
+
+        assert!(vault.link_mention(&mentions[2]).unwrap());
+        assert!(vault.link_mention(&mentions[1]).unwrap());
+        assert!(!vault.link_mention(&mentions[2]).unwrap());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("notes.md")).unwrap(),
+            "rust and [[Rust|Ferris]]! [[Rust]] `Rust` #rust Rusty trust [text](Rust.md) [[Rust|RUST]]"
+        );
+    }
+}
@@ -0,0 +1,380 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// Recognised tokens, with longer tokens before their prefixes
+const TOKENS: [&str; 37] = [
+    "YYYY", "YY", "Q", "MMMM", "MMM", "MM", "M", "DDDD", "DDD", "Do", "DD", "D", "dddd", "ddd",
+    "dd", "d", "e", "E", "GGGG", "GG", "WW", "W", "gggg", "gg", "ww", "w", "HH", "H", "hh", "h",
+    "mm", "m", "ss", "s", "A", "a", "X",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token<'a> {
+    Field(&'static str),
+    Literal(&'a str),
+}
+
+/// Formats a date the way moment.js does, which is the syntax Obsidian uses for date formats
+///
+/// Weeks use ISO numbering for `W`/`GGGG` and moment's default English locale (weeks start
+/// on Sunday, and week 1 contains January 1st) for `w`/`gggg`.
+pub fn format_moment(datetime: NaiveDateTime, format: &str) -> String {
+    let date = datetime.date();
+    let (week_year, week) = locale_week(date);
+    let mut formatted = String::new();
+
+    for token in tokens(format) {
+        let field = match token {
+            Token::Literal(literal) => {
+                formatted.push_str(literal);
+                continue;
+            }
+            Token::Field(field) => field,
+        };
+        let weekday = date.weekday().num_days_from_sunday() as usize;
+        let hour12 = match datetime.hour() % 12 {
+            0 => 12,
+            hour => hour,
+        };
+        let text = match field {
+            "YYYY" => format!("{:04}", date.year()),
+            "YY" => format!("{:02}", date.year().rem_euclid(100)),
+            "Q" => ((date.month0() / 3) + 1).to_string(),
+            "MMMM" => MONTHS[date.month0() as usize].to_string(),
+            "MMM" => MONTHS[date.month0() as usize][..3].to_string(),
+            "MM" => format!("{:02}", date.month()),
+            "M" => date.month().to_string(),
+            "DDDD" => format!("{:03}", date.ordinal()),
+            "DDD" => date.ordinal().to_string(),
+            "Do" => ordinal(date.day()),
+            "DD" => format!("{:02}", date.day()),
+            "D" => date.day().to_string(),
+            "dddd" => WEEKDAYS[weekday].to_string(),
+            "ddd" => WEEKDAYS[weekday][..3].to_string(),
+            "dd" => WEEKDAYS[weekday][..2].to_string(),
+            "d" | "e" => weekday.to_string(),
+            "E" => date.weekday().number_from_monday().to_string(),
+            "GGGG" => format!("{:04}", date.iso_week().year()),
+            "GG" => format!("{:02}", date.iso_week().year().rem_euclid(100)),
+            "WW" => format!("{:02}", date.iso_week().week()),
+            "W" => date.iso_week().week().to_string(),
+            "gggg" => format!("{week_year:04}"),
+            "gg" => format!("{:02}", week_year.rem_euclid(100)),
+            "ww" => format!("{week:02}"),
+            "w" => week.to_string(),
+            "HH" => format!("{:02}", datetime.hour()),
+            "H" => datetime.hour().to_string(),
+            "hh" => format!("{hour12:02}"),
+            "h" => hour12.to_string(),
+            "mm" => format!("{:02}", datetime.minute()),
+            "m" => datetime.minute().to_string(),
+            "ss" => format!("{:02}", datetime.second()),
+            "s" => datetime.second().to_string(),
+            "A" => if datetime.hour() < 12 { "AM" } else { "PM" }.to_string(),
+            "a" => if datetime.hour() < 12 { "am" } else { "pm" }.to_string(),
+            "X" => datetime.and_utc().timestamp().to_string(),
+            _ => unreachable!("unknown token {field}"),
+        };
+        formatted.push_str(&text);
+    }
+
+    formatted
+}
+
+/// Parses text that exactly matches a moment.js format, the inverse of [`format_moment`]
+///
+/// Missing parts default to the start of the period, so `2024-W07` is the Monday of that
+/// week and `2024-03` is March 1st.
+pub fn parse_moment(text: &str, format: &str) -> Option<NaiveDateTime> {
+    let mut fields = Fields::default();
+    let mut rest = text;
+
+    for token in tokens(format) {
+        let field = match token {
+            Token::Literal(literal) => {
+                rest = rest.strip_prefix(literal)?;
+                continue;
+            }
+            Token::Field(field) => field,
+        };
+        let (value, len) = match field {
+            "MMMM" | "MMM" => name(rest, &MONTHS, field.len())?,
+            "dddd" | "ddd" | "dd" => name(rest, &WEEKDAYS, field.len())?,
+            "A" | "a" => {
+                let meridiem = rest.get(..2)?.to_ascii_lowercase();
+                let pm = match meridiem.as_str() {
+                    "am" => 0,
+                    "pm" => 1,
+                    _ => return None,
+                };
+                (pm, 2)
+            }
+            "Do" => {
+                let (day, digits) = number(rest, 1, 2)?;
+                let suffix = rest.get(digits..digits + 2)?;
+                (ordinal(day as u32) == format!("{day}{suffix}")).then_some((day, digits + 2))?
+            }
+            "YYYY" | "GGGG" | "gggg" => number(rest, 4, 4)?,
+            "DDDD" => number(rest, 3, 3)?,
+            "DDD" => number(rest, 1, 3)?,
+            "X" => number(rest, 1, 12)?,
+            "Q" | "d" | "e" | "E" => number(rest, 1, 1)?,
+            _ if field.len() == 2 => number(rest, 2, 2)?,
+            _ => number(rest, 1, 2)?,
+        };
+        rest = &rest[len..];
+
+        let two_digit_year = |year: i64| if year > 68 { 1900 + year } else { 2000 + year };
+        match field {
+            "YYYY" => fields.year = Some(value),
+            "YY" => fields.year = Some(two_digit_year(value)),
+            "Q" => fields.quarter = Some(value),
+            "MMMM" | "MMM" => fields.month = Some(value + 1),
+            "MM" | "M" => fields.month = Some(value),
+            "DDDD" | "DDD" => fields.ordinal = Some(value),
+            "Do" | "DD" | "D" => fields.day = Some(value),
+            "dddd" | "ddd" | "dd" | "d" | "e" => fields.weekday = Some(value),
+            "E" => fields.weekday = Some(value % 7),
+            "GGGG" => fields.iso_year = Some(value),
+            "GG" => fields.iso_year = Some(two_digit_year(value)),
+            "WW" | "W" => fields.iso_week = Some(value),
+            "gggg" => fields.week_year = Some(value),
+            "gg" => fields.week_year = Some(two_digit_year(value)),
+            "ww" | "w" => fields.week = Some(value),
+            "HH" | "H" | "hh" | "h" => fields.hour = Some(value),
+            "mm" | "m" => fields.minute = Some(value),
+            "ss" | "s" => fields.second = Some(value),
+            "A" | "a" => fields.pm = Some(value == 1),
+            "X" => return chrono::DateTime::from_timestamp(value, 0).map(|dt| dt.naive_utc()),
+            _ => unreachable!("unknown token {field}"),
+        }
+    }
+
+    if !rest.is_empty() {
+        return None;
+    }
+    fields.resolve()
+}
+
+#[derive(Debug, Default)]
+struct Fields {
+    year: Option<i64>,
+    quarter: Option<i64>,
+    month: Option<i64>,
+    day: Option<i64>,
+    ordinal: Option<i64>,
+    /// Days from Sunday
+    weekday: Option<i64>,
+    iso_year: Option<i64>,
+    iso_week: Option<i64>,
+    week_year: Option<i64>,
+    week: Option<i64>,
+    hour: Option<i64>,
+    minute: Option<i64>,
+    second: Option<i64>,
+    pm: Option<bool>,
+}
+
+impl Fields {
+    fn resolve(&self) -> Option<NaiveDateTime> {
+        let int = |value: i64| i32::try_from(value).ok();
+        let uint = |value: i64| u32::try_from(value).ok();
+
+        let date = if let Some(week) = self.iso_week {
+            let year = self.iso_year.or(self.year)?;
+            let weekday = match self.weekday {
+                Some(0) => Weekday::Sun,
+                Some(day) => Weekday::try_from(u8::try_from(day - 1).ok()?).ok()?,
+                None => Weekday::Mon,
+            };
+            NaiveDate::from_isoywd_opt(int(year)?, uint(week)?, weekday)?
+        } else if let Some(week) = self.week {
+            let year = int(self.week_year.or(self.year)?)?;
+            // Week 1 ends on the first Saturday of the year
+            let jan1 = NaiveDate::from_ymd_opt(year, 1, 1)?;
+            let first_sunday =
+                jan1 - chrono::Days::new(jan1.weekday().num_days_from_sunday().into());
+            let offset = (week - 1) * 7 + self.weekday.unwrap_or(0);
+            first_sunday.checked_add_days(chrono::Days::new(u64::try_from(offset).ok()?))?
+        } else if let Some(ordinal) = self.ordinal {
+            NaiveDate::from_yo_opt(int(self.year?)?, uint(ordinal)?)?
+        } else {
+            let month = match (self.month, self.quarter) {
+                (Some(month), _) => month,
+                (None, Some(quarter)) => (quarter - 1) * 3 + 1,
+                (None, None) => 1,
+            };
+            NaiveDate::from_ymd_opt(int(self.year?)?, uint(month)?, uint(self.day.unwrap_or(1))?)?
+        };
+
+        let mut hour = self.hour.unwrap_or(0);
+        match self.pm {
+            Some(true) if hour < 12 => hour += 12,
+            Some(false) if hour == 12 => hour = 0,
+            _ => {}
+        }
+        let time = NaiveTime::from_hms_opt(
+            uint(hour)?,
+            uint(self.minute.unwrap_or(0))?,
+            uint(self.second.unwrap_or(0))?,
+        )?;
+
+        Some(date.and_time(time))
+    }
+}
+
+fn tokens(format: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = format;
+    while let Some(c) = rest.chars().next() {
+        if c == '[' {
+            if let Some(end) = rest.find(']') {
+                tokens.push(Token::Literal(&rest[1..end]));
+                rest = &rest[end + 1..];
+                continue;
+            }
+        }
+        match TOKENS.iter().find(|token| rest.starts_with(**token)) {
+            Some(token) => {
+                tokens.push(Token::Field(token));
+                rest = &rest[token.len()..];
+            }
+            None => {
+                tokens.push(Token::Literal(&rest[..c.len_utf8()]));
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    tokens
+}
+
+/// The year and number of moment's default (English) locale week containing `date`
+fn locale_week(date: NaiveDate) -> (i32, u32) {
+    let sunday = date - chrono::Days::new(date.weekday().num_days_from_sunday().into());
+    let saturday = sunday + chrono::Days::new(6);
+    (saturday.year(), saturday.ordinal0() / 7 + 1)
+}
+
+fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+/// A number of `min..=max` digits at the start of `text`, and how many bytes it took
+fn number(text: &str, min: usize, max: usize) -> Option<(i64, usize)> {
+    let len = text
+        .bytes()
+        .take(max)
+        .take_while(u8::is_ascii_digit)
+        .count();
+    if len < min {
+        return None;
+    }
+    Some((text[..len].parse().ok()?, len))
+}
+
+/// The index of the name (or its first `len` letters, for abbreviations) at the start of
+/// `text`
+fn name(text: &str, names: &[&str], len: usize) -> Option<(i64, usize)> {
+    names.iter().enumerate().find_map(|(i, name)| {
+        let name = if len == 4 { name } else { &name[..len] };
+        let prefix = text.get(..name.len())?;
+        prefix
+            .eq_ignore_ascii_case(name)
+            .then_some((i as i64, name.len()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn format_moment_supports_common_tokens() {
+        let date = datetime(2024, 2, 3, 14, 5);
+
+        assert_eq!(format_moment(date, "YYYY-MM-DD"), "2024-02-03");
+        assert_eq!(
+            format_moment(date, "dddd, MMMM Do YYYY [at] h:mm A"),
+            "Saturday, February 3rd 2024 at 2:05 PM"
+        );
+        assert_eq!(
+            format_moment(date, "YYYY/MM-MMM/ddd D"),
+            "2024/02-Feb/Sat 3"
+        );
+        assert_eq!(format_moment(date, "GGGG-[W]WW"), "2024-W05");
+        assert_eq!(format_moment(date, "YYYY-[Q]Q"), "2024-Q1");
+        // Moment's English weeks start on Sunday, with week 1 holding January 1st
+        assert_eq!(
+            format_moment(datetime(2022, 1, 1, 0, 0), "gggg-[W]ww"),
+            "2022-W01"
+        );
+        assert_eq!(
+            format_moment(datetime(2024, 12, 29, 0, 0), "gggg-[W]ww"),
+            "2025-W01"
+        );
+    }
+
+    #[test]
+    fn parse_moment_inverts_format() {
+        let date = datetime(2024, 2, 3, 14, 5);
+        for format in ["YYYY-MM-DD HH:mm", "dddd, MMMM Do YYYY [at] h:mm A", "X"] {
+            assert_eq!(
+                parse_moment(&format_moment(date, format), format),
+                Some(date)
+            );
+        }
+
+        assert_eq!(
+            parse_moment("2024-W07", "GGGG-[W]WW"),
+            Some(datetime(2024, 2, 12, 0, 0))
+        );
+        assert_eq!(
+            parse_moment("2024-Q2", "YYYY-[Q]Q"),
+            Some(datetime(2024, 4, 1, 0, 0))
+        );
+        assert_eq!(
+            parse_moment("2025-W01", "gggg-[W]ww"),
+            Some(datetime(2024, 12, 29, 0, 0))
+        );
+        assert_eq!(parse_moment("2024-02-30", "YYYY-MM-DD"), None);
+        assert_eq!(parse_moment("2024-02-03 extra", "YYYY-MM-DD"), None);
+    }
+}
@@ -1,61 +1,442 @@
-use std::{fs, path::PathBuf};
+use std::{
+    borrow::Cow,
+    fmt::{self, Write},
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use serde::de::DeserializeOwned;
+
+use crate::{frontmatter::patch_raw_properties, Error, VaultWriter};
 
 pub type Properties = serde_yaml::Value;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct ObsidianNote {
     pub file_path: PathBuf,
+    /// The file's text without any byte order mark. Body edits made with
+    /// [`ObsidianNote::edit_body`] are kept in it, while property edits only show up once the
+    /// note is written.
     pub file_contents: String,
     pub file_body: String,
+    /// The raw frontmatter between the delimiters, kept so edits can preserve its formatting
+    pub frontmatter: Option<String>,
+    /// The syntax `frontmatter` is written in, kept when the note is written back
+    pub frontmatter_format: FrontmatterFormat,
+    /// How the file breaks lines, used for any lines added to it
+    pub line_ending: LineEnding,
+    /// Whether the file starts with a UTF-8 byte order mark, which is written back with it
+    pub bom: bool,
     pub properties: Option<Properties>,
+    /// Where the raw YAML sits in `file_contents`
+    pub frontmatter_range: Option<SourceRange>,
+    /// Where `file_body` sits in `file_contents`
+    pub body_range: SourceRange,
+}
+
+/// How notes that aren't valid UTF-8 are read
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReadOptions {
+    /// Replace invalid bytes with U+FFFD instead of failing
+    pub lossy: bool,
+    /// Leave notes that aren't valid UTF-8 out of vault scans such as [`Vault::notes`] instead
+    /// of failing, when not `lossy`
+    ///
+    /// [`Vault::notes`]: crate::Vault::notes
+    pub skip_invalid: bool,
+}
+
+impl ReadOptions {
+    /// Whether a scan yields a note that was read with these options
+    pub(crate) fn keeps(self, note: &crate::Result<ObsidianNote>) -> bool {
+        !(self.skip_invalid && matches!(note, Err(Error::InvalidUtf8(_))))
+    }
+
+    /// Decodes a file's bytes, failing with [`Error::InvalidUtf8`] unless `lossy`
+    pub(crate) fn decode(self, file_path: &Path, bytes: Vec<u8>) -> crate::Result<String> {
+        match String::from_utf8(bytes) {
+            Ok(text) => Ok(text),
+            Err(err) if self.lossy => Ok(String::from_utf8_lossy(err.as_bytes()).into_owned()),
+            Err(_) => Err(Error::InvalidUtf8(file_path.to_path_buf())),
+        }
+    }
+}
+
+/// The syntax of a note's frontmatter
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FrontmatterFormat {
+    /// Between `---` lines, as Obsidian writes it
+    #[default]
+    Yaml,
+    /// Between `+++` lines, as in Hugo and Zola sites
+    Toml,
+    /// A JSON object between `---` lines, which older versions of Obsidian accepted
+    Json,
+}
+
+impl FrontmatterFormat {
+    fn delimiter(self) -> &'static str {
+        match self {
+            Self::Yaml | Self::Json => "---",
+            Self::Toml => "+++",
+        }
+    }
+
+    /// Parses raw frontmatter, treating empty frontmatter as no properties
+    pub(crate) fn parse(self, raw: &str) -> crate::Result<Option<Properties>> {
+        match self {
+            Self::Yaml => parse_properties(raw),
+            Self::Toml => {
+                let table = toml::from_str::<toml::Table>(raw)?;
+                Ok((!table.is_empty()).then(|| toml_to_properties(toml::Value::Table(table))))
+            }
+            Self::Json => {
+                let mapping = serde_json::from_str::<serde_yaml::Mapping>(raw)?;
+                Ok((!mapping.is_empty()).then_some(Properties::Mapping(mapping)))
+            }
+        }
+    }
+
+    /// Writes properties out in full, without the delimiters or a trailing newline
+    fn emit(self, properties: &Properties) -> Result<String, fmt::Error> {
+        let emitted = match self {
+            Self::Yaml => serde_yaml::to_string(properties).map_err(|_| fmt::Error)?,
+            Self::Toml => toml::to_string(properties).map_err(|_| fmt::Error)?,
+            Self::Json => serde_json::to_string_pretty(properties).map_err(|_| fmt::Error)?,
+        };
+        Ok(emitted.trim_end().to_string())
+    }
+}
+
+/// The line breaks a note is written with
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    /// `CrLf` if every line break in `text` is one. Files that mix both are left as they are.
+    pub fn detect(text: &str) -> Self {
+        let lines = text.matches('\n').count();
+        if lines > 0 && text.matches("\r\n").count() == lines {
+            Self::CrLf
+        } else {
+            Self::Lf
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+
+    /// `text` with its bare `\n` line breaks converted to this line ending
+    pub fn apply(self, text: &str) -> Cow<'_, str> {
+        if self == Self::Lf || !text.contains('\n') {
+            return Cow::Borrowed(text);
+        }
+        let mut converted = String::with_capacity(text.len());
+        for line in text.split_inclusive('\n') {
+            match line.strip_suffix('\n') {
+                Some(line) => {
+                    converted.push_str(line.strip_suffix('\r').unwrap_or(line));
+                    converted.push_str("\r\n");
+                }
+                None => converted.push_str(line),
+            }
+        }
+        Cow::Owned(converted)
+    }
+}
+
+/// A region of a file, as a byte range and the 1-based lines it starts and ends on
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SourceRange {
+    pub bytes: Range<usize>,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl SourceRange {
+    pub(crate) fn new(text: &str, bytes: Range<usize>) -> Self {
+        let start_line = text[..bytes.start].matches('\n').count() + 1;
+        let end_line = start_line + text[bytes.clone()].matches('\n').count();
+        Self {
+            bytes,
+            start_line,
+            end_line,
+        }
+    }
 }
 
 impl ObsidianNote {
-    pub fn read_from_path(file_path: &PathBuf) -> anyhow::Result<Self> {
-        let file_contents = fs::read_to_string(file_path)?;
+    pub fn read_from_path(file_path: &Path) -> crate::Result<Self> {
+        Self::read_from_path_with(file_path, ReadOptions::default())
+    }
+
+    pub fn read_from_path_with(file_path: &Path, options: ReadOptions) -> crate::Result<Self> {
+        let file_contents = options.decode(file_path, fs::read(file_path)?)?;
         let note = Self::parse(file_path, file_contents)?;
         Ok(note)
     }
 
-    pub fn parse(file_path: &PathBuf, file_contents: String) -> anyhow::Result<Self> {
-        let (frontmatter_str, file_body) = extract_frontmatter(&file_contents);
+    pub fn parse(file_path: &Path, mut file_contents: String) -> crate::Result<Self> {
+        let bom = file_contents.starts_with(BOM);
+        if bom {
+            file_contents.drain(..BOM.len_utf8());
+        }
+        let (frontmatter_format, frontmatter_range, body_range) = split_frontmatter(&file_contents);
+        let frontmatter_range =
+            frontmatter_range.map(|range| SourceRange::new(&file_contents, range));
+        let body_range = SourceRange::new(&file_contents, body_range);
 
-        let frontmatter = frontmatter_str
-            .map(|s| serde_yaml::from_str::<Properties>(&s))
-            .transpose()?
-            .and_then(|fm| {
-                if fm == serde_yaml::Value::Null {
-                    None
-                } else {
-                    Some(fm)
-                }
-            });
+        let frontmatter = frontmatter_range
+            .as_ref()
+            .map(|range| file_contents[range.bytes.clone()].to_string());
+        let properties = frontmatter
+            .as_deref()
+            .map(|raw| frontmatter_format.parse(raw))
+            .transpose()
+            .map_err(|err| frontmatter_error(err, file_path, frontmatter_range.as_ref()))?;
 
+        let properties = properties.flatten();
+        let line_ending = LineEnding::detect(&file_contents);
         let note = Self {
-            file_path: file_path.clone(),
-            file_body: file_body.unwrap_or(String::new()),
+            file_path: file_path.to_path_buf(),
+            file_body: file_contents[body_range.bytes.clone()].to_string(),
             file_contents,
-            properties: frontmatter,
+            frontmatter,
+            frontmatter_format,
+            line_ending,
+            bom,
+            properties,
+            frontmatter_range,
+            body_range,
         };
 
         Ok(note)
     }
+
+    /// Deserializes the frontmatter into `T`, treating missing frontmatter as an empty mapping
+    pub fn properties_as<T: DeserializeOwned>(&self) -> crate::Result<T> {
+        let properties = self
+            .properties
+            .clone()
+            .unwrap_or_else(|| Properties::Mapping(serde_yaml::Mapping::new()));
+
+        serde_path_to_error::deserialize(properties).map_err(|err| Error::Properties {
+            path: self.file_path.clone(),
+            field: err.path().to_string(),
+            source: err.into_inner(),
+        })
+    }
+
+    /// The `aliases` (or legacy `alias`) property, from either a list or a single string
+    pub fn aliases(&self) -> Vec<String> {
+        self.properties
+            .as_ref()
+            .map(parse_aliases)
+            .unwrap_or_default()
+    }
+
+    /// The 1-based line in the file of a byte offset into `file_body`
+    pub(crate) fn body_line(&self, body_offset: usize) -> usize {
+        self.body_range.start_line + self.file_body[..body_offset].matches('\n').count()
+    }
+
+    /// Writes the note through a temporary file, so a failure leaves the old file intact
+    pub fn write_to_path(&self, file_path: &Path) -> crate::Result<()> {
+        VaultWriter::default().write(file_path, self.to_string())
+    }
 }
 
-fn extract_frontmatter(content: &str) -> (Option<String>, Option<String>) {
-    let delimiter = "---";
-    let mut parts = content.splitn(3, delimiter);
+impl fmt::Display for ObsidianNote {
+    /// Writes the note back with the delimiters, whitespace and line endings it was parsed with,
+    /// so a note without changes comes out byte for byte as it went in
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let contents = &self.file_contents;
+        let body = &self.body_range.bytes;
+        let mut out = String::with_capacity(contents.len());
+        if self.bom {
+            out.push(BOM);
+        }
+
+        match (self.emitted_frontmatter()?, &self.frontmatter_range) {
+            (Some(raw), Some(range)) => {
+                let after = &contents[range.bytes.end..body.start];
+                out.push_str(&contents[..range.bytes.start]);
+                out.push_str(&raw);
+                // Frontmatter that was empty has its closing delimiter on the same line
+                if !raw.is_empty() && after.starts_with(self.frontmatter_format.delimiter()) {
+                    out.push('\n');
+                }
+                out.push_str(after);
+            }
+            (Some(raw), None) => {
+                let delimiter = self.frontmatter_format.delimiter();
+                write!(
+                    out,
+                    "{delimiter}\n{raw}\n{delimiter}\n{}",
+                    &contents[..body.start]
+                )?;
+            }
+            (None, None) => out.push_str(&contents[..body.start]),
+            (None, Some(_)) => {}
+        }
 
-    match (parts.next(), parts.next(), parts.next()) {
-        (Some(""), Some(frontmatter), Some(body)) => (
-            Some(frontmatter.trim().to_string()),
-            Some(body.trim().to_string()),
+        out.push_str(&self.file_body);
+        out.push_str(&contents[body.end..]);
+        f.write_str(&self.line_ending.apply(&out))
+    }
+}
+
+impl ObsidianNote {
+    /// The raw frontmatter to write between the delimiters. YAML re-emits only the properties
+    /// that were changed directly rather than through [`ObsidianNote::set_property`] and
+    /// friends, while other formats are re-emitted whole once anything changed.
+    fn emitted_frontmatter(&self) -> Result<Option<String>, fmt::Error> {
+        let format = self.frontmatter_format;
+        let raw = self.frontmatter.as_deref();
+        let Some(properties) = &self.properties else {
+            // Keep empty or comment-only frontmatter, but drop properties that were cleared
+            return Ok(raw
+                .filter(|raw| matches!(format.parse(raw), Ok(None)))
+                .map(str::to_string));
+        };
+
+        let patched = match format {
+            FrontmatterFormat::Yaml => raw.and_then(|raw| patch_raw_properties(raw, properties)),
+            FrontmatterFormat::Toml | FrontmatterFormat::Json => raw
+                .filter(
+                    |raw| matches!(format.parse(raw), Ok(Some(parsed)) if &parsed == properties),
+                )
+                .map(str::to_string),
+        };
+        match patched {
+            Some(patched) => Ok(Some(patched)),
+            None => format.emit(properties).map(Some),
+        }
+    }
+}
+
+const BOM: char = '\u{feff}';
+
+pub(crate) fn parse_properties(frontmatter: &str) -> crate::Result<Option<Properties>> {
+    let properties = serde_yaml::from_str::<Properties>(frontmatter)?;
+    Ok((properties != Properties::Null).then_some(properties))
+}
+
+fn parse_aliases(properties: &Properties) -> Vec<String> {
+    match properties
+        .get("aliases")
+        .or_else(|| properties.get("alias"))
+    {
+        Some(Properties::Sequence(values)) => values
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(str::to_string)
+            .collect(),
+        Some(Properties::String(alias)) => vec![alias.clone()],
+        _ => Vec::new(),
+    }
+}
+
+/// Points a frontmatter parse error at the file, with its line counted from the top of the file
+fn frontmatter_error(err: Error, file_path: &Path, range: Option<&SourceRange>) -> Error {
+    match err {
+        Error::Yaml {
+            line,
+            column,
+            source,
+            ..
+        } => {
+            let first_line = range.map_or(0, |range| range.start_line - 1);
+            Error::Yaml {
+                path: Some(file_path.to_path_buf()),
+                line: line.map(|line| line + first_line),
+                column,
+                source,
+            }
+        }
+        other => other.in_file(file_path),
+    }
+}
+
+/// The format and trimmed frontmatter and body ranges of `content`
+fn split_frontmatter(content: &str) -> (FrontmatterFormat, Option<Range<usize>>, Range<usize>) {
+    let format = [FrontmatterFormat::Yaml, FrontmatterFormat::Toml]
+        .into_iter()
+        .find(|format| content.starts_with(format.delimiter()));
+    let Some(format) = format else {
+        return (
+            FrontmatterFormat::default(),
+            None,
+            trimmed(content, 0..content.len()),
+        );
+    };
+
+    let delimiter = format.delimiter();
+    let start = delimiter.len();
+    let (frontmatter, body) = match content[start..].find(delimiter) {
+        Some(end) => {
+            let end = start + end;
+            (
+                trimmed(content, start..end),
+                trimmed(content, end + delimiter.len()..content.len()),
+            )
+        }
+        None => (
+            trimmed(content, start..content.len()),
+            content.len()..content.len(),
+        ),
+    };
+
+    // JSON is also valid YAML, so only YAML flow mappings that aren't JSON stay YAML
+    let raw = &content[frontmatter.clone()];
+    let format = match format {
+        FrontmatterFormat::Yaml
+            if raw.starts_with('{') && serde_json::from_str::<serde_json::Value>(raw).is_ok() =>
+        {
+            FrontmatterFormat::Json
+        }
+        format => format,
+    };
+    (format, Some(frontmatter), body)
+}
+
+fn toml_to_properties(value: toml::Value) -> Properties {
+    match value {
+        toml::Value::String(s) => Properties::String(s),
+        toml::Value::Integer(i) => Properties::from(i),
+        toml::Value::Float(f) => Properties::from(f),
+        toml::Value::Boolean(b) => Properties::Bool(b),
+        // Dates read as strings, like unquoted dates in YAML frontmatter
+        toml::Value::Datetime(datetime) => Properties::String(datetime.to_string()),
+        toml::Value::Array(values) => {
+            Properties::Sequence(values.into_iter().map(toml_to_properties).collect())
+        }
+        toml::Value::Table(table) => Properties::Mapping(
+            table
+                .into_iter()
+                .map(|(key, value)| (Properties::String(key), toml_to_properties(value)))
+                .collect(),
         ),
-        (Some(""), Some(frontmatter), None) => (Some(frontmatter.trim().to_string()), None),
-        _ => (None, Some(content.trim().to_string())),
     }
 }
 
+/// `range` without surrounding whitespace
+fn trimmed(text: &str, range: Range<usize>) -> Range<usize> {
+    let slice = &text[range.clone()];
+    let start = range.start + (slice.len() - slice.trim_start().len());
+    let end = range.end - (slice.len() - slice.trim_end().len());
+    start..end.max(start)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,4 +513,293 @@ mod tests {
             ObsidianNote::parse(&PathBuf::from("a-note.md"), note_content.to_string()).unwrap();
         assert_eq!(note.properties, None);
     }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct BookNote {
+        author: String,
+        rating: u8,
+        #[serde(default)]
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn properties_as_deserializes_into_struct() {
+        let note_content = indoc! {r"
+            ---
+            author: Ursula K. Le Guin
+            rating: 5
+            ---
+        "};
+        let note =
+            ObsidianNote::parse(&PathBuf::from("a-note.md"), note_content.to_string()).unwrap();
+
+        assert_eq!(
+            note.properties_as::<BookNote>().unwrap(),
+            BookNote {
+                author: "Ursula K. Le Guin".to_string(),
+                rating: 5,
+                tags: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn properties_as_reports_path_and_field() {
+        let note_content = indoc! {r"
+            ---
+            author: Ursula K. Le Guin
+            rating: excellent
+            ---
+        "};
+        let note =
+            ObsidianNote::parse(&PathBuf::from("a-note.md"), note_content.to_string()).unwrap();
+        let err = format!("{:#}", note.properties_as::<BookNote>().unwrap_err());
+
+        assert!(err.contains("a-note.md"));
+        assert!(err.contains("rating"));
+    }
+
+    #[test]
+    fn properties_as_handles_missing_frontmatter() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Optional {
+            status: Option<String>,
+        }
+
+        let note = ObsidianNote::parse(&PathBuf::from("a-note.md"), "Body".to_string()).unwrap();
+        assert_eq!(note.properties_as::<Optional>().unwrap().status, None);
+    }
+
+    #[test]
+    fn to_string_emits_frontmatter_and_body() {
+        let note_content = indoc! {r"
+            ---
+            some-property: foo
+            ---
+            The note body
+        "};
+        let mut note =
+            ObsidianNote::parse(&PathBuf::from("a-note.md"), note_content.to_string()).unwrap();
+        note.file_body = "A new body".to_string();
+
+        assert_eq!(
+            note.to_string(),
+            indoc! {r"
+                ---
+                some-property: foo
+                ---
+                A new body
+            "}
+        );
+    }
+
+    #[test]
+    fn to_string_omits_missing_frontmatter() {
+        let note =
+            ObsidianNote::parse(&PathBuf::from("a-note.md"), "The note contents".to_string())
+                .unwrap();
+        assert_eq!(note.to_string(), "The note contents");
+    }
+
+    #[test]
+    fn to_string_round_trips_untouched_notes_exactly() {
+        let notes = [
+            "---\n# comment\ntitle: 'Quoted'\n\ntags: [a, b]\nlist:\n  - x\n---\n\nBody\n\n",
+            "---\n---\nBody",
+            "---\nkey: value",
+            "\n  Just a body",
+            "",
+        ];
+        for contents in notes {
+            let note = ObsidianNote::parse(Path::new("a-note.md"), contents.to_string()).unwrap();
+            assert_eq!(note.to_string(), contents);
+        }
+    }
+
+    #[test]
+    fn to_string_re_emits_only_changed_properties() {
+        let mut note = ObsidianNote::parse(
+            Path::new("a-note.md"),
+            "---\ntitle: \"Quoted\" # keep\n\ntags: [a, b]\nold: 1\n---\nBody\n".to_string(),
+        )
+        .unwrap();
+        let properties = note.properties.as_mut().unwrap().as_mapping_mut().unwrap();
+        properties.insert("tags".into(), vec!["c"].into());
+        properties.remove("old");
+        properties.insert("new".into(), true.into());
+
+        assert_eq!(
+            note.to_string(),
+            "---\ntitle: \"Quoted\" # keep\n\ntags:\n- c\nnew: true\n---\nBody\n"
+        );
+    }
+
+    #[test]
+    fn parse_reads_toml_frontmatter_and_keeps_its_format() {
+        let contents = indoc! {r#"
+            +++
+            title = "Hugo post" # imported
+            draft = false
+            date = 2024-03-15
+            tags = ["a", "b"]
+            +++
+
+            Body
+        "#};
+        let mut note = ObsidianNote::parse(Path::new("post.md"), contents.to_string()).unwrap();
+
+        assert_eq!(note.frontmatter_format, FrontmatterFormat::Toml);
+        let properties = note.properties.as_ref().unwrap();
+        assert_eq!(properties["title"], "Hugo post");
+        assert_eq!(properties["draft"], false);
+        assert_eq!(properties["date"], "2024-03-15");
+        assert_eq!(properties["tags"][1], "b");
+        assert_eq!(note.to_string(), contents);
+
+        note.set_property("draft", true).unwrap();
+        note.remove_property("tags");
+        assert_eq!(
+            note.to_string(),
+            "+++\ntitle = \"Hugo post\"\ndraft = true\ndate = \"2024-03-15\"\n+++\n\nBody\n"
+        );
+    }
+
+    #[test]
+    fn parse_reports_invalid_toml() {
+        let err = ObsidianNote::parse(Path::new("post.md"), "+++\ntitle =\n+++\n".to_string())
+            .unwrap_err();
+        assert!(matches!(err, Error::Toml { path: Some(_), .. }));
+    }
+
+    #[test]
+    fn parse_reads_json_frontmatter() {
+        let contents = "---\n{\n\t\"title\": \"A\\/B\",\n\t\"tags\": [\"x\"]\n}\n---\nBody\n";
+        let mut note = ObsidianNote::parse(Path::new("old.md"), contents.to_string()).unwrap();
+
+        assert_eq!(note.frontmatter_format, FrontmatterFormat::Json);
+        assert_eq!(note.properties.as_ref().unwrap()["title"], "A/B");
+        assert_eq!(note.to_string(), contents);
+
+        note.set_property("status", "done").unwrap();
+        assert_eq!(
+            note.to_string(),
+            indoc! {r#"
+                ---
+                {
+                  "title": "A/B",
+                  "tags": [
+                    "x"
+                  ],
+                  "status": "done"
+                }
+                ---
+                Body
+            "#}
+        );
+    }
+
+    #[test]
+    fn parse_reads_yaml_flow_mapping_frontmatter() {
+        let note = ObsidianNote::parse(
+            Path::new("a.md"),
+            "---\n{title: A, tags: [x]}\n---\nBody\n".to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(note.frontmatter_format, FrontmatterFormat::Yaml);
+        assert_eq!(note.properties.as_ref().unwrap()["title"], "A");
+    }
+
+    #[test]
+    fn crlf_notes_keep_their_line_endings() {
+        let contents = "---\r\ntitle: A\r\n---\r\n## Log\r\n- first\r\n";
+        let mut note = ObsidianNote::parse(Path::new("a.md"), contents.to_string()).unwrap();
+        assert_eq!(note.line_ending, LineEnding::CrLf);
+        assert_eq!(note.to_string(), contents);
+
+        note.set_property("tags", vec!["x"]).unwrap();
+        note.insert_under_heading("Log", "- second\n- third", crate::Position::End)
+            .unwrap();
+        assert_eq!(
+            note.to_string(),
+            "---\r\ntitle: A\r\ntags:\r\n- x\r\n---\r\n## Log\r\n- first\r\n- second\r\n- third\r\n"
+        );
+
+        let mixed = "a\r\nb\n";
+        assert_eq!(LineEnding::detect(mixed), LineEnding::Lf);
+        let note = ObsidianNote::parse(Path::new("a.md"), mixed.to_string()).unwrap();
+        assert_eq!(note.to_string(), mixed);
+    }
+
+    #[test]
+    fn parse_strips_and_keeps_byte_order_marks() {
+        let contents = "\u{feff}---\ntitle: A\n---\nBody";
+        let mut note = ObsidianNote::parse(Path::new("a.md"), contents.to_string()).unwrap();
+
+        assert!(note.bom);
+        assert_eq!(note.properties.as_ref().unwrap()["title"], "A");
+        assert_eq!(note.file_body, "Body");
+        assert_eq!(note.to_string(), contents);
+
+        note.edit_body(&[crate::TextEdit::new(0..4, "Text")])
+            .unwrap();
+        note.properties = None;
+        assert_eq!(note.to_string(), "\u{feff}Text");
+    }
+
+    #[test]
+    fn write_to_path_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a-note.md");
+        let note_content = indoc! {r"
+            ---
+            some-property: foo
+            ---
+            The note body
+        "};
+        let note = ObsidianNote::parse(&path, note_content.to_string()).unwrap();
+
+        note.write_to_path(&path).unwrap();
+        let reread = ObsidianNote::read_from_path(&path).unwrap();
+
+        assert_eq!(reread.properties, note.properties);
+        assert_eq!(reread.file_body, note.file_body);
+    }
+
+    #[test]
+    fn parse_records_source_ranges() {
+        let note_content = indoc! {r"
+            ---
+            title: A note
+            tags: [a]
+            ---
+
+            First line
+            Second line
+        "};
+        let note = ObsidianNote::parse(Path::new("a-note.md"), note_content.to_string()).unwrap();
+
+        let frontmatter = note.frontmatter_range.as_ref().unwrap();
+        assert_eq!(
+            &note_content[frontmatter.bytes.clone()],
+            "title: A note\ntags: [a]"
+        );
+        assert_eq!((frontmatter.start_line, frontmatter.end_line), (2, 3));
+
+        assert_eq!(&note_content[note.body_range.bytes.clone()], note.file_body);
+        assert_eq!(
+            (note.body_range.start_line, note.body_range.end_line),
+            (6, 7)
+        );
+    }
+
+    #[test]
+    fn parse_records_body_range_without_frontmatter() {
+        let note =
+            ObsidianNote::parse(Path::new("a-note.md"), "\n  Just a body\n".to_string()).unwrap();
+
+        assert_eq!(note.frontmatter_range, None);
+        assert_eq!(note.body_range.bytes, 3..14);
+        assert_eq!(note.body_range.start_line, 2);
+    }
 }
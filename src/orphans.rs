@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use crate::{BacklinkIndex, LinkResolver, Vault};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OrphanOptions {
+    /// Folders, relative to the vault root, whose notes are never reported, e.g. `Templates`
+    pub excluded_folders: Vec<PathBuf>,
+}
+
+impl OrphanOptions {
+    pub fn excluding(folders: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
+        Self {
+            excluded_folders: folders.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl Vault {
+    /// Notes that no other note links to or embeds
+    pub fn orphans(&self, options: &OrphanOptions) -> crate::Result<Vec<PathBuf>> {
+        let notes = self.notes().collect::<crate::Result<Vec<_>>>()?;
+        let resolver = LinkResolver::from_vault_notes(self, &notes)?;
+        let index = BacklinkIndex::new(&notes, &resolver);
+
+        let orphans = notes
+            .into_iter()
+            .map(|note| note.file_path)
+            .filter(|path| {
+                let relative = self.relative_path(path);
+                !options
+                    .excluded_folders
+                    .iter()
+                    .any(|folder| relative.starts_with(folder))
+            })
+            .filter(|path| index.backlinks(path).is_empty())
+            .collect();
+
+        Ok(orphans)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn vault() -> (tempfile::TempDir, Vault) {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("Templates")).unwrap();
+        fs::write(dir.path().join("hub.md"), "[[linked]]").unwrap();
+        fs::write(dir.path().join("linked.md"), "[[hub]]").unwrap();
+        fs::write(dir.path().join("lonely.md"), "[[lonely]]").unwrap();
+        fs::write(dir.path().join("Templates/daily.md"), "").unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+        (dir, vault)
+    }
+
+    #[test]
+    fn orphans_finds_notes_without_inbound_links() {
+        let (dir, vault) = vault();
+        let orphans = vault.orphans(&OrphanOptions::default()).unwrap();
+
+        assert_eq!(
+            orphans,
+            vec![
+                dir.path().join("Templates/daily.md"),
+                dir.path().join("lonely.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn orphans_counts_markdown_links() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.md"), "[B](b.md)").unwrap();
+        fs::write(dir.path().join("b.md"), "[A](a.md#Top)").unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        assert!(vault.orphans(&OrphanOptions::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn orphans_skips_excluded_folders() {
+        let (dir, vault) = vault();
+        let orphans = vault
+            .orphans(&OrphanOptions::excluding(["Templates"]))
+            .unwrap();
+
+        assert_eq!(orphans, vec![dir.path().join("lonely.md")]);
+    }
+}
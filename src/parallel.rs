@@ -0,0 +1,52 @@
+use rayon::prelude::*;
+
+use crate::{ObsidianNote, Vault};
+
+impl Vault {
+    /// Reads and parses notes across threads. The vault is walked up front, so collecting the
+    /// iterator keeps the same order as [`Vault::notes`].
+    pub fn par_notes(&self) -> impl ParallelIterator<Item = crate::Result<ObsidianNote>> {
+        let options = self.read_options;
+        self.note_paths()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(move |path| {
+                path.and_then(|path| ObsidianNote::read_from_path_with(&path, options))
+            })
+            .filter(move |note| options.keeps(note))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn par_notes_matches_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..50 {
+            let folder = dir.path().join(format!("folder-{}", i % 5));
+            fs::create_dir_all(&folder).unwrap();
+            fs::write(folder.join(format!("{i}.md")), format!("#tag-{i}")).unwrap();
+        }
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let parallel: Vec<ObsidianNote> = vault.par_notes().collect::<crate::Result<_>>().unwrap();
+        let sequential: Vec<ObsidianNote> = vault.notes().collect::<crate::Result<_>>().unwrap();
+        assert_eq!(parallel, sequential);
+        assert_eq!(parallel.len(), 50);
+    }
+
+    #[test]
+    fn par_notes_reports_unreadable_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("good.md"), "").unwrap();
+        fs::write(dir.path().join("bad.md"), [0xff, 0xfe]).unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let results: Vec<_> = vault.par_notes().collect();
+        assert!(results[0].is_err());
+        assert!(results[1].is_ok());
+    }
+}
@@ -0,0 +1,135 @@
+use std::{collections::BTreeMap, fs};
+
+use serde::Deserialize;
+
+use crate::{config::read_config, Vault};
+
+/// A community plugin's `manifest.json`
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PluginManifest {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub min_app_version: Option<String>,
+    pub description: String,
+    pub author: String,
+    pub is_desktop_only: bool,
+    /// Fields without a typed field, such as `authorUrl` or `fundingUrl`
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+/// A community plugin installed under `.obsidian/plugins`
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstalledPlugin {
+    /// The plugin's folder name, which Obsidian keeps equal to the manifest's `id`
+    pub id: String,
+    pub manifest: PluginManifest,
+    /// Whether the plugin is listed in `community-plugins.json`
+    pub enabled: bool,
+    /// The plugin's `data.json`, if it has saved any settings
+    pub settings: Option<serde_json::Value>,
+}
+
+impl InstalledPlugin {
+    pub fn version(&self) -> &str {
+        &self.manifest.version
+    }
+}
+
+impl Vault {
+    /// Every installed community plugin, enabled or not, sorted by ID. Folders without a
+    /// `manifest.json` are skipped.
+    pub fn plugins(&self) -> crate::Result<Vec<InstalledPlugin>> {
+        let plugins_dir = self.config_dir().join("plugins");
+        let entries = match fs::read_dir(&plugins_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let enabled = self.config()?.community_plugins;
+
+        let mut plugins = Vec::new();
+        for entry in entries {
+            let dir = entry?.path();
+            if !dir.is_dir() {
+                continue;
+            }
+            let Some(manifest) = read_config::<PluginManifest>(&dir, "manifest.json")? else {
+                continue;
+            };
+            let id = dir
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            plugins.push(InstalledPlugin {
+                enabled: enabled.contains(&id),
+                settings: read_config(&dir, "data.json")?,
+                manifest,
+                id,
+            });
+        }
+
+        plugins.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(plugins)
+    }
+
+    pub fn plugin(&self, id: &str) -> crate::Result<Option<InstalledPlugin>> {
+        Ok(self.plugins()?.into_iter().find(|plugin| plugin.id == id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CONFIG_DIR;
+
+    #[test]
+    fn plugins_reads_manifests_and_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join(CONFIG_DIR);
+        for id in ["dataview", "obsidian-kanban", "leftover"] {
+            fs::create_dir_all(config.join("plugins").join(id)).unwrap();
+        }
+        fs::write(
+            config.join("plugins/dataview/manifest.json"),
+            r#"{"id": "dataview", "name": "Dataview", "version": "0.5.67", "minAppVersion": "0.13.11", "author": "Michael Brenan", "authorUrl": "https://github.com/blacksmithgu"}"#,
+        )
+        .unwrap();
+        fs::write(
+            config.join("plugins/dataview/data.json"),
+            r#"{"enableDataviewJs": true, "refreshInterval": 2500}"#,
+        )
+        .unwrap();
+        fs::write(
+            config.join("plugins/obsidian-kanban/manifest.json"),
+            r#"{"id": "obsidian-kanban", "name": "Kanban", "version": "2.0.51"}"#,
+        )
+        .unwrap();
+        fs::write(config.join("community-plugins.json"), r#"["dataview"]"#).unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let plugins = vault.plugins().unwrap();
+        let ids: Vec<&str> = plugins.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["dataview", "obsidian-kanban"]);
+
+        let dataview = &plugins[0];
+        assert!(dataview.enabled);
+        assert_eq!(dataview.version(), "0.5.67");
+        assert_eq!(
+            dataview.manifest.min_app_version.as_deref(),
+            Some("0.13.11")
+        );
+        assert_eq!(
+            dataview.manifest.other["authorUrl"],
+            "https://github.com/blacksmithgu"
+        );
+        assert_eq!(dataview.settings.as_ref().unwrap()["refreshInterval"], 2500);
+
+        let kanban = vault.plugin("obsidian-kanban").unwrap().unwrap();
+        assert!(!kanban.enabled);
+        assert_eq!(kanban.settings, None);
+    }
+}
@@ -0,0 +1,414 @@
+use std::{cmp::Ordering, ops::RangeBounds};
+
+use crate::{ObsidianNote, Properties, Vault};
+
+/// A Dataview-style query over note metadata, built up with chained conditions
+///
+/// Keys are looked up in [`ObsidianNote::metadata`], plus `file.name` and `file.path`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Query {
+    conditions: Vec<Condition>,
+    sort: Vec<(String, SortOrder)>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Exists(String),
+    Equals(String, Properties),
+    NotEquals(String, Properties),
+    /// A list value containing the item, or a string value containing the substring
+    Contains(String, Properties),
+    LessThan(String, Properties),
+    GreaterThan(String, Properties),
+    /// The note has the tag, or a tag nested under it
+    HasTag(String),
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl Query {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn condition(mut self, condition: Condition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    pub fn exists(self, key: &str) -> Self {
+        self.condition(Condition::Exists(key.to_string()))
+    }
+
+    pub fn eq(self, key: &str, value: impl Into<Properties>) -> Self {
+        self.condition(Condition::Equals(key.to_string(), value.into()))
+    }
+
+    pub fn ne(self, key: &str, value: impl Into<Properties>) -> Self {
+        self.condition(Condition::NotEquals(key.to_string(), value.into()))
+    }
+
+    pub fn contains(self, key: &str, value: impl Into<Properties>) -> Self {
+        self.condition(Condition::Contains(key.to_string(), value.into()))
+    }
+
+    pub fn lt(self, key: &str, value: impl Into<Properties>) -> Self {
+        self.condition(Condition::LessThan(key.to_string(), value.into()))
+    }
+
+    pub fn gt(self, key: &str, value: impl Into<Properties>) -> Self {
+        self.condition(Condition::GreaterThan(key.to_string(), value.into()))
+    }
+
+    pub fn tag(self, tag: &str) -> Self {
+        self.condition(Condition::HasTag(tag.trim_start_matches('#').to_string()))
+    }
+
+    /// Sorts by a key, with later calls breaking ties. Notes missing the key sort last.
+    pub fn sort_by(mut self, key: &str, order: SortOrder) -> Self {
+        self.sort.push((key.to_string(), order));
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn matches(&self, note: &ObsidianNote) -> bool {
+        let metadata = QueryMetadata::new(note);
+        self.conditions
+            .iter()
+            .all(|condition| metadata.matches(condition))
+    }
+
+    pub fn run<'a>(&self, notes: &'a [ObsidianNote]) -> Vec<&'a ObsidianNote> {
+        self.matching_indices(notes)
+            .into_iter()
+            .map(|i| &notes[i])
+            .collect()
+    }
+
+    /// Indices of the matching notes, in sorted order
+    fn matching_indices(&self, notes: &[ObsidianNote]) -> Vec<usize> {
+        let mut matched: Vec<(QueryMetadata, usize)> = notes
+            .iter()
+            .enumerate()
+            .map(|(i, note)| (QueryMetadata::new(note), i))
+            .filter(|(metadata, _)| self.conditions.iter().all(|c| metadata.matches(c)))
+            .collect();
+
+        matched.sort_by(|(a, _), (b, _)| {
+            self.sort
+                .iter()
+                .map(|(key, order)| {
+                    let ordering = match (a.get(key), b.get(key)) {
+                        (Some(a), Some(b)) => compare(&a, &b).unwrap_or(Ordering::Equal),
+                        (Some(_), None) => return Ordering::Less,
+                        (None, Some(_)) => return Ordering::Greater,
+                        (None, None) => Ordering::Equal,
+                    };
+                    match order {
+                        SortOrder::Ascending => ordering,
+                        SortOrder::Descending => ordering.reverse(),
+                    }
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let limit = self.limit.unwrap_or(usize::MAX);
+        matched.into_iter().take(limit).map(|(_, i)| i).collect()
+    }
+}
+
+impl Vault {
+    pub fn query(&self, query: &Query) -> crate::Result<Vec<ObsidianNote>> {
+        let notes = self.notes().collect::<crate::Result<Vec<_>>>()?;
+        let indices = query.matching_indices(&notes);
+
+        let mut notes: Vec<Option<ObsidianNote>> = notes.into_iter().map(Some).collect();
+        Ok(indices
+            .into_iter()
+            .filter_map(|i| notes[i].take())
+            .collect())
+    }
+}
+
+impl Vault {
+    /// Notes whose frontmatter satisfies `predicate`, which sees an empty mapping for notes
+    /// without any
+    pub fn notes_where<'a>(
+        &self,
+        predicate: impl Fn(&Properties) -> bool + 'a,
+    ) -> impl Iterator<Item = crate::Result<ObsidianNote>> + 'a {
+        let empty = Properties::Mapping(serde_yaml::Mapping::new());
+        self.notes().filter(move |note| {
+            note.as_ref().map_or(true, |note| {
+                predicate(note.properties.as_ref().unwrap_or(&empty))
+            })
+        })
+    }
+
+    /// Notes where a property equals `value`, or is a list containing it
+    pub fn with_property<'a>(
+        &self,
+        key: &'a str,
+        value: impl Into<Properties>,
+    ) -> impl Iterator<Item = crate::Result<ObsidianNote>> + 'a {
+        let value = value.into();
+        self.notes_where(move |properties| {
+            properties
+                .get(key)
+                .is_some_and(|v| equals(v, &value) || v.is_sequence() && contains(v, &value))
+        })
+    }
+
+    /// Notes where a number or ISO date property falls within `range`, such as
+    /// `Properties::from("2024-01-01")..`
+    pub fn with_property_in<'a>(
+        &self,
+        key: &'a str,
+        range: impl RangeBounds<Properties> + 'a,
+    ) -> impl Iterator<Item = crate::Result<ObsidianNote>> + 'a {
+        self.notes_where(move |properties| {
+            properties
+                .get(key)
+                .is_some_and(|value| in_range(value, &range))
+        })
+    }
+}
+
+struct QueryMetadata<'a> {
+    note: &'a ObsidianNote,
+    metadata: Properties,
+    tags: Vec<String>,
+}
+
+impl<'a> QueryMetadata<'a> {
+    fn new(note: &'a ObsidianNote) -> Self {
+        Self {
+            note,
+            metadata: note.metadata(),
+            tags: note
+                .tags()
+                .into_iter()
+                .map(|t| t.name.to_lowercase())
+                .collect(),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<Properties> {
+        match key {
+            "file.name" => self
+                .note
+                .file_path
+                .file_stem()
+                .map(|stem| Properties::from(stem.to_string_lossy().into_owned())),
+            "file.path" => Some(Properties::from(
+                self.note.file_path.to_string_lossy().into_owned(),
+            )),
+            _ => self.metadata.get(key).cloned(),
+        }
+    }
+
+    fn matches(&self, condition: &Condition) -> bool {
+        match condition {
+            Condition::Exists(key) => self.get(key).is_some_and(|v| !v.is_null()),
+            Condition::Equals(key, value) => self.get(key).is_some_and(|v| equals(&v, value)),
+            Condition::NotEquals(key, value) => !self.get(key).is_some_and(|v| equals(&v, value)),
+            Condition::Contains(key, value) => self.get(key).is_some_and(|v| contains(&v, value)),
+            Condition::LessThan(key, value) => self
+                .get(key)
+                .is_some_and(|v| compare(&v, value) == Some(Ordering::Less)),
+            Condition::GreaterThan(key, value) => self
+                .get(key)
+                .is_some_and(|v| compare(&v, value) == Some(Ordering::Greater)),
+            Condition::HasTag(tag) => {
+                let tag = tag.to_lowercase();
+                self.tags
+                    .iter()
+                    .any(|t| *t == tag || t.starts_with(&format!("{tag}/")))
+            }
+        }
+    }
+}
+
+fn equals(a: &Properties, b: &Properties) -> bool {
+    compare(a, b) == Some(Ordering::Equal) || a == b
+}
+
+fn contains(haystack: &Properties, needle: &Properties) -> bool {
+    match (haystack, needle) {
+        (Properties::Sequence(items), _) => items.iter().any(|item| equals(item, needle)),
+        (Properties::String(s), Properties::String(sub)) => s.contains(sub.as_str()),
+        _ => equals(haystack, needle),
+    }
+}
+
+fn in_range(value: &Properties, range: &impl RangeBounds<Properties>) -> bool {
+    use std::ops::Bound::*;
+
+    let above = match range.start_bound() {
+        Included(start) => matches!(
+            compare(value, start),
+            Some(Ordering::Greater | Ordering::Equal)
+        ),
+        Excluded(start) => compare(value, start) == Some(Ordering::Greater),
+        // Only numbers, strings and booleans have an order at all
+        Unbounded => compare(value, value).is_some(),
+    };
+    let below = match range.end_bound() {
+        Included(end) => matches!(compare(value, end), Some(Ordering::Less | Ordering::Equal)),
+        Excluded(end) => compare(value, end) == Some(Ordering::Less),
+        Unbounded => true,
+    };
+    above && below
+}
+
+/// Orders numbers numerically and strings (including ISO dates) lexicographically
+pub(crate) fn compare(a: &Properties, b: &Properties) -> Option<Ordering> {
+    match (a, b) {
+        (Properties::Number(a), Properties::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Properties::String(a), Properties::String(b)) => Some(a.cmp(b)),
+        (Properties::Bool(a), Properties::Bool(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn notes() -> Vec<ObsidianNote> {
+        [
+            (
+                "alpha.md",
+                "---\nstatus: active\ndue: 2024-03-01\ntags: [project/acme]\n---\n",
+            ),
+            (
+                "beta.md",
+                "---\nstatus: active\ndue: 2024-01-15\n---\n#project",
+            ),
+            (
+                "gamma.md",
+                "---\nstatus: done\ndue: 2023-12-01\ntags: [project]\n---\n",
+            ),
+            ("delta.md", "status:: active\n#project"),
+        ]
+        .iter()
+        .map(|(path, contents)| ObsidianNote::parse(Path::new(path), contents.to_string()).unwrap())
+        .collect()
+    }
+
+    fn names(notes: &[&ObsidianNote]) -> Vec<String> {
+        notes
+            .iter()
+            .map(|n| n.file_path.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn run_filters_by_value_and_tag_then_sorts() {
+        let notes = notes();
+        let query = Query::new()
+            .eq("status", "active")
+            .tag("#project")
+            .sort_by("due", SortOrder::Ascending);
+
+        assert_eq!(
+            names(&query.run(&notes)),
+            vec!["beta.md", "alpha.md", "delta.md"]
+        );
+    }
+
+    #[test]
+    fn run_supports_comparisons_and_limits() {
+        let notes = notes();
+        let query = Query::new()
+            .gt("due", "2023-12-31")
+            .sort_by("due", SortOrder::Descending)
+            .limit(1);
+
+        assert_eq!(names(&query.run(&notes)), vec!["alpha.md"]);
+    }
+
+    #[test]
+    fn run_supports_contains_and_file_fields() {
+        let notes = notes();
+
+        let query = Query::new().contains("tags", "project");
+        assert_eq!(names(&query.run(&notes)), vec!["gamma.md"]);
+
+        let query = Query::new().ne("status", "active").exists("due");
+        assert_eq!(names(&query.run(&notes)), vec!["gamma.md"]);
+
+        let query = Query::new().eq("file.name", "delta");
+        assert!(query.matches(&notes[3]));
+    }
+
+    #[test]
+    fn vault_filters_notes_by_property() {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, contents) in [
+            (
+                "a.md",
+                "---\nstatus: active\npriority: 1\ndue: 2024-03-01\n---\n",
+            ),
+            ("b.md", "---\nstatus: [active, blocked]\npriority: 5\n---\n"),
+            (
+                "c.md",
+                "---\nstatus: done\npriority: high\ndue: 2023-12-01\n---\n",
+            ),
+            ("d.md", "No frontmatter"),
+        ] {
+            std::fs::write(dir.path().join(path), contents).unwrap();
+        }
+        let vault = Vault::open(dir.path()).unwrap();
+        let names = |notes: Vec<crate::Result<ObsidianNote>>| -> Vec<String> {
+            notes
+                .into_iter()
+                .map(|n| {
+                    n.unwrap()
+                        .file_path
+                        .file_name()
+                        .unwrap()
+                        .to_string_lossy()
+                        .into_owned()
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            names(vault.with_property("status", "active").collect()),
+            vec!["a.md", "b.md"]
+        );
+        assert_eq!(
+            names(
+                vault
+                    .with_property_in("priority", Properties::from(2)..=10.into())
+                    .collect()
+            ),
+            vec!["b.md"]
+        );
+        assert_eq!(
+            names(
+                vault
+                    .with_property_in("due", ..Properties::from("2024-01-01"))
+                    .collect()
+            ),
+            vec!["c.md"]
+        );
+        assert_eq!(
+            names(vault.notes_where(|p| p.get("status").is_none()).collect()),
+            vec!["d.md"]
+        );
+    }
+}
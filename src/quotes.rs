@@ -0,0 +1,142 @@
+use std::ops::Range;
+
+use crate::{
+    callouts::strip_quote,
+    code::{fenced_ranges, in_ranges},
+    ObsidianNote,
+};
+
+/// A `>` blockquote that isn't a callout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blockquote {
+    /// The quote with one level of `>` removed and without its attribution line
+    pub text: String,
+    /// Who the quote is by, from a line like `— Seneca`
+    pub attribution: Option<String>,
+    /// Quotes nested in this one, with spans into [`Blockquote::text`]
+    pub nested: Vec<Blockquote>,
+    /// Byte range of the quoted lines, excluding the last line's newline
+    pub span: Range<usize>,
+}
+
+impl ObsidianNote {
+    pub fn blockquotes(&self) -> Vec<Blockquote> {
+        parse_blockquotes(&self.file_body)
+    }
+}
+
+/// Finds blockquotes outside code blocks. A quote's attribution is taken from its last line, or
+/// the line right after it, when that starts with a dash or `~`.
+pub fn parse_blockquotes(text: &str) -> Vec<Blockquote> {
+    let code = fenced_ranges(text);
+    let mut quotes = Vec::new();
+    let mut current: Option<(Range<usize>, Vec<&str>)> = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let content = line.trim_end_matches(['\n', '\r']);
+        let quoted = (!in_ranges(&code, start))
+            .then(|| strip_quote(content))
+            .flatten();
+
+        match (&mut current, quoted) {
+            (Some((span, lines)), Some(inner)) => {
+                lines.push(inner);
+                span.end = start + content.len();
+            }
+            (None, Some(inner)) => current = Some((start..start + content.len(), vec![inner])),
+            (Some(_), None) => {
+                let (span, lines) = current.take().unwrap_or_default();
+                quotes.extend(blockquote(span, lines, attribution(content)));
+            }
+            (None, None) => {}
+        }
+    }
+    if let Some((span, lines)) = current {
+        quotes.extend(blockquote(span, lines, None));
+    }
+
+    quotes
+}
+
+/// Builds a quote from its unquoted lines, unless it's a callout
+fn blockquote(
+    span: Range<usize>,
+    mut lines: Vec<&str>,
+    after: Option<String>,
+) -> Option<Blockquote> {
+    if lines.first()?.trim_start().starts_with("[!") {
+        return None;
+    }
+
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    let inside = lines.last().and_then(|line| attribution(line));
+    if inside.is_some() {
+        lines.pop();
+    }
+
+    let text = lines.join("\n").trim_end().to_string();
+    Some(Blockquote {
+        nested: parse_blockquotes(&text),
+        text,
+        attribution: inside.or(after),
+        span,
+    })
+}
+
+/// The name in an attribution line such as `— Seneca`, `-- Seneca` or `~ Seneca`
+fn attribution(line: &str) -> Option<String> {
+    let line = line.trim();
+    let name = ["—", "–", "--", "~"]
+        .iter()
+        .find_map(|dash| line.strip_prefix(dash))?
+        .trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn parse_blockquotes_reads_attribution() {
+        let text = indoc! {r"
+            > We suffer more often in imagination than in reality.
+            > — Seneca, *Letters*
+
+            > Stay hungry, stay foolish.
+            -- Stewart Brand
+
+            > [!note] Not a quote
+            > Callout body
+        "};
+
+        let quotes = parse_blockquotes(text);
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(
+            quotes[0].text,
+            "We suffer more often in imagination than in reality."
+        );
+        assert_eq!(quotes[0].attribution.as_deref(), Some("Seneca, *Letters*"));
+        assert!(text[quotes[0].span.clone()].ends_with("*Letters*"));
+        assert_eq!(quotes[1].text, "Stay hungry, stay foolish.");
+        assert_eq!(quotes[1].attribution.as_deref(), Some("Stewart Brand"));
+    }
+
+    #[test]
+    fn parse_blockquotes_nests_quotes() {
+        let quotes = parse_blockquotes("> Reply\n>\n> > Original\n> > ~ Ada\n");
+
+        assert_eq!(quotes.len(), 1);
+        assert_eq!(quotes[0].text, "Reply\n\n> Original\n> ~ Ada");
+        assert_eq!(quotes[0].attribution, None);
+        let nested = &quotes[0].nested;
+        assert_eq!(nested[0].text, "Original");
+        assert_eq!(nested[0].attribution.as_deref(), Some("Ada"));
+    }
+}
@@ -0,0 +1,332 @@
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+use crate::{
+    edit::TextEdit,
+    links::{parse_markdown_links, scan_wikilinks},
+    resolver::{nfc, normalize, relative_to},
+    vault::is_note,
+    Error, FileChange, LinkResolver, LinkStyle, NewLinkFormat, ObsidianNote, Vault,
+};
+
+/// Characters Obsidian percent-encodes in markdown link destinations
+pub(crate) const DESTINATION: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'#')
+    .add(b'%')
+    .add(b'(')
+    .add(b')')
+    .add(b'<')
+    .add(b'>')
+    .add(b'^');
+
+impl Vault {
+    /// Renames or moves a note, rewriting every wikilink, embed and markdown link that pointed
+    /// at it. Returns the (post-rename) paths of the notes whose links were updated.
+    ///
+    /// Wikilink targets are written in the vault's new link format, and markdown links stay
+    /// relative or vault-absolute as they were.
+    pub fn rename_note(
+        &self,
+        old: impl AsRef<Path>,
+        new: impl AsRef<Path>,
+    ) -> crate::Result<Vec<PathBuf>> {
+        let old = self.path.join(old);
+        if !old.is_file() {
+            return Err(Error::NoteNotFound(old));
+        }
+        self.rename_file(old, self.path.join(new))
+    }
+
+    /// Moves any file in the vault and rewrites the links to it, as [`Vault::rename_note`] does
+    pub(crate) fn rename_file(&self, old: PathBuf, new: PathBuf) -> crate::Result<Vec<PathBuf>> {
+        if new.exists() {
+            return Err(Error::AlreadyExists(new));
+        }
+
+        let notes = self.notes().collect::<crate::Result<Vec<_>>>()?;
+        let resolver = LinkResolver::from_vault_notes(self, &notes)?;
+        let renamer = Renamer {
+            vault: self,
+            resolver: &resolver,
+            style: self.link_style()?,
+            old: &old,
+            new: &new,
+        };
+
+        let mut changes = vec![FileChange::Rename {
+            from: old.clone(),
+            to: new.clone(),
+        }];
+        for mut note in notes {
+            let edits = renamer.link_edits(&note);
+            let destination = if note.file_path == old {
+                new.clone()
+            } else {
+                note.file_path.clone()
+            };
+            if !edits.is_empty() {
+                note.edit_body(&edits)?;
+                changes.push(FileChange::Write {
+                    path: destination,
+                    contents: note.to_string(),
+                });
+            }
+        }
+
+        let updated = changes[1..]
+            .iter()
+            .map(|change| change.path().to_path_buf())
+            .collect();
+        self.writer.apply(changes)?;
+        Ok(updated)
+    }
+}
+
+struct Renamer<'a> {
+    vault: &'a Vault,
+    resolver: &'a LinkResolver,
+    style: LinkStyle,
+    old: &'a Path,
+    new: &'a Path,
+}
+
+impl Renamer<'_> {
+    fn link_edits(&self, note: &ObsidianNote) -> Vec<TextEdit> {
+        let mut edits = Vec::new();
+
+        for (_, link) in scan_wikilinks(&note.file_body) {
+            // Links through an alias keep working after a rename, so leave them alone
+            if self.resolver.resolve_link(&link, &note.file_path) != Some(self.old)
+                || !same_name(&link.target, self.old)
+            {
+                continue;
+            }
+
+            let raw = &note.file_body[link.span.clone()];
+            let target_start = raw.find("[[").unwrap_or_default() + 2;
+            let Some(offset) = raw[target_start..].find(&link.target) else {
+                continue;
+            };
+            let start = link.span.start + target_start + offset;
+            edits.push(TextEdit::new(
+                start..start + link.target.len(),
+                self.wikilink_target(&link.target, note),
+            ));
+        }
+
+        for link in parse_markdown_links(&note.file_body) {
+            let Some(path) = link.path() else {
+                continue;
+            };
+            if self.resolver.resolve(&path, &note.file_path) != Some(self.old) {
+                continue;
+            }
+
+            let mut destination = self.markdown_destination(&path, note, &link.destination);
+            if let Some(fragment) = link.fragment() {
+                destination = format!("{destination}#{fragment}");
+            }
+            edits.push(TextEdit::new(link.destination_span, destination));
+        }
+
+        edits
+    }
+
+    fn wikilink_target(&self, old_target: &str, note: &ObsidianNote) -> String {
+        let relative = match self.style.format {
+            NewLinkFormat::Relative => relative_to(
+                self.new,
+                self.source(note).parent().unwrap_or(Path::new("")),
+            ),
+            NewLinkFormat::Shortest | NewLinkFormat::Absolute => {
+                self.vault.relative_path(self.new).to_path_buf()
+            }
+        };
+        let keep_extension = old_target.ends_with(".md") || !is_note(self.new);
+        let path = if keep_extension {
+            relative
+        } else {
+            relative.with_extension("")
+        };
+
+        let name = link_name(self.new);
+        let unique = self
+            .resolver
+            .files()
+            .iter()
+            .filter(|f| f.as_path() != self.old && link_name(f) == name)
+            .count()
+            == 0;
+
+        let shortest = self.style.format == NewLinkFormat::Shortest;
+        if shortest && unique && !old_target.contains('/') {
+            let name = path.file_name().unwrap_or_default();
+            name.to_string_lossy().into_owned()
+        } else {
+            path_to_link(&path)
+        }
+    }
+
+    /// Where `note` will be once the rename is done
+    fn source<'a>(&'a self, note: &'a ObsidianNote) -> &'a Path {
+        if note.file_path == self.old {
+            self.new
+        } else {
+            &note.file_path
+        }
+    }
+
+    fn markdown_destination(&self, old_path: &str, note: &ObsidianNote, raw: &str) -> String {
+        let source = self.source(note);
+        let source_dir = note.file_path.parent().unwrap_or(Path::new(""));
+
+        // Keep relative links relative, and vault-absolute links absolute
+        let is_relative = normalize(&source_dir.join(old_path)) == self.old;
+        let path = if is_relative {
+            relative_to(self.new, source.parent().unwrap_or(Path::new("")))
+        } else {
+            self.vault.relative_path(self.new).to_path_buf()
+        };
+
+        let path = path_to_link(&path);
+        if raw.contains(' ') {
+            path
+        } else {
+            utf8_percent_encode(&path, DESTINATION).to_string()
+        }
+    }
+}
+
+pub(crate) fn same_name(target: &str, path: &Path) -> bool {
+    let target = target.strip_suffix(".md").unwrap_or(target);
+    let target_name = target.rsplit('/').next().unwrap_or(target);
+    link_name(path)
+        .is_some_and(|name| nfc(&name.to_string_lossy()).eq_ignore_ascii_case(&nfc(target_name)))
+}
+
+/// The name a file is linked by: a note's stem, or an attachment's full file name
+pub(crate) fn link_name(path: &Path) -> Option<&OsStr> {
+    if is_note(path) {
+        path.file_stem()
+    } else {
+        path.file_name()
+    }
+}
+
+/// A path with `/` separators, as used in link text on every platform
+pub(crate) fn path_to_link(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::vault_with_files;
+    use indoc::indoc;
+    use std::fs;
+
+    fn read(vault: &Vault, path: &str) -> String {
+        fs::read_to_string(vault.path.join(path)).unwrap()
+    }
+
+    #[test]
+    fn rename_note_rewrites_wikilinks_and_embeds() {
+        let (_dir, vault) = vault_with_files(&[
+            ("Old.md", "# Old"),
+            (
+                "source.md",
+                "[[Old]] [[Old|Alias]] [[Old#Heading]] ![[Old^block]] [[Other]]\n",
+            ),
+            ("Other.md", ""),
+        ]);
+
+        let updated = vault.rename_note("Old.md", "New.md").unwrap();
+
+        assert_eq!(updated, vec![vault.path.join("source.md")]);
+        assert!(vault.path.join("New.md").is_file());
+        assert!(!vault.path.join("Old.md").exists());
+        assert_eq!(
+            read(&vault, "source.md"),
+            "[[New]] [[New|Alias]] [[New#Heading]] ![[New^block]] [[Other]]\n"
+        );
+    }
+
+    #[test]
+    fn rename_note_uses_paths_when_name_is_ambiguous() {
+        let (_dir, vault) = vault_with_files(&[
+            ("a/Old.md", ""),
+            ("b/New.md", ""),
+            ("source.md", "[[Old]] [[a/Old]]"),
+        ]);
+
+        vault.rename_note("a/Old.md", "a/New.md").unwrap();
+        assert_eq!(read(&vault, "source.md"), "[[a/New]] [[a/New]]");
+    }
+
+    #[test]
+    fn rename_note_rewrites_markdown_links() {
+        let (_dir, vault) = vault_with_files(&[
+            ("notes/Old Note.md", ""),
+            (
+                "notes/source.md",
+                indoc! {r"
+                    [relative](Old%20Note.md#Section) [absolute](notes/Old%20Note.md)
+                    [angle](<Old Note.md>) [external](https://example.com/Old%20Note.md)
+                "},
+            ),
+        ]);
+
+        vault
+            .rename_note("notes/Old Note.md", "archive/New Note.md")
+            .unwrap();
+
+        assert_eq!(
+            read(&vault, "notes/source.md"),
+            indoc! {r"
+                [relative](../archive/New%20Note.md#Section) [absolute](archive/New%20Note.md)
+                [angle](<../archive/New Note.md>) [external](https://example.com/Old%20Note.md)
+            "}
+        );
+    }
+
+    #[test]
+    fn rename_note_follows_relative_link_format() {
+        let (_dir, vault) = vault_with_files(&[
+            (".obsidian/app.json", r#"{"newLinkFormat": "relative"}"#),
+            ("a/Old.md", ""),
+            ("b/source.md", "[[Old]] ![[Old#Part]]"),
+        ]);
+
+        vault.rename_note("a/Old.md", "c/New.md").unwrap();
+        assert_eq!(
+            read(&vault, "b/source.md"),
+            "[[../c/New]] ![[../c/New#Part]]"
+        );
+    }
+
+    #[test]
+    fn rename_note_leaves_alias_links() {
+        let (_dir, vault) = vault_with_files(&[
+            ("Old.md", "---\naliases: [Nickname]\n---\n"),
+            ("source.md", "[[Nickname]]"),
+        ]);
+
+        let updated = vault.rename_note("Old.md", "New.md").unwrap();
+        assert!(updated.is_empty());
+        assert_eq!(read(&vault, "source.md"), "[[Nickname]]");
+    }
+
+    #[test]
+    fn rename_note_refuses_to_overwrite() {
+        let (_dir, vault) = vault_with_files(&[("Old.md", ""), ("New.md", "")]);
+        assert!(vault.rename_note("Old.md", "New.md").is_err());
+    }
+}
@@ -0,0 +1,422 @@
+use percent_encoding::utf8_percent_encode;
+use pulldown_cmark::{
+    html, CodeBlockKind, CowStr, Event, LinkType, Parser, Tag, TagEnd, TextMergeWithOffset,
+};
+
+use crate::{
+    ast::options,
+    callouts::parse_callouts,
+    comments::strip_comments,
+    diagrams::{is_diagram_language, parse_diagrams},
+    graph::escape_xml,
+    headings::{atx_heading, heading_anchor},
+    links::scan_wikilinks,
+    rename::DESTINATION,
+    tags::parse_inline_tags,
+    Diagram, Embed, Fold, HeadingAnchors, ObsidianNote, TagSource, WikiLink,
+};
+
+type Href<'a, T> = Box<dyn Fn(&T) -> Option<String> + 'a>;
+
+/// Renders Obsidian-flavored markdown to HTML
+///
+/// Wikilinks and embeds get their `href`/`src` from [`Renderer::link_href`], and a link whose
+/// href is `None` is rendered with the `is-unresolved` class. Headings get `id`s from
+/// [`HeadingAnchors`].
+pub struct Renderer<'a> {
+    link_href: Href<'a, WikiLink>,
+    tag_href: Href<'a, str>,
+    diagram_html: Href<'a, Diagram>,
+}
+
+impl Default for Renderer<'_> {
+    fn default() -> Self {
+        Self {
+            link_href: Box::new(|link| Some(default_href(link))),
+            tag_href: Box::new(|tag| Some(format!("#{}", utf8_percent_encode(tag, DESTINATION)))),
+            diagram_html: Box::new(|_| None),
+        }
+    }
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn link_href(mut self, href: impl Fn(&WikiLink) -> Option<String> + 'a) -> Self {
+        self.link_href = Box::new(href);
+        self
+    }
+
+    /// Where tags link to, or `None` to render them as plain spans
+    pub fn tag_href(mut self, href: impl Fn(&str) -> Option<String> + 'a) -> Self {
+        self.tag_href = Box::new(href);
+        self
+    }
+
+    /// HTML to show in place of a diagram's code block, such as an inline SVG rendered from it,
+    /// or `None` to leave it as code
+    pub fn diagram_html(mut self, html: impl Fn(&Diagram) -> Option<String> + 'a) -> Self {
+        self.diagram_html = Box::new(html);
+        self
+    }
+
+    /// Renders `text`, leaving out its `%%` comments as Obsidian's reading view does
+    pub fn render(&self, text: &str) -> String {
+        let mut output = String::new();
+        let text = strip_comments(text);
+        self.render_into(&text, &mut HeadingAnchors::default(), &mut output);
+        output
+    }
+
+    fn render_into(&self, text: &str, anchors: &mut HeadingAnchors, output: &mut String) {
+        let mut cursor = 0;
+
+        for callout in parse_callouts(text) {
+            self.render_markdown(&text[cursor..callout.span.start], anchors, output);
+            cursor = callout.span.end;
+
+            let fold = match callout.fold {
+                Some(Fold::Expanded) => " data-callout-fold=\"+\"",
+                Some(Fold::Collapsed) => " data-callout-fold=\"-\"",
+                None => "",
+            };
+            let title = callout.title.unwrap_or_else(|| capitalize(&callout.kind));
+            output.push_str(&format!(
+                "<div class=\"callout\" data-callout=\"{}\"{fold}>\n<div class=\"callout-title\">{}</div>\n<div class=\"callout-content\">\n",
+                escape_xml(&callout.kind),
+                escape_xml(&title),
+            ));
+            self.render_into(&callout.body, anchors, output);
+            output.push_str("</div>\n</div>\n");
+        }
+        self.render_markdown(&text[cursor..], anchors, output);
+    }
+
+    fn render_markdown(&self, text: &str, anchors: &mut HeadingAnchors, output: &mut String) {
+        if text.trim().is_empty() {
+            return;
+        }
+
+        let mut events = Vec::new();
+        let mut in_code_block = false;
+        let mut in_diagram = false;
+        let mut in_link = false;
+        let mut embed: Option<WikiLink> = None;
+
+        // Merged so highlights and tags split across text events are still found
+        let parser = Parser::new_ext(text, options()).into_offset_iter();
+        for (event, span) in TextMergeWithOffset::new(parser) {
+            match event {
+                Event::Start(Tag::Link {
+                    link_type: LinkType::WikiLink { .. },
+                    ..
+                }) => {
+                    let link = wikilink_at(text, span);
+                    in_link = true;
+                    events.push(Event::InlineHtml(CowStr::from(
+                        match (self.link_href)(&link) {
+                            Some(href) => format!(
+                                "<a href=\"{}\" class=\"internal-link\">",
+                                escape_xml(&href)
+                            ),
+                            None => "<a class=\"internal-link is-unresolved\">".to_string(),
+                        },
+                    )));
+                }
+                Event::End(TagEnd::Link) if in_link => {
+                    in_link = false;
+                    events.push(Event::InlineHtml(CowStr::from("</a>")));
+                }
+                Event::Start(Tag::Image {
+                    link_type: LinkType::WikiLink { .. },
+                    ..
+                }) => embed = Some(wikilink_at(text, span)),
+                Event::End(TagEnd::Image) if embed.is_some() => {
+                    let link = embed.take().unwrap_or_default();
+                    events.push(Event::InlineHtml(CowStr::from(self.embed_html(link))));
+                }
+                _ if embed.is_some() => {}
+                Event::Start(Tag::Heading {
+                    level,
+                    id: None,
+                    classes,
+                    attrs,
+                }) => {
+                    let line = text[span].lines().next().unwrap_or_default();
+                    let heading = atx_heading(line).map_or(line.trim(), |(_, text)| text);
+                    events.push(Event::Start(Tag::Heading {
+                        level,
+                        id: Some(CowStr::from(anchors.next(heading))),
+                        classes,
+                        attrs,
+                    }));
+                }
+                Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(ref info)))
+                    if is_diagram_language(info.split_whitespace().next().unwrap_or_default()) =>
+                {
+                    let html = parse_diagrams(&text[span.clone()])
+                        .into_iter()
+                        .next()
+                        .and_then(|diagram| (self.diagram_html)(&diagram));
+                    match html {
+                        Some(html) => {
+                            in_diagram = true;
+                            events.push(Event::Html(CowStr::from(html)));
+                        }
+                        None => {
+                            in_code_block = true;
+                            events.push(event);
+                        }
+                    }
+                }
+                Event::End(TagEnd::CodeBlock) if in_diagram => in_diagram = false,
+                _ if in_diagram => {}
+                Event::Start(Tag::CodeBlock(_)) => {
+                    in_code_block = true;
+                    events.push(event);
+                }
+                Event::End(TagEnd::CodeBlock) => {
+                    in_code_block = false;
+                    events.push(event);
+                }
+                Event::Text(text) if !in_code_block && !in_link => {
+                    events.push(match self.inline_html(&text) {
+                        Some(html) => Event::InlineHtml(CowStr::from(html)),
+                        None => Event::Text(text),
+                    });
+                }
+                event => events.push(event),
+            }
+        }
+
+        html::push_html(output, events.into_iter());
+    }
+
+    fn embed_html(&self, link: WikiLink) -> String {
+        let href = (self.link_href)(&link);
+        let label = link.alias.clone().unwrap_or_else(|| link.target.clone());
+
+        match (Embed::from_link(link.clone()), href) {
+            (Embed::Image(_), Some(src)) => {
+                // `![[image.png|300]]` and `![[image.png|300x200]]` set the size
+                let size = link.alias.as_deref().and_then(|alias| {
+                    let (width, height) = alias.split_once('x').unwrap_or((alias, ""));
+                    width.parse::<u32>().ok()?;
+                    Some(match height.parse::<u32>() {
+                        Ok(height) => format!(" width=\"{width}\" height=\"{height}\""),
+                        Err(_) => format!(" width=\"{width}\""),
+                    })
+                });
+                let alt = if size.is_some() { &link.target } else { &label };
+                format!(
+                    "<img src=\"{}\" alt=\"{}\"{}>",
+                    escape_xml(&src),
+                    escape_xml(alt),
+                    size.unwrap_or_default()
+                )
+            }
+            (_, Some(href)) => format!(
+                "<span class=\"internal-embed\"><a href=\"{}\" class=\"internal-link\">{}</a></span>",
+                escape_xml(&href),
+                escape_xml(&label)
+            ),
+            (_, None) => format!(
+                "<span class=\"internal-embed is-unresolved\">{}</span>",
+                escape_xml(&label)
+            ),
+        }
+    }
+
+    /// Text with highlights and tags marked up, or `None` if it has neither
+    fn inline_html(&self, text: &str) -> Option<String> {
+        if !text.contains("==") && !text.contains('#') {
+            return None;
+        }
+
+        let mut segments: Vec<&str> = text.split("==").collect();
+        // An unpaired `==` is literal text
+        let unpaired = segments.len().is_multiple_of(2);
+        let trailing = if unpaired { segments.pop() } else { None };
+
+        let mut html = String::new();
+        for (i, segment) in segments.iter().enumerate() {
+            let highlighted = i % 2 == 1;
+            if highlighted {
+                html.push_str("<mark>");
+            }
+            html.push_str(&self.tags_html(segment));
+            if highlighted {
+                html.push_str("</mark>");
+            }
+        }
+        if let Some(trailing) = trailing {
+            html.push_str("==");
+            html.push_str(&self.tags_html(trailing));
+        }
+
+        Some(html)
+    }
+
+    fn tags_html(&self, text: &str) -> String {
+        let mut html = String::new();
+        let mut cursor = 0;
+
+        for tag in parse_inline_tags(text) {
+            let TagSource::Inline(span) = tag.source else {
+                continue;
+            };
+            html.push_str(&escape_xml(&text[cursor..span.start]));
+            cursor = span.end;
+
+            let name = escape_xml(&tag.name);
+            html.push_str(&match (self.tag_href)(&tag.name) {
+                Some(href) => format!(
+                    "<a href=\"{}\" class=\"tag\">#{name}</a>",
+                    escape_xml(&href)
+                ),
+                None => format!("<span class=\"tag\">#{name}</span>"),
+            });
+        }
+        html.push_str(&escape_xml(&text[cursor..]));
+
+        html
+    }
+}
+
+impl ObsidianNote {
+    pub fn render_html(&self, renderer: &Renderer) -> String {
+        renderer.render(&self.file_body)
+    }
+}
+
+/// Renders with the default [`Renderer`], linking to targets as written
+pub fn render_html(text: &str) -> String {
+    Renderer::default().render(text)
+}
+
+pub(crate) fn wikilink_at(text: &str, span: std::ops::Range<usize>) -> WikiLink {
+    scan_wikilinks(&text[span])
+        .into_iter()
+        .next()
+        .map(|(_, link)| link)
+        .unwrap_or_default()
+}
+
+fn default_href(link: &WikiLink) -> String {
+    let mut href = utf8_percent_encode(&link.target, DESTINATION).to_string();
+    if let Some(heading) = &link.heading {
+        href.push('#');
+        href.push_str(&heading_anchor(heading));
+    } else if let Some(block) = &link.block {
+        href.push_str("#^");
+        href.push_str(&utf8_percent_encode(block, DESTINATION).to_string());
+    }
+    href
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn render_resolves_wikilinks_through_callback() {
+        let renderer = Renderer::new().link_href(|link| {
+            (link.target == "Known").then(|| format!("/notes/{}.html", link.target))
+        });
+        let html = renderer.render("See [[Known|this]] and [[Missing]].");
+
+        assert_eq!(
+            html,
+            "<p>See <a href=\"/notes/Known.html\" class=\"internal-link\">this</a> and \
+             <a class=\"internal-link is-unresolved\">Missing</a>.</p>\n"
+        );
+    }
+
+    #[test]
+    fn render_links_headings_by_anchor() {
+        let html =
+            render_html("# Plan: v2\n\n## Plan: v2\n\nSee [[#Plan v2]] and [[Other#Big Idea!]]");
+        assert_eq!(
+            html,
+            "<h1 id=\"plan-v2\">Plan: v2</h1>\n<h2 id=\"plan-v2-1\">Plan: v2</h2>\n\
+             <p>See <a href=\"#plan-v2\" class=\"internal-link\">#Plan v2</a> and \
+             <a href=\"Other#big-idea\" class=\"internal-link\">Other#Big Idea!</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn render_hides_comments() {
+        let html = render_html("Shown %%hidden%% text\n%%\nprivate\n%%\n`%%code%%`");
+        assert_eq!(html, "<p>Shown  text\n<code>%%code%%</code></p>\n");
+    }
+
+    #[test]
+    fn render_replaces_diagrams_through_callback() {
+        let renderer = Renderer::new().diagram_html(|diagram| {
+            (diagram.language == "mermaid").then(|| format!("<svg>{}</svg>", diagram.source.trim()))
+        });
+        let html = renderer.render("```mermaid\nA --> B\n```\n\n```dot\ndigraph {}\n```");
+        assert_eq!(
+            html,
+            "<svg>A --> B</svg>\n<pre><code class=\"language-dot\">digraph {}\n</code></pre>\n"
+        );
+    }
+
+    #[test]
+    fn render_handles_embeds() {
+        let html = render_html("![[photo one.png|300]] ![[Other note]]");
+        assert_eq!(
+            html,
+            "<p><img src=\"photo%20one.png\" alt=\"photo one.png\" width=\"300\"> \
+             <span class=\"internal-embed\"><a href=\"Other%20note\" class=\"internal-link\">Other note</a></span></p>\n"
+        );
+    }
+
+    #[test]
+    fn render_marks_highlights_and_tags() {
+        let html = Renderer::new()
+            .tag_href(|_| None)
+            .render("Some ==important [& urgent]== with #tag/nested\n\n`==code== #not`");
+        assert_eq!(
+            html,
+            "<p>Some <mark>important [&amp; urgent]</mark> with <span class=\"tag\">#tag/nested</span></p>\n\
+             <p><code>==code== #not</code></p>\n"
+        );
+    }
+
+    #[test]
+    fn render_wraps_callouts() {
+        let html = render_html(indoc! {r"
+            Before
+
+            > [!warning]- Careful
+            > Body with **bold**
+
+            After
+        "});
+        assert_eq!(
+            html,
+            indoc! {r#"
+                <p>Before</p>
+                <div class="callout" data-callout="warning" data-callout-fold="-">
+                <div class="callout-title">Careful</div>
+                <div class="callout-content">
+                <p>Body with <strong>bold</strong></p>
+                </div>
+                </div>
+                <p>After</p>
+            "#}
+        );
+    }
+}
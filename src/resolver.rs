@@ -0,0 +1,458 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+};
+
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+use crate::{Error, ObsidianNote, Vault, WikiLink};
+
+/// Resolves link targets to files the way Obsidian does, given every file in the vault
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LinkResolver {
+    root: PathBuf,
+    files: Vec<PathBuf>,
+    aliases: HashMap<String, Vec<PathBuf>>,
+    /// Whether file names match regardless of case, as on macOS and Windows
+    case_insensitive: bool,
+    index: FileIndex,
+}
+
+/// The files' components normalized for matching, built once so lookups don't rescan them
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct FileIndex {
+    /// Each file's key, in the same order as `files`
+    keys: Vec<Vec<String>>,
+    /// The first file with each key
+    by_path: HashMap<Vec<String>, usize>,
+    /// Files by the last component of their key, in order
+    by_name: HashMap<String, Vec<usize>>,
+}
+
+/// What a link target resolves to, see [`LinkResolver::resolve_target`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution<'a> {
+    Found(&'a Path),
+    /// Several files match equally by name, most preferred first. Obsidian opens the first.
+    Ambiguous(Vec<&'a Path>),
+    NotFound,
+}
+
+impl<'a> Resolution<'a> {
+    /// The file Obsidian would open
+    pub fn path(&self) -> Option<&'a Path> {
+        match self {
+            Self::Found(path) => Some(path),
+            Self::Ambiguous(paths) => paths.first().copied(),
+            Self::NotFound => None,
+        }
+    }
+
+    pub fn is_ambiguous(&self) -> bool {
+        matches!(self, Self::Ambiguous(_))
+    }
+}
+
+impl<'a> From<Vec<&'a Path>> for Resolution<'a> {
+    fn from(mut paths: Vec<&'a Path>) -> Self {
+        match paths.len() {
+            0 => Self::NotFound,
+            1 => Self::Found(paths.remove(0)),
+            _ => Self::Ambiguous(paths),
+        }
+    }
+}
+
+impl LinkResolver {
+    /// `files` may be relative to `root` or include it, as long as sources passed to
+    /// [`LinkResolver::resolve`] use the same form
+    pub fn new(root: impl Into<PathBuf>, files: impl IntoIterator<Item = PathBuf>) -> Self {
+        let mut files: Vec<PathBuf> = files.into_iter().collect();
+        files.sort();
+
+        let mut resolver = Self {
+            root: root.into(),
+            files,
+            aliases: HashMap::new(),
+            case_insensitive: false,
+            index: FileIndex::default(),
+        };
+        resolver.build_index();
+        resolver
+    }
+
+    /// Matches file names regardless of case, as on case-insensitive filesystems
+    pub fn with_case_insensitive(mut self, case_insensitive: bool) -> Self {
+        if self.case_insensitive != case_insensitive {
+            self.case_insensitive = case_insensitive;
+            self.build_index();
+        }
+        self
+    }
+
+    fn build_index(&mut self) {
+        let keys: Vec<Vec<String>> = self.files.iter().map(|file| self.key(file)).collect();
+        let mut index = FileIndex::default();
+        for (i, key) in keys.iter().enumerate() {
+            index.by_path.entry(key.clone()).or_insert(i);
+            if let Some(name) = key.last() {
+                index.by_name.entry(name.clone()).or_default().push(i);
+            }
+        }
+        index.keys = keys;
+        self.index = index;
+    }
+
+    pub fn from_vault(vault: &Vault) -> crate::Result<Self> {
+        let notes = vault.notes().collect::<crate::Result<Vec<_>>>()?;
+        Self::from_vault_notes(vault, &notes)
+    }
+
+    /// Like [`LinkResolver::from_vault`], reusing notes that have already been read
+    pub fn from_vault_notes(vault: &Vault, notes: &[ObsidianNote]) -> crate::Result<Self> {
+        let files = vault.files().collect::<crate::Result<Vec<_>>>()?;
+        let mut resolver = Self::new(&vault.path, files);
+        for note in notes {
+            resolver.add_note_aliases(note);
+        }
+        Ok(resolver)
+    }
+
+    pub fn from_notes(notes: &[ObsidianNote]) -> Self {
+        let mut resolver = Self::new("", notes.iter().map(|n| n.file_path.clone()));
+        for note in notes {
+            resolver.add_note_aliases(note);
+        }
+        resolver
+    }
+
+    pub fn add_alias(&mut self, alias: &str, path: &Path) {
+        self.aliases
+            .entry(nfc(&alias.to_lowercase()).into_owned())
+            .or_default()
+            .push(path.to_path_buf());
+    }
+
+    fn add_note_aliases(&mut self, note: &ObsidianNote) {
+        for alias in &note.aliases() {
+            self.add_alias(alias, &note.file_path);
+        }
+    }
+
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    pub fn resolve_link(&self, link: &WikiLink, source: &Path) -> Option<&Path> {
+        self.resolve(&link.target, source)
+    }
+
+    /// The file a link target from `source` points to. An empty target refers to the source
+    /// itself, as in `[[#Heading]]`.
+    pub fn resolve(&self, target: &str, source: &Path) -> Option<&Path> {
+        self.resolve_target(target, source).path()
+    }
+
+    /// Like [`LinkResolver::resolve`], failing with [`Error::UnresolvedLink`] when the target
+    /// doesn't match any file
+    pub fn try_resolve(&self, target: &str, source: &Path) -> crate::Result<&Path> {
+        self.resolve(target, source)
+            .ok_or_else(|| Error::UnresolvedLink {
+                note: source.to_path_buf(),
+                target: target.to_string(),
+            })
+    }
+
+    /// Like [`LinkResolver::resolve`], reporting targets that several files match by name
+    /// instead of picking one
+    pub fn resolve_target(&self, target: &str, source: &Path) -> Resolution<'_> {
+        let target = target.trim();
+        if target.is_empty() {
+            return self
+                .find_exact(source)
+                .map_or(Resolution::NotFound, Resolution::Found);
+        }
+
+        let candidates = [PathBuf::from(target), PathBuf::from(format!("{target}.md"))];
+        candidates
+            .iter()
+            .map(|candidate| self.resolve_path(candidate, source))
+            .find(|matches| !matches.is_empty())
+            .unwrap_or_else(|| self.resolve_alias(target))
+            .into()
+    }
+
+    fn resolve_path(&self, target: &Path, source: &Path) -> Vec<&Path> {
+        let source_dir = source.parent().unwrap_or(Path::new(""));
+
+        // Paths from the vault root, then paths relative to the linking note
+        let exact = [
+            normalize(&self.root.join(target.strip_prefix("/").unwrap_or(target))),
+            normalize(&source_dir.join(target)),
+        ];
+        if let Some(file) = exact.iter().find_map(|path| self.find_exact(path)) {
+            return vec![file];
+        }
+
+        // Otherwise paths ending with the target, the shortest first, preferring the source's
+        // folder
+        let target = self.key(target);
+        let Some(candidates) = target.last().and_then(|name| self.index.by_name.get(name)) else {
+            return Vec::new();
+        };
+        let mut matches: Vec<&Path> = candidates
+            .iter()
+            .filter(|&&i| self.index.keys[i].ends_with(&target))
+            .map(|&i| self.files[i].as_path())
+            .collect();
+        matches.sort_by_key(|file| (file.parent() != Some(source_dir), file.components().count()));
+        matches
+    }
+
+    /// The file at `path`. Names in different Unicode normal forms match, since macOS stores
+    /// them decomposed.
+    fn find_exact(&self, path: &Path) -> Option<&Path> {
+        self.index
+            .by_path
+            .get(&self.key(path))
+            .map(|&i| self.files[i].as_path())
+    }
+
+    /// `path`'s components as files are matched on: NFC, and lowercase unless case matters
+    fn key(&self, path: &Path) -> Vec<String> {
+        path.components()
+            .map(|c| {
+                let name = c.as_os_str().to_string_lossy();
+                let name = nfc(&name);
+                if self.case_insensitive {
+                    name.to_lowercase()
+                } else {
+                    name.into_owned()
+                }
+            })
+            .collect()
+    }
+
+    fn resolve_alias(&self, target: &str) -> Vec<&Path> {
+        self.aliases
+            .get(nfc(&target.to_lowercase()).as_ref())
+            .map(|paths| paths.iter().map(PathBuf::as_path).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl Vault {
+    pub fn link_resolver(&self) -> crate::Result<LinkResolver> {
+        LinkResolver::from_vault(self)
+    }
+}
+
+/// `text` in Unicode Normalization Form C, borrowed when it already is
+pub(crate) fn nfc(text: &str) -> Cow<'_, str> {
+    if is_nfc_quick(text.chars()) == IsNormalized::Yes {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(text.nfc().collect())
+    }
+}
+
+/// Lexically resolves `.` and `..` components
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// `path` expressed relative to the directory `base`, using `..` where needed
+pub(crate) fn relative_to(path: &Path, base: &Path) -> PathBuf {
+    let path: Vec<Component> = path.components().collect();
+    let base: Vec<Component> = base.components().collect();
+    let common = path.iter().zip(&base).take_while(|(a, b)| a == b).count();
+
+    let mut relative = PathBuf::new();
+    for _ in common..base.len() {
+        relative.push("..");
+    }
+    for component in &path[common..] {
+        relative.push(component);
+    }
+    relative
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolver(files: &[&str]) -> LinkResolver {
+        LinkResolver::new("", files.iter().map(PathBuf::from))
+    }
+
+    #[test]
+    fn resolve_matches_basenames_and_infers_extension() {
+        let resolver = resolver(&["folder/Note.md", "image.png", "Note.v2.md"]);
+        let source = Path::new("source.md");
+
+        assert_eq!(
+            resolver.resolve("Note", source),
+            Some(Path::new("folder/Note.md"))
+        );
+        assert_eq!(
+            resolver.resolve("Note.md", source),
+            Some(Path::new("folder/Note.md"))
+        );
+        assert_eq!(
+            resolver.resolve("image.png", source),
+            Some(Path::new("image.png"))
+        );
+        assert_eq!(
+            resolver.resolve("Note.v2", source),
+            Some(Path::new("Note.v2.md"))
+        );
+        assert_eq!(resolver.resolve("Missing", source), None);
+    }
+
+    #[test]
+    fn resolve_prefers_shortest_path_then_same_folder() {
+        let resolver = resolver(&["a/b/Note.md", "c/Note.md", "d/e/Note.md"]);
+
+        assert_eq!(
+            resolver.resolve("Note", Path::new("x.md")),
+            Some(Path::new("c/Note.md"))
+        );
+        assert_eq!(
+            resolver.resolve("Note", Path::new("d/e/source.md")),
+            Some(Path::new("d/e/Note.md"))
+        );
+    }
+
+    #[test]
+    fn resolve_handles_subfolder_and_relative_paths() {
+        let resolver = resolver(&["one/Note.md", "two/Note.md", "two/sub/Other.md"]);
+
+        assert_eq!(
+            resolver.resolve("two/Note", Path::new("x.md")),
+            Some(Path::new("two/Note.md"))
+        );
+        assert_eq!(
+            resolver.resolve("/one/Note", Path::new("x.md")),
+            Some(Path::new("one/Note.md"))
+        );
+        assert_eq!(
+            resolver.resolve("../Note", Path::new("two/sub/Other.md")),
+            Some(Path::new("two/Note.md"))
+        );
+    }
+
+    #[test]
+    fn resolve_falls_back_to_aliases() {
+        let note = ObsidianNote::parse(
+            Path::new("People/Ada Lovelace.md"),
+            "---\naliases: [Ada, Countess]\n---\n".to_string(),
+        )
+        .unwrap();
+        let resolver = LinkResolver::from_notes(&[note]);
+
+        assert_eq!(
+            resolver.resolve("ada", Path::new("x.md")),
+            Some(Path::new("People/Ada Lovelace.md"))
+        );
+    }
+
+    #[test]
+    fn resolve_target_reports_ambiguity_and_ignores_case() {
+        let resolver = resolver(&["a/Note.md", "b/c/Note.md", "Other.md"]);
+        let source = Path::new("x.md");
+
+        assert_eq!(
+            resolver.resolve_target("Note", source),
+            Resolution::Ambiguous(vec![Path::new("a/Note.md"), Path::new("b/c/Note.md")])
+        );
+        assert_eq!(
+            resolver.resolve_target("b/c/Note", source),
+            Resolution::Found(Path::new("b/c/Note.md"))
+        );
+        assert_eq!(
+            resolver.resolve_target("other", source),
+            Resolution::NotFound
+        );
+
+        let resolver = resolver.with_case_insensitive(true);
+        assert_eq!(
+            resolver.resolve_target("other", source),
+            Resolution::Found(Path::new("Other.md"))
+        );
+        assert_eq!(
+            resolver.resolve("B/C/NOTE.md", source),
+            Some(Path::new("b/c/Note.md"))
+        );
+    }
+
+    #[test]
+    fn resolve_matches_across_unicode_normal_forms() {
+        let decomposed = "Cafe\u{301}.md";
+        let resolver = resolver(&[decomposed, "Ne\u{301}e/Plan.md"]);
+        let source = Path::new("x.md");
+
+        assert_eq!(
+            resolver.resolve("Caf\u{e9}", source),
+            Some(Path::new(decomposed))
+        );
+        assert_eq!(
+            resolver.resolve("N\u{e9}e/Plan", source),
+            Some(Path::new("Ne\u{301}e/Plan.md"))
+        );
+    }
+
+    #[test]
+    fn resolve_indexes_names_in_one_normal_form() {
+        let resolver =
+            resolver(&["Notes/Caf\u{e9}.md", "Ne\u{301}e.png"]).with_case_insensitive(true);
+        let source = Path::new("x.md");
+
+        assert_eq!(
+            resolver.resolve("notes/CAFE\u{301}", source),
+            Some(Path::new("Notes/Caf\u{e9}.md"))
+        );
+        assert_eq!(
+            resolver.resolve("N\u{c9}E.png", source),
+            Some(Path::new("Ne\u{301}e.png"))
+        );
+        assert_eq!(
+            resolver.resolve("Caf\u{e9}", Path::new("Notes/Caf\u{e9}.md")),
+            Some(Path::new("Notes/Caf\u{e9}.md"))
+        );
+    }
+
+    #[test]
+    fn resolve_empty_target_is_source() {
+        let resolver = resolver(&["Note.md"]);
+        assert_eq!(
+            resolver.resolve("", Path::new("Note.md")),
+            Some(Path::new("Note.md"))
+        );
+    }
+
+    #[test]
+    fn try_resolve_reports_unresolved_links() {
+        let resolver = resolver(&["Note.md"]);
+        let source = Path::new("Note.md");
+
+        assert_eq!(
+            resolver.try_resolve("Note", source).unwrap(),
+            Path::new("Note.md")
+        );
+        assert!(matches!(
+            resolver.try_resolve("Missing", source),
+            Err(Error::UnresolvedLink { note, target }) if note == source && target == "Missing"
+        ));
+    }
+}
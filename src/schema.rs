@@ -0,0 +1,550 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::PathBuf,
+};
+
+use serde_json::{json, Value};
+
+use crate::{frontmatter::property_offset, ObsidianNote, Properties, Vault};
+
+/// The property types Obsidian's properties view knows about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PropertyType {
+    Text,
+    List,
+    Number,
+    Checkbox,
+    /// A `YYYY-MM-DD` string
+    Date,
+    /// A `YYYY-MM-DDTHH:MM` string, optionally with seconds
+    DateTime,
+}
+
+impl PropertyType {
+    /// The most specific type of a value, or `None` for nulls and nested mappings
+    pub fn of(value: &Properties) -> Option<Self> {
+        [
+            Self::Date,
+            Self::DateTime,
+            Self::Text,
+            Self::List,
+            Self::Number,
+            Self::Checkbox,
+        ]
+        .into_iter()
+        .find(|kind| kind.matches(value))
+    }
+
+    /// Whether a (non-null) value has this type
+    pub fn matches(self, value: &Properties) -> bool {
+        match self {
+            Self::Text => value.is_string(),
+            Self::List => value.is_sequence(),
+            Self::Number => value.is_number(),
+            Self::Checkbox => value.is_bool(),
+            Self::Date => value.as_str().is_some_and(is_date),
+            Self::DateTime => value.as_str().is_some_and(is_datetime),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertySchema {
+    pub key: String,
+    pub kind: PropertyType,
+    pub required: bool,
+    /// The values the property may take, checked against each item of a list
+    pub allowed: Option<Vec<Properties>>,
+}
+
+impl PropertySchema {
+    pub fn new(key: &str, kind: PropertyType) -> Self {
+        Self {
+            key: key.to_string(),
+            kind,
+            required: false,
+            allowed: None,
+        }
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn allowed(mut self, values: impl IntoIterator<Item = impl Into<Properties>>) -> Self {
+        self.allowed = Some(values.into_iter().map(Into::into).collect());
+        self
+    }
+}
+
+/// Rules for the frontmatter of every note, checked with [`Schema::validate`]
+///
+/// Properties without a rule are allowed, and empty (null) values only count against
+/// `required`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Schema {
+    pub properties: Vec<PropertySchema>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub path: PathBuf,
+    pub key: String,
+    /// The 1-based line of the property in the file, if it's present
+    pub line: Option<usize>,
+    pub kind: ViolationKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViolationKind {
+    Missing,
+    WrongType {
+        expected: PropertyType,
+        found: Properties,
+    },
+    NotAllowed(Properties),
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn property(mut self, property: PropertySchema) -> Self {
+        self.properties.push(property);
+        self
+    }
+
+    pub fn validate(&self, note: &ObsidianNote) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for property in &self.properties {
+            let violation = |kind| Violation {
+                path: note.file_path.clone(),
+                key: property.key.clone(),
+                line: property_line(note, &property.key),
+                kind,
+            };
+
+            let value = note.properties.as_ref().and_then(|p| p.get(&property.key));
+            let Some(value) = value.filter(|value| !value.is_null()) else {
+                if property.required {
+                    violations.push(violation(ViolationKind::Missing));
+                }
+                continue;
+            };
+
+            if !property.kind.matches(value) {
+                violations.push(violation(ViolationKind::WrongType {
+                    expected: property.kind,
+                    found: value.clone(),
+                }));
+                continue;
+            }
+            if let Some(allowed) = &property.allowed {
+                let values = match value {
+                    Properties::Sequence(items) => items.iter().collect(),
+                    value => vec![value],
+                };
+                for value in values {
+                    if !allowed.contains(value) {
+                        violations.push(violation(ViolationKind::NotAllowed(value.clone())));
+                    }
+                }
+            }
+        }
+        violations
+    }
+}
+
+impl Vault {
+    /// Every schema violation in the vault's notes
+    pub fn validate(&self, schema: &Schema) -> crate::Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+        for note in self.notes() {
+            violations.extend(schema.validate(&note?));
+        }
+        Ok(violations)
+    }
+}
+
+/// The properties seen across a set of notes, from [`Vault::infer_schema`]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct InferredSchema {
+    /// How many notes were scanned
+    pub notes: usize,
+    /// Sorted by key
+    pub properties: Vec<InferredProperty>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InferredProperty {
+    pub key: String,
+    /// How many notes have the property
+    pub count: usize,
+    /// How many notes have each type of value
+    pub types: BTreeMap<PropertyType, usize>,
+    /// How many notes leave the property empty
+    pub empty: usize,
+}
+
+impl InferredProperty {
+    /// The most common type, preferring the earlier variant on ties
+    pub fn most_common_type(&self) -> Option<PropertyType> {
+        self.types
+            .iter()
+            .max_by(|(a, x), (b, y)| x.cmp(y).then(b.cmp(a)))
+            .map(|(kind, _)| *kind)
+    }
+}
+
+impl InferredSchema {
+    pub fn from_notes<'a>(notes: impl IntoIterator<Item = &'a ObsidianNote>) -> Self {
+        let mut schema = Self::default();
+        let mut properties: BTreeMap<String, InferredProperty> = BTreeMap::new();
+        for note in notes {
+            schema.notes += 1;
+            let Some(mapping) = note.properties.as_ref().and_then(|p| p.as_mapping()) else {
+                continue;
+            };
+            for (key, value) in mapping {
+                let Some(key) = key.as_str() else {
+                    continue;
+                };
+                let property =
+                    properties
+                        .entry(key.to_string())
+                        .or_insert_with(|| InferredProperty {
+                            key: key.to_string(),
+                            count: 0,
+                            types: BTreeMap::new(),
+                            empty: 0,
+                        });
+                property.count += 1;
+                match PropertyType::of(value) {
+                    Some(kind) => *property.types.entry(kind).or_default() += 1,
+                    None if value.is_null() => property.empty += 1,
+                    None => {}
+                }
+            }
+        }
+        schema.properties = properties.into_values().collect();
+        schema
+    }
+
+    /// A schema using each property's most common type, requiring the properties every note
+    /// has
+    pub fn to_schema(&self) -> Schema {
+        let mut schema = Schema::new();
+        for property in &self.properties {
+            let Some(kind) = property.most_common_type() else {
+                continue;
+            };
+            let mut rule = PropertySchema::new(&property.key, kind);
+            rule.required = property.count == self.notes && property.empty == 0;
+            schema = schema.property(rule);
+        }
+        schema
+    }
+
+    /// A JSON Schema for the frontmatter, allowing every type seen for each property
+    pub fn to_json_schema(&self) -> Value {
+        let mut properties = serde_json::Map::new();
+        let mut required = Vec::new();
+        for property in &self.properties {
+            let mut types: Vec<Value> =
+                property.types.keys().map(|kind| json_type(*kind)).collect();
+            if property.empty > 0 {
+                types.push(json!({ "type": "null" }));
+            }
+            let schema = match types.len() {
+                0 => json!({}),
+                1 => types.remove(0),
+                _ => json!({ "anyOf": types }),
+            };
+            properties.insert(property.key.clone(), schema);
+            if property.count == self.notes {
+                required.push(property.key.clone());
+            }
+        }
+
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })
+    }
+}
+
+impl Vault {
+    /// The property keys used across the vault, with the types of their values
+    pub fn infer_schema(&self) -> crate::Result<InferredSchema> {
+        let notes = self.notes().collect::<crate::Result<Vec<_>>>()?;
+        Ok(InferredSchema::from_notes(&notes))
+    }
+}
+
+/// How a property key is used, from [`Vault::property_stats`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyStats {
+    pub key: String,
+    /// How many notes have the property
+    pub notes: usize,
+    pub types: BTreeSet<PropertyType>,
+    /// Up to [`PropertyStats::MAX_EXAMPLES`] distinct non-empty values, in the order seen
+    pub examples: Vec<Properties>,
+}
+
+impl PropertyStats {
+    pub const MAX_EXAMPLES: usize = 3;
+}
+
+impl Vault {
+    /// Usage of every property key, most used first
+    pub fn property_stats(&self) -> crate::Result<Vec<PropertyStats>> {
+        let mut stats: HashMap<String, PropertyStats> = HashMap::new();
+        for note in self.notes() {
+            let note = note?;
+            let Some(mapping) = note.properties.as_ref().and_then(|p| p.as_mapping()) else {
+                continue;
+            };
+            for (key, value) in mapping {
+                let Some(key) = key.as_str() else {
+                    continue;
+                };
+                let entry = stats
+                    .entry(key.to_string())
+                    .or_insert_with(|| PropertyStats {
+                        key: key.to_string(),
+                        notes: 0,
+                        types: BTreeSet::new(),
+                        examples: Vec::new(),
+                    });
+                entry.notes += 1;
+                entry.types.extend(PropertyType::of(value));
+                if !value.is_null()
+                    && entry.examples.len() < PropertyStats::MAX_EXAMPLES
+                    && !entry.examples.contains(value)
+                {
+                    entry.examples.push(value.clone());
+                }
+            }
+        }
+
+        let mut stats: Vec<PropertyStats> = stats.into_values().collect();
+        stats.sort_by(|a, b| b.notes.cmp(&a.notes).then_with(|| a.key.cmp(&b.key)));
+        Ok(stats)
+    }
+}
+
+fn json_type(kind: PropertyType) -> Value {
+    match kind {
+        PropertyType::Text => json!({ "type": "string" }),
+        PropertyType::List => json!({ "type": "array" }),
+        PropertyType::Number => json!({ "type": "number" }),
+        PropertyType::Checkbox => json!({ "type": "boolean" }),
+        PropertyType::Date => json!({ "type": "string", "format": "date" }),
+        // Obsidian leaves out the seconds and time zone that `date-time` requires
+        PropertyType::DateTime => json!({
+            "type": "string",
+            "pattern": "^\\d{4}-\\d{2}-\\d{2}[T ]\\d{2}:\\d{2}(:\\d{2})?$",
+        }),
+    }
+}
+
+fn property_line(note: &ObsidianNote, key: &str) -> Option<usize> {
+    let raw = note.frontmatter.as_deref()?;
+    let range = note.frontmatter_range.as_ref()?;
+    let offset = property_offset(raw, key)?;
+    Some(range.start_line + raw[..offset].matches('\n').count())
+}
+
+fn is_date(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| {
+            if i == 4 || i == 7 {
+                *b == b'-'
+            } else {
+                b.is_ascii_digit()
+            }
+        })
+}
+
+fn is_datetime(s: &str) -> bool {
+    let Some((date, time)) = s.split_once(['T', ' ']) else {
+        return false;
+    };
+    let time = time.as_bytes();
+    let digits = |range: std::ops::Range<usize>| time[range].iter().all(u8::is_ascii_digit);
+    is_date(date)
+        && matches!(time.len(), 5 | 8)
+        && digits(0..2)
+        && time[2] == b':'
+        && digits(3..5)
+        && (time.len() == 5 || (time[5] == b':' && digits(6..8)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::path::Path;
+
+    fn schema() -> Schema {
+        Schema::new()
+            .property(
+                PropertySchema::new("status", PropertyType::Text)
+                    .required()
+                    .allowed(["draft", "done"]),
+            )
+            .property(PropertySchema::new("due", PropertyType::Date))
+            .property(PropertySchema::new("tags", PropertyType::List).allowed(["a", "b"]))
+    }
+
+    #[test]
+    fn validate_reports_violations_with_lines() {
+        let note = ObsidianNote::parse(
+            Path::new("Note.md"),
+            indoc! {"
+                ---
+                status: archived
+                due: next week
+                tags: [a, c]
+                ---
+            "}
+            .to_string(),
+        )
+        .unwrap();
+
+        let violations = schema().validate(&note);
+        let found: Vec<(&str, Option<usize>, &ViolationKind)> = violations
+            .iter()
+            .map(|v| (v.key.as_str(), v.line, &v.kind))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "status",
+                    Some(2),
+                    &ViolationKind::NotAllowed("archived".into())
+                ),
+                (
+                    "due",
+                    Some(3),
+                    &ViolationKind::WrongType {
+                        expected: PropertyType::Date,
+                        found: "next week".into()
+                    }
+                ),
+                ("tags", Some(4), &ViolationKind::NotAllowed("c".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_accepts_valid_notes_and_flags_missing_keys() {
+        let valid = ObsidianNote::parse(
+            Path::new("Valid.md"),
+            "---\nstatus: done\ndue: 2024-03-01\ntags:\n---\n".to_string(),
+        )
+        .unwrap();
+        assert_eq!(schema().validate(&valid), vec![]);
+
+        let bare = ObsidianNote::parse(Path::new("Bare.md"), "Text".to_string()).unwrap();
+        assert_eq!(
+            schema().validate(&bare),
+            vec![Violation {
+                path: "Bare.md".into(),
+                key: "status".to_string(),
+                line: None,
+                kind: ViolationKind::Missing,
+            }]
+        );
+        assert!(PropertyType::DateTime.matches(&"2024-03-01T09:30".into()));
+    }
+
+    #[test]
+    fn infer_schema_counts_types_and_emits_json_schema() {
+        let notes: Vec<ObsidianNote> = [
+            "---\nstatus: done\ndue: 2024-03-01\n---\n",
+            "---\nstatus: draft\ndue:\npriority: 2\n---\n",
+            "---\nstatus: draft\ndue: soon\n---\n",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, contents)| {
+            ObsidianNote::parse(Path::new(&format!("{i}.md")), contents.to_string()).unwrap()
+        })
+        .collect();
+        let inferred = InferredSchema::from_notes(&notes);
+
+        let due = &inferred.properties[0];
+        assert_eq!(due.key, "due");
+        assert_eq!(due.count, 3);
+        assert_eq!(due.empty, 1);
+        assert_eq!(
+            due.types,
+            BTreeMap::from([(PropertyType::Text, 1), (PropertyType::Date, 1)])
+        );
+
+        assert_eq!(
+            inferred.to_schema(),
+            Schema::new()
+                .property(PropertySchema::new("due", PropertyType::Text))
+                .property(PropertySchema::new("priority", PropertyType::Number))
+                .property(PropertySchema::new("status", PropertyType::Text).required())
+        );
+        assert_eq!(
+            inferred.to_json_schema(),
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "due": { "anyOf": [
+                        { "type": "string" },
+                        { "type": "string", "format": "date" },
+                        { "type": "null" },
+                    ] },
+                    "priority": { "type": "number" },
+                    "status": { "type": "string" },
+                },
+                "required": ["due", "status"],
+            })
+        );
+    }
+
+    #[test]
+    fn property_stats_summarizes_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, contents) in [
+            ("a.md", "---\nstatus: done\ntags: [x]\n---\n"),
+            ("b.md", "---\nstatus: 3\n---\n"),
+            ("c.md", "---\nstatus: done\n---\n"),
+        ] {
+            std::fs::write(dir.path().join(name), contents).unwrap();
+        }
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let stats = vault.property_stats().unwrap();
+        assert_eq!(
+            stats,
+            vec![
+                PropertyStats {
+                    key: "status".to_string(),
+                    notes: 3,
+                    types: BTreeSet::from([PropertyType::Text, PropertyType::Number]),
+                    examples: vec!["done".into(), 3.into()],
+                },
+                PropertyStats {
+                    key: "tags".to_string(),
+                    notes: 1,
+                    types: BTreeSet::from([PropertyType::List]),
+                    examples: vec![Properties::Sequence(vec!["x".into()])],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use std::{collections::HashMap, ops::Range, path::PathBuf};
+
+use crate::{ObsidianNote, Vault};
+
+#[cfg(feature = "tantivy")]
+mod tantivy_index;
+#[cfg(feature = "tantivy")]
+pub use tantivy_index::*;
+
+const SNIPPET_CONTEXT: usize = 40;
+
+/// Documents containing a term, with the byte range of each occurrence
+type Postings = Vec<(usize, Vec<Range<usize>>)>;
+
+/// An in-memory inverted index over note bodies
+#[derive(Debug, Default, Clone)]
+pub struct SearchIndex {
+    documents: Vec<(PathBuf, String)>,
+    /// Keyed by lowercased term
+    postings: HashMap<String, Postings>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub path: PathBuf,
+    /// Number of matched term occurrences
+    pub score: usize,
+    pub matches: Vec<SearchMatch>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    /// Byte range of the matched term within the note body
+    pub span: Range<usize>,
+    /// Text surrounding the match, on a single line
+    pub snippet: String,
+}
+
+impl SearchIndex {
+    pub fn from_notes(notes: &[ObsidianNote]) -> Self {
+        let mut index = Self::default();
+        for note in notes {
+            index.add(note);
+        }
+        index
+    }
+
+    pub fn add(&mut self, note: &ObsidianNote) {
+        let document = self.documents.len();
+        let mut terms: HashMap<String, Vec<Range<usize>>> = HashMap::new();
+        for (term, span) in tokenize(&note.file_body) {
+            terms.entry(term).or_default().push(span);
+        }
+        for (term, spans) in terms {
+            self.postings
+                .entry(term)
+                .or_default()
+                .push((document, spans));
+        }
+
+        self.documents
+            .push((note.file_path.clone(), note.file_body.clone()));
+    }
+
+    /// Notes containing every term in `query`, best matches first
+    pub fn search(&self, query: &str) -> Vec<SearchResult> {
+        let terms: Vec<String> = tokenize(query).into_iter().map(|(term, _)| term).collect();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut hits: HashMap<usize, Vec<Range<usize>>> = HashMap::new();
+        for (i, term) in terms.iter().enumerate() {
+            let postings = self.postings.get(term).map_or(&[][..], Vec::as_slice);
+            let mut term_hits: HashMap<usize, Vec<Range<usize>>> =
+                postings.iter().cloned().collect();
+
+            if i == 0 {
+                hits = term_hits;
+            } else {
+                hits.retain(|document, spans| match term_hits.remove(document) {
+                    Some(more) => {
+                        spans.extend(more);
+                        true
+                    }
+                    None => false,
+                });
+            }
+        }
+
+        let mut results: Vec<SearchResult> = hits
+            .into_iter()
+            .map(|(document, mut spans)| {
+                let (path, text) = &self.documents[document];
+                spans.sort_by_key(|span| span.start);
+                SearchResult {
+                    path: path.clone(),
+                    score: spans.len(),
+                    matches: spans
+                        .into_iter()
+                        .map(|span| SearchMatch {
+                            snippet: snippet(text, &span),
+                            span,
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        results.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+        results
+    }
+}
+
+impl Vault {
+    pub fn search_index(&self) -> crate::Result<SearchIndex> {
+        let mut index = SearchIndex::default();
+        for note in self.notes() {
+            index.add(&note?);
+        }
+        Ok(index)
+    }
+
+    pub fn search(&self, query: &str) -> crate::Result<Vec<SearchResult>> {
+        Ok(self.search_index()?.search(query))
+    }
+}
+
+/// Lowercased words and their byte ranges
+pub(crate) fn tokenize(text: &str) -> Vec<(String, Range<usize>)> {
+    let mut tokens = Vec::new();
+    let mut start: Option<usize> = None;
+
+    for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (start, c.is_alphanumeric()) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                tokens.push((text[s..i].to_lowercase(), s..i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+fn snippet(text: &str, span: &Range<usize>) -> String {
+    let mut start = span.start.saturating_sub(SNIPPET_CONTEXT);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (span.end + SNIPPET_CONTEXT).min(text.len());
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let mut snippet = text[start..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if end < text.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn index() -> SearchIndex {
+        let notes: Vec<ObsidianNote> = [
+            ("a.md", "Rust is a systems language.\nRust is fast."),
+            ("b.md", "Gardening notes: tomatoes need sun."),
+            ("c.md", "Learning rust and gardening together"),
+        ]
+        .iter()
+        .map(|(path, body)| ObsidianNote::parse(Path::new(path), body.to_string()).unwrap())
+        .collect();
+        SearchIndex::from_notes(&notes)
+    }
+
+    #[test]
+    fn search_ranks_by_occurrences() {
+        let results = index().search("RUST");
+
+        let paths: Vec<_> = results.iter().map(|r| r.path.clone()).collect();
+        assert_eq!(paths, vec![PathBuf::from("a.md"), PathBuf::from("c.md")]);
+        assert_eq!(results[0].score, 2);
+        assert_eq!(results[0].matches[1].span, 28..32);
+    }
+
+    #[test]
+    fn search_requires_every_term() {
+        let results = index().search("rust gardening");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path, PathBuf::from("c.md"));
+        assert!(index().search("rust tomatoes").is_empty());
+        assert!(index().search("  ").is_empty());
+    }
+
+    #[test]
+    fn search_returns_snippets() {
+        let results = index().search("tomatoes");
+        assert_eq!(
+            results[0].matches[0].snippet,
+            "Gardening notes: tomatoes need sun."
+        );
+
+        let long = "word ".repeat(20) + "needle" + &" word".repeat(20);
+        let note = ObsidianNote::parse(Path::new("long.md"), long).unwrap();
+        let snippet = &SearchIndex::from_notes(&[note]).search("needle")[0].matches[0].snippet;
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(snippet.contains("word needle word"));
+    }
+}
@@ -0,0 +1,318 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use tantivy::{
+    collector::{DocSetCollector, TopDocs},
+    directory::MmapDirectory,
+    query::{AllQuery, QueryParser},
+    schema::{Field, OwnedValue, Schema, Value, STORED, STRING, TEXT},
+    Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term,
+};
+
+use crate::{ObsidianNote, Vault};
+
+const WRITER_MEMORY: usize = 50_000_000;
+
+/// A persistent full-text index backed by Tantivy
+///
+/// Queries use Tantivy's query syntax and search the `title`, `body`, `tags` and `properties`
+/// fields, so `tags:project` or `properties.status:active` narrow a search to one field.
+pub struct TantivyIndex {
+    index: Index,
+    reader: IndexReader,
+    writer: IndexWriter,
+    fields: Fields,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TantivyHit {
+    pub path: PathBuf,
+    pub score: f32,
+}
+
+#[derive(Clone, Copy)]
+struct Fields {
+    path: Field,
+    modified: Field,
+    title: Field,
+    body: Field,
+    tags: Field,
+    properties: Field,
+}
+
+impl TantivyIndex {
+    /// Opens the index stored in `dir`, creating it if it doesn't exist yet
+    pub fn open_or_create(dir: &Path) -> crate::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let (schema, fields) = schema();
+        Self::with_index(
+            Index::open_or_create(
+                MmapDirectory::open(dir).map_err(tantivy::TantivyError::from)?,
+                schema,
+            )?,
+            fields,
+        )
+    }
+
+    pub fn in_memory() -> crate::Result<Self> {
+        let (schema, fields) = schema();
+        Self::with_index(Index::create_in_ram(schema), fields)
+    }
+
+    fn with_index(index: Index, fields: Fields) -> crate::Result<Self> {
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        let writer = index.writer(WRITER_MEMORY)?;
+        Ok(Self {
+            index,
+            reader,
+            writer,
+            fields,
+        })
+    }
+
+    /// Adds a note, replacing any earlier version with the same path. Changes are visible to
+    /// searches after [`TantivyIndex::commit`].
+    pub fn add_note(&mut self, note: &ObsidianNote) -> crate::Result<()> {
+        self.add_note_modified(note, 0)
+    }
+
+    fn add_note_modified(&mut self, note: &ObsidianNote, modified: u64) -> crate::Result<()> {
+        self.remove_note(&note.file_path);
+
+        let fields = self.fields;
+        let mut document = TantivyDocument::default();
+        document.add_text(fields.path, note.file_path.to_string_lossy());
+        document.add_u64(fields.modified, modified);
+        document.add_text(fields.title, note.title());
+        document.add_text(fields.body, &note.file_body);
+        for tag in note.tags() {
+            document.add_text(fields.tags, &tag.name);
+        }
+        if let Some(properties) = &note.properties {
+            if let serde_json::Value::Object(object) = serde_json::to_value(properties)? {
+                document.add_field_value(fields.properties, &OwnedValue::from(object));
+            }
+        }
+
+        self.writer.add_document(document)?;
+        Ok(())
+    }
+
+    pub fn remove_note(&mut self, path: &Path) {
+        self.writer.delete_term(Term::from_field_text(
+            self.fields.path,
+            &path.to_string_lossy(),
+        ));
+    }
+
+    pub fn commit(&mut self) -> crate::Result<()> {
+        self.writer.commit()?;
+        self.reader.reload()?;
+        Ok(())
+    }
+
+    pub fn search(&self, query: &str, limit: usize) -> crate::Result<Vec<TantivyHit>> {
+        let fields = self.fields;
+        let mut parser = QueryParser::for_index(
+            &self.index,
+            vec![fields.title, fields.body, fields.tags, fields.properties],
+        );
+        parser.set_field_boost(fields.title, 2.0);
+        parser.set_field_boost(fields.tags, 1.5);
+        let query = parser.parse_query(query)?;
+
+        let searcher = self.reader.searcher();
+        searcher
+            .search(&query, &TopDocs::with_limit(limit).order_by_score())?
+            .into_iter()
+            .map(|(score, address)| {
+                let document: TantivyDocument = searcher.doc(address)?;
+                Ok(TantivyHit {
+                    path: stored_path(&document, fields),
+                    score,
+                })
+            })
+            .collect()
+    }
+
+    /// Paths in the index with the modification time they were indexed at
+    fn indexed(&self) -> crate::Result<HashMap<PathBuf, u64>> {
+        let searcher = self.reader.searcher();
+        searcher
+            .search(&AllQuery, &DocSetCollector)?
+            .into_iter()
+            .map(|address| {
+                let document: TantivyDocument = searcher.doc(address)?;
+                let modified = document
+                    .get_first(self.fields.modified)
+                    .and_then(|value| value.as_u64())
+                    .unwrap_or(0);
+                Ok((stored_path(&document, self.fields), modified))
+            })
+            .collect()
+    }
+
+    /// Brings the index up to date with the vault, reindexing only notes whose modification
+    /// time changed and dropping notes that no longer exist. Returns the number of notes
+    /// reindexed.
+    pub fn update_from_vault(&mut self, vault: &Vault) -> crate::Result<usize> {
+        let mut indexed = self.indexed()?;
+        let mut updated = 0;
+
+        for path in vault.note_paths() {
+            let path = path?;
+            let modified = fs::metadata(&path)?
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos() as u64;
+
+            if indexed.remove(&path) != Some(modified) {
+                self.add_note_modified(&ObsidianNote::read_from_path(&path)?, modified)?;
+                updated += 1;
+            }
+        }
+
+        for path in indexed.keys() {
+            self.remove_note(path);
+        }
+
+        self.commit()?;
+        Ok(updated)
+    }
+}
+
+impl Vault {
+    /// Opens the Tantivy index in `dir` and updates it with any notes changed since it was
+    /// last built
+    pub fn tantivy_index(&self, dir: &Path) -> crate::Result<TantivyIndex> {
+        let mut index = TantivyIndex::open_or_create(dir)?;
+        index.update_from_vault(self)?;
+        Ok(index)
+    }
+}
+
+fn schema() -> (Schema, Fields) {
+    let mut builder = Schema::builder();
+    let fields = Fields {
+        path: builder.add_text_field("path", STRING | STORED),
+        modified: builder.add_u64_field("modified", STORED),
+        title: builder.add_text_field("title", TEXT),
+        body: builder.add_text_field("body", TEXT),
+        tags: builder.add_text_field("tags", TEXT),
+        properties: builder.add_json_field("properties", TEXT),
+    };
+    (builder.build(), fields)
+}
+
+fn stored_path(document: &TantivyDocument, fields: Fields) -> PathBuf {
+    document
+        .get_first(fields.path)
+        .and_then(|value| value.as_str())
+        .map(PathBuf::from)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn note(path: &str, contents: &str) -> ObsidianNote {
+        ObsidianNote::parse(Path::new(path), contents.to_string()).unwrap()
+    }
+
+    fn paths(hits: &[TantivyHit]) -> Vec<PathBuf> {
+        hits.iter().map(|hit| hit.path.clone()).collect()
+    }
+
+    #[test]
+    fn search_is_field_aware() {
+        let mut index = TantivyIndex::in_memory().unwrap();
+        index
+            .add_note(&note(
+                "Gardening.md",
+                indoc! {r"
+                    ---
+                    status: active
+                    tags: [hobby]
+                    ---
+                    Tomatoes need sun.
+                "},
+            ))
+            .unwrap();
+        index
+            .add_note(&note(
+                "Rust.md",
+                "---\nstatus: done\n---\nA note on gardening.",
+            ))
+            .unwrap();
+        index.commit().unwrap();
+
+        let hits = index.search("gardening", 10).unwrap();
+        assert_eq!(hits[0].path, PathBuf::from("Gardening.md"));
+        assert_eq!(hits.len(), 2);
+
+        assert_eq!(
+            paths(&index.search("title:gardening", 10).unwrap()),
+            vec![PathBuf::from("Gardening.md")]
+        );
+        assert_eq!(
+            paths(&index.search("tags:hobby", 10).unwrap()),
+            vec![PathBuf::from("Gardening.md")]
+        );
+        assert_eq!(
+            paths(&index.search("properties.status:done", 10).unwrap()),
+            vec![PathBuf::from("Rust.md")]
+        );
+    }
+
+    #[test]
+    fn add_note_replaces_earlier_versions() {
+        let mut index = TantivyIndex::in_memory().unwrap();
+        index.add_note(&note("a.md", "old words")).unwrap();
+        index.commit().unwrap();
+        index.add_note(&note("a.md", "new words")).unwrap();
+        index.commit().unwrap();
+
+        assert!(index.search("old", 10).unwrap().is_empty());
+        assert_eq!(index.search("words", 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn update_from_vault_reindexes_changed_notes() {
+        let vault_dir = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(vault_dir.path()).unwrap();
+        fs::write(vault_dir.path().join("a.md"), "apples").unwrap();
+        fs::write(vault_dir.path().join("b.md"), "bananas").unwrap();
+
+        let index = vault.tantivy_index(index_dir.path()).unwrap();
+        assert_eq!(index.search("apples", 10).unwrap().len(), 1);
+        drop(index);
+
+        let a = vault_dir.path().join("a.md");
+        fs::write(&a, "cherries").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&a)
+            .unwrap()
+            .set_modified(UNIX_EPOCH)
+            .unwrap();
+        fs::remove_file(vault_dir.path().join("b.md")).unwrap();
+
+        let mut index = TantivyIndex::open_or_create(index_dir.path()).unwrap();
+        assert_eq!(index.update_from_vault(&vault).unwrap(), 1);
+        assert!(index.search("apples", 10).unwrap().is_empty());
+        assert!(index.search("bananas", 10).unwrap().is_empty());
+        assert_eq!(paths(&index.search("cherries", 10).unwrap()), vec![a]);
+        assert_eq!(index.update_from_vault(&vault).unwrap(), 0);
+    }
+}
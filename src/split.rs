@@ -0,0 +1,293 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    blocks::parse_blocks,
+    create::{numbered_path, FORBIDDEN},
+    edit::{apply_edits, TextEdit},
+    headings::{find_nested_heading, heading_link_text},
+    links::scan_wikilinks,
+    Block, Error, FileChange, Heading, LinkResolver, LinkStyle, ObsidianNote, Vault, WikiLink,
+};
+
+/// A section moving out of the note being split
+struct Part {
+    /// Index of the section's heading in the note's outline
+    heading: usize,
+    /// Byte range of the section within the note's body, heading line included
+    span: Range<usize>,
+    path: PathBuf,
+}
+
+impl Vault {
+    /// Splits a note at each heading of `level` into new notes named after the headings, next
+    /// to it. Returns the new notes' paths.
+    ///
+    /// Each section's content, without its heading, moves to the new note under a link back to
+    /// the original, where a link to the new note takes its place. Wikilinks and embeds to the
+    /// moved headings and blocks, from anywhere in the vault, are redirected to the new notes.
+    pub fn split_note(&self, path: impl AsRef<Path>, level: u8) -> crate::Result<Vec<PathBuf>> {
+        let original = self.path.join(path);
+        if !original.is_file() {
+            return Err(Error::NoteNotFound(original));
+        }
+        let mut note = ObsidianNote::read_from_path_with(&original, self.read_options)?;
+        let headings = note.headings();
+        let body = note.file_body.clone();
+
+        let folder = original.parent().unwrap_or(&self.path);
+        let mut parts: Vec<Part> = Vec::new();
+        for (index, heading) in headings.iter().enumerate() {
+            if heading.level != level {
+                continue;
+            }
+            let end = headings[index + 1..]
+                .iter()
+                .find(|next| next.level <= level)
+                .map_or(body.len(), |next| next.span.start);
+
+            let title: String = heading_link_text(&heading.text)
+                .chars()
+                .filter(|c| !FORBIDDEN.contains(c))
+                .collect();
+            let title = match title.trim() {
+                "" => "Untitled",
+                title => title,
+            };
+            let path = (0..)
+                .map(|n| numbered_path(folder, title, n))
+                .find(|path| !path.exists() && parts.iter().all(|part| part.path != *path))
+                .ok_or_else(|| Error::AlreadyExists(folder.join(format!("{title}.md"))))?;
+            parts.push(Part {
+                heading: index,
+                span: heading.span.start..end,
+                path,
+            });
+        }
+        if parts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let notes = self.notes().collect::<crate::Result<Vec<_>>>()?;
+        let resolver = LinkResolver::from_vault_notes(self, &notes)?;
+        let mut files = resolver.files().to_vec();
+        files.extend(parts.iter().map(|part| part.path.clone()));
+        let splitter = Splitter {
+            vault: self,
+            resolver: &resolver,
+            new_resolver: &LinkResolver::new(&self.path, files),
+            style: self.link_style()?,
+            original: &original,
+            headings: &headings,
+            blocks: &parse_blocks(&body),
+            parts: &parts,
+        };
+
+        let mut changes = Vec::new();
+        for part in &parts {
+            let start = headings[part.heading].span.end;
+            let content = &body[start..part.span.end];
+            let edits = splitter.link_edits(content, &part.path);
+            let back = splitter.target(&original, &part.path);
+            changes.push(FileChange::Write {
+                path: part.path.clone(),
+                contents: format!("[[{back}]]\n\n{}\n", apply_edits(content, &edits).trim()),
+            });
+        }
+
+        let mut edits: Vec<TextEdit> = splitter
+            .link_edits(&body, &original)
+            .into_iter()
+            .filter(|edit| !parts.iter().any(|p| p.span.contains(&edit.span.start)))
+            .collect();
+        for part in &parts {
+            let link = splitter.target(&part.path, &original);
+            let gap = if part.span.end < body.len() {
+                "\n\n"
+            } else {
+                ""
+            };
+            edits.push(TextEdit::new(part.span.clone(), format!("[[{link}]]{gap}")));
+        }
+        note.edit_body(&edits)?;
+        changes.push(FileChange::Write {
+            path: original.clone(),
+            contents: note.to_string(),
+        });
+
+        for mut other in notes {
+            if other.file_path == original {
+                continue;
+            }
+            let edits = splitter.link_edits(&other.file_body, &other.file_path);
+            if !edits.is_empty() {
+                other.edit_body(&edits)?;
+                changes.push(FileChange::Write {
+                    path: other.file_path.clone(),
+                    contents: other.to_string(),
+                });
+            }
+        }
+
+        self.writer.apply(changes)?;
+        Ok(parts.into_iter().map(|part| part.path).collect())
+    }
+}
+
+struct Splitter<'a> {
+    vault: &'a Vault,
+    /// Resolves links as they were before the split
+    resolver: &'a LinkResolver,
+    /// Includes the new notes, to write the shortest links to them
+    new_resolver: &'a LinkResolver,
+    style: LinkStyle,
+    original: &'a Path,
+    headings: &'a [Heading],
+    blocks: &'a [Block],
+    parts: &'a [Part],
+}
+
+impl Splitter<'_> {
+    /// Rewrites the heading and block links in `text` that point into a moved section, and for
+    /// text moving to a new note, links to the original's own headings
+    fn link_edits(&self, text: &str, source: &Path) -> Vec<TextEdit> {
+        let mut edits = Vec::new();
+        for (is_embed, link) in scan_wikilinks(text) {
+            if link.heading.is_none() && link.block.is_none() {
+                continue;
+            }
+            // Links in moved text were written relative to the original note
+            let written_in = if self.parts.iter().any(|p| p.path == source) {
+                self.original
+            } else {
+                source
+            };
+            if self.resolver.resolve_link(&link, written_in) != Some(self.original) {
+                continue;
+            }
+
+            let (part, subpath) = self.destination(&link);
+            let destination = part.map_or(self.original, |part| &part.path);
+            if destination == self.original && (!link.target.is_empty() || source == self.original)
+            {
+                continue;
+            }
+
+            let bang = if is_embed { "!" } else { "" };
+            let target = self.target(destination, source);
+            let alias = link
+                .alias
+                .as_ref()
+                .map(|alias| format!("|{alias}"))
+                .unwrap_or_default();
+            edits.push(TextEdit::new(
+                link.span.clone(),
+                format!("{bang}[[{target}{subpath}{alias}]]"),
+            ));
+        }
+        edits
+    }
+
+    /// The moved section a link into the original points to, if any, and the `#Heading` or
+    /// `#^block` to link to within its destination
+    fn destination(&self, link: &WikiLink) -> (Option<&Part>, String) {
+        let containing = |offset: usize| self.parts.iter().find(|p| p.span.contains(&offset));
+
+        if let Some(id) = &link.block {
+            let part = self
+                .blocks
+                .iter()
+                .find(|block| block.id == *id)
+                .and_then(|block| containing(block.span.start));
+            return (part, format!("#^{id}"));
+        }
+
+        let heading = link.heading.as_deref().unwrap_or_default();
+        match find_nested_heading(self.headings, heading) {
+            Some(index) => match containing(self.headings[index].span.start) {
+                Some(part) if part.heading == index => (Some(part), String::new()),
+                Some(part) => {
+                    let text = heading_link_text(&self.headings[index].text);
+                    (Some(part), format!("#{text}"))
+                }
+                None => (None, format!("#{heading}")),
+            },
+            None => (None, format!("#{heading}")),
+        }
+    }
+
+    fn target(&self, target: &Path, source: &Path) -> String {
+        self.style
+            .wikilink_target(self.vault, self.new_resolver, target, source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::fs;
+
+    #[test]
+    fn split_note_moves_sections_and_redirects_links() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("Book.md"),
+            indoc! {r"
+                ---
+                tags: [book]
+                ---
+                # Book
+                Intro, see [[#Two]] and [[#Book]]
+
+                ## One
+                First ^first
+
+                ## Two
+                Second, unlike [[#One]] and [[#Book]]
+                ### Detail
+                More
+            "},
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("Index.md"),
+            "[[Book#Two#Detail|detail]] ![[Book^first]] [[Book#Book]] [[Book]]",
+        )
+        .unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let created = vault.split_note("Book.md", 2).unwrap();
+        assert_eq!(
+            created,
+            vec![dir.path().join("One.md"), dir.path().join("Two.md")]
+        );
+
+        let read = |name: &str| fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(
+            read("Book.md"),
+            indoc! {r"
+                ---
+                tags: [book]
+                ---
+                # Book
+                Intro, see [[Two]] and [[#Book]]
+
+                [[One]]
+
+                [[Two]]
+            "}
+        );
+        assert_eq!(read("One.md"), "[[Book]]\n\nFirst ^first\n");
+        assert_eq!(
+            read("Two.md"),
+            "[[Book]]\n\nSecond, unlike [[One]] and [[Book#Book]]\n### Detail\nMore\n"
+        );
+        assert_eq!(
+            read("Index.md"),
+            "[[Two#Detail|detail]] ![[One#^first]] [[Book#Book]] [[Book]]"
+        );
+    }
+}
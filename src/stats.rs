@@ -0,0 +1,198 @@
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    code::fenced_ranges,
+    links::{parse_markdown_links, parse_wikilinks},
+    vault::is_note,
+    ObsidianNote, Vault,
+};
+
+/// The reading speed [`NoteStats::reading_time`] assumes
+pub const WORDS_PER_MINUTE: usize = 200;
+
+/// How many notes [`VaultStats::largest_notes`] lists
+pub const LARGEST_NOTES: usize = 10;
+
+/// Size metrics for a note's body, leaving out frontmatter and fenced code blocks
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NoteStats {
+    pub words: usize,
+    /// Characters including whitespace, like Obsidian's status bar
+    pub characters: usize,
+    pub headings: usize,
+    /// Wikilinks and markdown links, not counting embeds
+    pub links: usize,
+    pub reading_time: Duration,
+}
+
+impl ObsidianNote {
+    pub fn stats(&self) -> NoteStats {
+        let prose = without_code_blocks(&self.file_body);
+        let words = prose
+            .split_whitespace()
+            .filter(|word| word.chars().any(char::is_alphanumeric))
+            .count();
+        let links = parse_wikilinks(&prose).len()
+            + parse_markdown_links(&prose)
+                .iter()
+                .filter(|link| !link.is_embed)
+                .count();
+
+        NoteStats {
+            words,
+            characters: prose.trim().chars().count(),
+            headings: self.headings().len(),
+            links,
+            reading_time: Duration::from_secs((words * 60).div_ceil(WORDS_PER_MINUTE) as u64),
+        }
+    }
+}
+
+/// Totals across a vault, from [`Vault::stats`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct VaultStats {
+    pub notes: usize,
+    pub words: usize,
+    /// Folders relative to the vault root, with an empty path for the root itself
+    pub notes_per_folder: BTreeMap<PathBuf, usize>,
+    /// Lowercased tags, so `#Idea` and `#idea` are counted together
+    pub notes_per_tag: BTreeMap<String, usize>,
+    /// Files that aren't notes, such as images, PDFs and canvases
+    pub attachments: usize,
+    pub attachment_bytes: u64,
+    /// Note paths and word counts, most words first
+    pub largest_notes: Vec<(PathBuf, usize)>,
+}
+
+impl Vault {
+    pub fn stats(&self) -> crate::Result<VaultStats> {
+        let mut stats = VaultStats::default();
+        let mut word_counts = Vec::new();
+
+        for path in self.files() {
+            let path = path?;
+            if !is_note(&path) {
+                stats.attachments += 1;
+                stats.attachment_bytes += fs::metadata(&path)?.len();
+                continue;
+            }
+            let note = ObsidianNote::read_from_path_with(&path, self.read_options);
+            if !self.read_options.keeps(&note) {
+                continue;
+            }
+            let note = note?;
+
+            let words = note.stats().words;
+            stats.notes += 1;
+            stats.words += words;
+            let folder = self.relative_path(&path).parent().unwrap_or(Path::new(""));
+            *stats
+                .notes_per_folder
+                .entry(folder.to_path_buf())
+                .or_default() += 1;
+            for tag in note.tags() {
+                *stats
+                    .notes_per_tag
+                    .entry(tag.name.to_lowercase())
+                    .or_default() += 1;
+            }
+            word_counts.push((path, words));
+        }
+
+        // Stable, so notes with the same count stay in vault order
+        word_counts.sort_by_key(|(_, words)| Reverse(*words));
+        word_counts.truncate(LARGEST_NOTES);
+        stats.largest_notes = word_counts;
+        Ok(stats)
+    }
+}
+
+fn without_code_blocks(text: &str) -> String {
+    let mut prose = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for block in fenced_ranges(text) {
+        prose.push_str(&text[cursor..block.start]);
+        cursor = block.end;
+    }
+    prose.push_str(&text[cursor..]);
+    prose
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::vault_with_files;
+    use indoc::indoc;
+    use std::path::Path;
+
+    #[test]
+    fn vault_stats_totals_notes_tags_and_attachments() {
+        let (dir, vault) = vault_with_files(&[
+            ("a.md", "One two #Idea"),
+            (
+                "Projects/b.md",
+                "---\ntags: [idea, work]\n---\nOne two three four",
+            ),
+            ("Projects/c.md", ""),
+            ("Projects/image.png", "12345"),
+            (".obsidian/app.json", "{}"),
+        ]);
+
+        let stats = vault.stats().unwrap();
+        assert_eq!(stats.notes, 3);
+        assert_eq!(stats.words, 7);
+        assert_eq!(
+            stats.notes_per_folder,
+            BTreeMap::from([(PathBuf::new(), 1), (PathBuf::from("Projects"), 2)])
+        );
+        assert_eq!(
+            stats.notes_per_tag,
+            BTreeMap::from([("idea".to_string(), 2), ("work".to_string(), 1)])
+        );
+        assert_eq!((stats.attachments, stats.attachment_bytes), (1, 5));
+        assert_eq!(
+            stats.largest_notes,
+            vec![
+                (dir.path().join("Projects/b.md"), 4),
+                (dir.path().join("a.md"), 3),
+                (dir.path().join("Projects/c.md"), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn stats_skip_frontmatter_and_code_blocks() {
+        let note = ObsidianNote::parse(
+            Path::new("a.md"),
+            indoc! {r"
+                ---
+                title: Not counted
+                ---
+                # Heading
+                See [[Other]] and [docs](https://example.com) -- ![[image.png]]
+                ```
+                # not a heading [[Nor a link]]
+                ```
+            "}
+            .to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            note.stats(),
+            NoteStats {
+                words: 6,
+                characters: 73,
+                headings: 1,
+                links: 2,
+                reading_time: Duration::from_secs(2),
+            }
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use std::ops::Range;
+
+use crate::{
+    code::{fenced_ranges, in_ranges},
+    ObsidianNote, TextEdit,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    #[default]
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+/// A markdown table. Cells are kept as written, so a `|` inside one stays escaped as `\|`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub header: Vec<String>,
+    /// One per column
+    pub alignments: Vec<Alignment>,
+    /// Rows padded or cut to the header's number of columns
+    pub rows: Vec<Vec<String>>,
+    /// Byte range from the header to the end of the last row, not including its line break
+    pub span: Range<usize>,
+}
+
+impl Table {
+    /// The index of the column with the header `name`
+    pub fn column(&self, name: &str) -> Option<usize> {
+        self.header.iter().position(|header| header == name)
+    }
+
+    pub fn cell(&self, row: usize, column: usize) -> Option<&str> {
+        self.rows.get(row)?.get(column).map(String::as_str)
+    }
+
+    /// The table as markdown with its columns padded to line up
+    pub fn to_markdown(&self) -> String {
+        let columns = self.header.len();
+        let widths: Vec<usize> = (0..columns)
+            .map(|column| {
+                let cells = self.rows.iter().filter_map(|row| row.get(column));
+                std::iter::once(&self.header[column])
+                    .chain(cells)
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or_default()
+                    .max(3)
+            })
+            .collect();
+        let alignment = |column: usize| self.alignments.get(column).copied().unwrap_or_default();
+
+        let row_line = |cells: &[String]| {
+            let cells: Vec<String> = (0..columns)
+                .map(|column| {
+                    let cell = cells.get(column).map_or("", String::as_str);
+                    pad(cell, widths[column], alignment(column))
+                })
+                .collect();
+            format!("| {} |", cells.join(" | "))
+        };
+        let delimiters: Vec<String> = (0..columns)
+            .map(|column| {
+                let width = widths[column];
+                match alignment(column) {
+                    Alignment::None => "-".repeat(width),
+                    Alignment::Left => format!(":{}", "-".repeat(width - 1)),
+                    Alignment::Center => format!(":{}:", "-".repeat(width - 2)),
+                    Alignment::Right => format!("{}:", "-".repeat(width - 1)),
+                }
+            })
+            .collect();
+
+        let mut lines = vec![
+            row_line(&self.header),
+            format!("| {} |", delimiters.join(" | ")),
+        ];
+        lines.extend(self.rows.iter().map(|row| row_line(row)));
+        lines.join("\n")
+    }
+}
+
+impl ObsidianNote {
+    pub fn tables(&self) -> Vec<Table> {
+        parse_tables(&self.file_body)
+    }
+
+    /// Writes `table` over the body text at its span, as returned by [`ObsidianNote::tables`]
+    pub fn replace_table(&mut self, table: &Table) -> crate::Result<()> {
+        self.edit_body(&[TextEdit::new(table.span.clone(), table.to_markdown())])
+    }
+}
+
+/// Finds tables outside code blocks: a header row, a delimiter row with the same number of
+/// cells, and the rows up to the next line without a `|`
+pub fn parse_tables(text: &str) -> Vec<Table> {
+    let code = fenced_ranges(text);
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        lines.push((offset, content));
+        offset += line.len();
+    }
+
+    let mut tables = Vec::new();
+    let mut i = 0;
+    while i + 1 < lines.len() {
+        let (start, header_line) = lines[i];
+        let header = split_row(header_line);
+        let alignments = delimiter_row(lines[i + 1].1);
+        let is_table = !in_ranges(&code, start)
+            && header_line.contains('|')
+            && alignments.as_ref().is_some_and(|a| a.len() == header.len());
+        if !is_table {
+            i += 1;
+            continue;
+        }
+
+        let mut end = lines[i + 1].0 + lines[i + 1].1.len();
+        let mut rows = Vec::new();
+        i += 2;
+        while let Some(&(row_start, line)) = lines.get(i) {
+            if !line.contains('|') || line.trim().is_empty() {
+                break;
+            }
+            let mut row = split_row(line);
+            row.resize(header.len(), String::new());
+            rows.push(row);
+            end = row_start + line.len();
+            i += 1;
+        }
+
+        tables.push(Table {
+            header,
+            alignments: alignments.unwrap_or_default(),
+            rows,
+            span: start..end,
+        });
+    }
+
+    tables
+}
+
+/// A row's trimmed cells, split on `|`s that aren't escaped
+fn split_row(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = match line.strip_suffix('|') {
+        Some(stripped) if !stripped.ends_with('\\') => stripped,
+        _ => line,
+    };
+
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut escaped = false;
+    for c in line.chars() {
+        if c == '|' && !escaped {
+            cells.push(cell.trim().to_string());
+            cell.clear();
+        } else {
+            cell.push(c);
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+/// The alignments in a row like `| :-- | :-: | --: |`, or `None` if it isn't one
+fn delimiter_row(line: &str) -> Option<Vec<Alignment>> {
+    if !line.contains('-') {
+        return None;
+    }
+    split_row(line)
+        .iter()
+        .map(|cell| {
+            let left = cell.starts_with(':');
+            let right = cell.ends_with(':');
+            let dashes = cell.trim_matches(':');
+            if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+                return None;
+            }
+            Some(match (left, right) {
+                (true, true) => Alignment::Center,
+                (true, false) => Alignment::Left,
+                (false, true) => Alignment::Right,
+                (false, false) => Alignment::None,
+            })
+        })
+        .collect()
+}
+
+fn pad(cell: &str, width: usize, alignment: Alignment) -> String {
+    let padding = width.saturating_sub(cell.chars().count());
+    match alignment {
+        Alignment::Right => format!("{}{cell}", " ".repeat(padding)),
+        Alignment::Center => {
+            let left = padding / 2;
+            format!("{}{cell}{}", " ".repeat(left), " ".repeat(padding - left))
+        }
+        Alignment::None | Alignment::Left => format!("{cell}{}", " ".repeat(padding)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::path::Path;
+
+    #[test]
+    fn parse_tables_reads_cells_and_alignment() {
+        let text = indoc! {r"
+            Intro
+            | Name | Score | Note |
+            |:-----|------:|:----:|
+            | Ada  | 10 | [[Ada\|the first]] |
+            | Bob |
+            Not a row
+
+            ```
+            | a | b |
+            |---|---|
+            ```
+        "};
+
+        let tables = parse_tables(text);
+        assert_eq!(tables.len(), 1);
+        let table = &tables[0];
+        assert_eq!(table.header, vec!["Name", "Score", "Note"]);
+        assert_eq!(
+            table.alignments,
+            vec![Alignment::Left, Alignment::Right, Alignment::Center]
+        );
+        assert_eq!(table.cell(0, 2), Some("[[Ada\\|the first]]"));
+        assert_eq!(table.rows[1], vec!["Bob", "", ""]);
+        assert_eq!(table.column("Score"), Some(1));
+        assert!(text[table.span.clone()].ends_with("| Bob |"));
+    }
+
+    #[test]
+    fn replace_table_writes_aligned_markdown() {
+        let mut note = ObsidianNote::parse(
+            Path::new("a.md"),
+            "Before\n\n|a|b|\n|-|:-:|\n|1|2|\n\nAfter\n".to_string(),
+        )
+        .unwrap();
+
+        let mut table = note.tables().remove(0);
+        table.rows[0][1] = "two".to_string();
+        table.rows.push(vec!["longer".to_string(), "x".to_string()]);
+        note.replace_table(&table).unwrap();
+
+        assert_eq!(
+            note.to_string(),
+            indoc! {r"
+                Before
+
+                | a      |  b  |
+                | ------ | :-: |
+                | 1      | two |
+                | longer |  x  |
+
+                After
+            "}
+        );
+    }
+}
@@ -0,0 +1,449 @@
+use std::{ops::Range, path::PathBuf};
+
+use crate::{
+    code::{code_ranges, in_ranges},
+    math::math_ranges,
+    Error, ObsidianNote, Properties, TextEdit, Vault,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    /// The tag without its leading `#`, e.g. `project/acme`
+    pub name: String,
+    pub source: TagSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagSource {
+    Frontmatter,
+    /// Byte range of the `#tag` within the note body
+    Inline(Range<usize>),
+}
+
+impl Tag {
+    fn frontmatter(name: &str) -> Self {
+        Self {
+            name: name.trim_start_matches('#').to_string(),
+            source: TagSource::Frontmatter,
+        }
+    }
+
+    /// The enclosing tags from the top down, e.g. `project` and `project/client` for
+    /// `project/client/acme`
+    pub fn parents(&self) -> Vec<&str> {
+        self.name
+            .match_indices('/')
+            .map(|(i, _)| &self.name[..i])
+            .filter(|parent| !parent.is_empty())
+            .collect()
+    }
+
+    /// Whether this tag is nested somewhere under `ancestor`, ignoring case and a leading `#`
+    pub fn is_descendant_of(&self, ancestor: &str) -> bool {
+        let ancestor = ancestor.trim_start_matches('#').trim_end_matches('/');
+        self.parents()
+            .iter()
+            .any(|parent| parent.eq_ignore_ascii_case(ancestor))
+    }
+
+    /// Matches `project` exactly, `project/*` for tags directly under it, or `project/**` for
+    /// the tag and everything nested under it
+    pub fn matches(&self, pattern: &str) -> bool {
+        let pattern = pattern.trim_start_matches('#');
+        if let Some(ancestor) = pattern.strip_suffix("/**") {
+            self.name.eq_ignore_ascii_case(ancestor) || self.is_descendant_of(ancestor)
+        } else if let Some(parent) = pattern.strip_suffix("/*") {
+            self.parents()
+                .last()
+                .is_some_and(|p| p.eq_ignore_ascii_case(parent))
+        } else {
+            self.name.eq_ignore_ascii_case(pattern)
+        }
+    }
+}
+
+impl Vault {
+    /// Notes with a tag matching `pattern`, as in [`Tag::matches`]
+    pub fn notes_with_tag<'a>(
+        &self,
+        pattern: &'a str,
+    ) -> impl Iterator<Item = crate::Result<ObsidianNote>> + 'a {
+        self.notes().filter(move |note| match note {
+            Ok(note) => note.tags().iter().any(|tag| tag.matches(pattern)),
+            Err(_) => true,
+        })
+    }
+}
+
+impl ObsidianNote {
+    /// Frontmatter and inline tags, de-duplicated case-insensitively like Obsidian does
+    pub fn tags(&self) -> Vec<Tag> {
+        let mut tags: Vec<Tag> = Vec::new();
+        let all = self
+            .properties
+            .iter()
+            .flat_map(frontmatter_tags)
+            .chain(parse_inline_tags(&self.file_body));
+
+        for tag in all {
+            if !tags.iter().any(|t| t.name.eq_ignore_ascii_case(&tag.name)) {
+                tags.push(tag);
+            }
+        }
+
+        tags
+    }
+}
+
+/// The changes renaming a tag makes to one note
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagRename {
+    pub path: PathBuf,
+    /// Replacements for inline tags, with spans relative to the note body
+    pub body_edits: Vec<TextEdit>,
+    /// The property holding the note's tags and its new value, if any entries changed
+    pub property: Option<(String, Properties)>,
+}
+
+impl Vault {
+    /// Renames a tag, and the tags nested under it, in the bodies and frontmatter of every note.
+    /// Tags in code are left alone. Returns the changes made.
+    pub fn rename_tag(&self, old: &str, new: &str) -> crate::Result<Vec<TagRename>> {
+        let plan = self.plan_tag_rename(old, new)?;
+        let mut transaction = self.transaction();
+        for change in &plan {
+            transaction.edit_note(&change.path, |note| {
+                note.edit_body(&change.body_edits)?;
+                match &change.property {
+                    Some((key, value)) => note.set_property(key, value.clone()),
+                    None => Ok(()),
+                }
+            })?;
+        }
+        transaction.commit()?;
+        Ok(plan)
+    }
+
+    /// The changes [`Vault::rename_tag`] would make, without writing anything
+    pub fn plan_tag_rename(&self, old: &str, new: &str) -> crate::Result<Vec<TagRename>> {
+        let old = old.trim_start_matches('#');
+        let new = new.trim_start_matches('#');
+        let valid = |tag: &str| !tag.is_empty() && tag.chars().all(is_tag_char);
+        for tag in [old, new] {
+            if !valid(tag) {
+                return Err(Error::InvalidTag(tag.to_string()));
+            }
+        }
+
+        let mut plan = Vec::new();
+        for note in self.notes() {
+            let note = note?;
+            let body_edits: Vec<TextEdit> = parse_inline_tags(&note.file_body)
+                .into_iter()
+                .filter_map(|tag| {
+                    let TagSource::Inline(span) = tag.source else {
+                        return None;
+                    };
+                    let renamed = renamed_tag(&tag.name, old, new)?;
+                    Some(TextEdit::new(span, format!("#{renamed}")))
+                })
+                .collect();
+            let property = note
+                .properties
+                .as_ref()
+                .and_then(|properties| renamed_tags_property(properties, old, new));
+
+            if !body_edits.is_empty() || property.is_some() {
+                plan.push(TagRename {
+                    path: note.file_path,
+                    body_edits,
+                    property,
+                });
+            }
+        }
+        Ok(plan)
+    }
+}
+
+/// `name` with `old` replaced by `new`, if it is `old` or nested under it
+fn renamed_tag(name: &str, old: &str, new: &str) -> Option<String> {
+    let prefix = name.get(..old.len())?;
+    let rest = &name[old.len()..];
+    (prefix.eq_ignore_ascii_case(old) && (rest.is_empty() || rest.starts_with('/')))
+        .then(|| format!("{new}{rest}"))
+}
+
+fn renamed_tags_property(
+    properties: &Properties,
+    old: &str,
+    new: &str,
+) -> Option<(String, Properties)> {
+    let key = ["tags", "tag"]
+        .into_iter()
+        .find(|key| properties.get(key).is_some())?;
+    let rename = |entry: &str| {
+        let hash = if entry.starts_with('#') { "#" } else { "" };
+        renamed_tag(entry.trim_start_matches('#'), old, new).map(|name| format!("{hash}{name}"))
+    };
+
+    let value = match properties.get(key)? {
+        Properties::Sequence(values) => {
+            let mut changed = false;
+            let values = values
+                .iter()
+                .map(|value| match value.as_str().and_then(rename) {
+                    Some(renamed) => {
+                        changed = true;
+                        Properties::from(renamed)
+                    }
+                    None => value.clone(),
+                })
+                .collect();
+            changed.then_some(Properties::Sequence(values))?
+        }
+        // Keep the original separators
+        Properties::String(value) => {
+            let mut renamed = String::new();
+            let mut changed = false;
+            for part in value.split_inclusive([',', ' ']) {
+                let (entry, separator) = match part.strip_suffix([',', ' ']) {
+                    Some(entry) => (entry, &part[entry.len()..]),
+                    None => (part, ""),
+                };
+                match rename(entry) {
+                    Some(entry) => {
+                        changed = true;
+                        renamed.push_str(&entry);
+                    }
+                    None => renamed.push_str(entry),
+                }
+                renamed.push_str(separator);
+            }
+            changed.then_some(Properties::String(renamed))?
+        }
+        _ => return None,
+    };
+    Some((key.to_string(), value))
+}
+
+/// Tags from the `tags` (or legacy `tag`) property, as a list or a comma/space separated string
+pub fn frontmatter_tags(properties: &Properties) -> Vec<Tag> {
+    let value = properties.get("tags").or_else(|| properties.get("tag"));
+
+    match value {
+        Some(Properties::Sequence(values)) => values
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(Tag::frontmatter)
+            .collect(),
+        Some(Properties::String(value)) => value
+            .split([',', ' '])
+            .filter(|s| !s.is_empty())
+            .map(Tag::frontmatter)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Every inline `#tag` occurrence, skipping code, math and `#` that doesn't follow whitespace
+pub fn parse_inline_tags(text: &str) -> Vec<Tag> {
+    let mut skipped = code_ranges(text);
+    skipped.extend(math_ranges(text));
+    let mut tags = Vec::new();
+
+    for (start, _) in text.match_indices('#') {
+        if in_ranges(&skipped, start) {
+            continue;
+        }
+        // Tags must start a line or follow whitespace, which also rules out URL fragments
+        if text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| !c.is_whitespace())
+        {
+            continue;
+        }
+
+        let name_len: usize = text[start + 1..]
+            .chars()
+            .take_while(|c| is_tag_char(*c))
+            .map(char::len_utf8)
+            .sum();
+        let name = &text[start + 1..start + 1 + name_len];
+
+        // Purely numeric tags aren't allowed
+        if name.chars().all(|c| c.is_ascii_digit() || c == '/') {
+            continue;
+        }
+
+        tags.push(Tag {
+            name: name.to_string(),
+            source: TagSource::Inline(start..start + 1 + name_len),
+        });
+    }
+
+    tags
+}
+
+pub(crate) fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '/') || (!c.is_ascii() && !c.is_whitespace())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::fs;
+
+    fn names(tags: &[Tag]) -> Vec<&str> {
+        tags.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    #[test]
+    fn parse_inline_tags_finds_tags() {
+        let tags = parse_inline_tags("#start some #nested/tag text, #with-dash_and_underscore");
+        assert_eq!(
+            names(&tags),
+            vec!["start", "nested/tag", "with-dash_and_underscore"]
+        );
+        assert_eq!(tags[0].source, TagSource::Inline(0..6));
+    }
+
+    #[test]
+    fn parse_inline_tags_ignores_headings_urls_and_numbers() {
+        let tags = parse_inline_tags(indoc! {r"
+            # Heading
+            ## Subheading
+            https://example.com/#anchor [[Note#Heading]] #2024
+        "});
+        assert!(tags.is_empty());
+    }
+
+    #[test]
+    fn parse_inline_tags_ignores_code_and_math() {
+        let tags = parse_inline_tags(indoc! {r"
+            `#inline` #real $x #notatag$
+            ```
+            #fenced
+            ```
+        "});
+        assert_eq!(names(&tags), vec!["real"]);
+    }
+
+    #[test]
+    fn tags_merges_frontmatter_and_inline() {
+        let note_content = indoc! {r"
+            ---
+            tags:
+              - '#project'
+              - reading
+            ---
+            Some #Project text #inline
+        "};
+        let note =
+            ObsidianNote::parse(&PathBuf::from("a-note.md"), note_content.to_string()).unwrap();
+        let tags = note.tags();
+
+        assert_eq!(names(&tags), vec!["project", "reading", "inline"]);
+        assert_eq!(tags[0].source, TagSource::Frontmatter);
+    }
+
+    #[test]
+    fn tags_reads_string_frontmatter() {
+        let note_content = indoc! {r"
+            ---
+            tags: one, two
+            ---
+        "};
+        let note =
+            ObsidianNote::parse(&PathBuf::from("a-note.md"), note_content.to_string()).unwrap();
+
+        assert_eq!(names(&note.tags()), vec!["one", "two"]);
+    }
+
+    #[test]
+    fn tags_know_their_hierarchy() {
+        let tag = Tag::frontmatter("project/client/acme");
+        assert_eq!(tag.parents(), vec!["project", "project/client"]);
+        assert!(tag.is_descendant_of("#Project"));
+        assert!(tag.is_descendant_of("project/client"));
+        assert!(!tag.is_descendant_of("project/client/acme"));
+        assert!(!tag.is_descendant_of("proj"));
+
+        assert!(tag.matches("project/**"));
+        assert!(tag.matches("project/client/*"));
+        assert!(!tag.matches("project/*"));
+        assert!(Tag::frontmatter("project").matches("#project/**"));
+    }
+
+    #[test]
+    fn notes_with_tag_matches_nested_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "#project/client/acme").unwrap();
+        std::fs::write(dir.path().join("b.md"), "---\ntags: project\n---\n").unwrap();
+        std::fs::write(dir.path().join("c.md"), "#projects").unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let names: Vec<PathBuf> = vault
+            .notes_with_tag("#project/**")
+            .map(|note| vault.relative_path(&note.unwrap().file_path).to_path_buf())
+            .collect();
+        assert_eq!(names, vec![PathBuf::from("a.md"), PathBuf::from("b.md")]);
+    }
+
+    #[test]
+    fn rename_tag_rewrites_bodies_and_frontmatter() {
+        let dir = tempfile::tempdir().unwrap();
+        let note = indoc! {"
+            ---
+            tags: [Project/acme, other]
+            ---
+            Tagged #project and #project/acme, not #projects or `#project`.
+        "};
+        fs::write(dir.path().join("a.md"), note).unwrap();
+        fs::write(
+            dir.path().join("b.md"),
+            "---\ntags: \"work, #project\"\n---\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("c.md"), "#other").unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let plan = vault.plan_tag_rename("#project", "#work/client").unwrap();
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[0].body_edits.len(), 2);
+        assert_eq!(
+            plan[1].property,
+            Some(("tags".to_string(), "work, #work/client".into()))
+        );
+        assert_eq!(fs::read_to_string(dir.path().join("a.md")).unwrap(), note);
+
+        vault.rename_tag("project", "work/client").unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("a.md")).unwrap(),
+            indoc! {"
+                ---
+                tags:
+                - work/client/acme
+                - other
+                ---
+                Tagged #work/client and #work/client/acme, not #projects or `#project`.
+            "}
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("c.md")).unwrap(),
+            "#other"
+        );
+    }
+
+    #[test]
+    fn rename_tag_rejects_invalid_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        assert!(matches!(
+            vault.rename_tag("project", "two words"),
+            Err(Error::InvalidTag(_))
+        ));
+    }
+}
@@ -0,0 +1,195 @@
+use std::ops::Range;
+
+use crate::{
+    code::{fenced_ranges, in_ranges},
+    headings::atx_heading,
+    ObsidianNote,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Task {
+    /// The character between the brackets, `' '` for open tasks
+    pub status: char,
+    pub text: String,
+    /// Nesting depth within the list, starting at 0
+    pub indent: usize,
+    /// Text of the closest heading above the task
+    pub heading: Option<String>,
+    /// 1-based line number within the note body
+    pub line: usize,
+    pub span: Range<usize>,
+}
+
+impl Task {
+    pub fn is_complete(&self) -> bool {
+        self.status != ' '
+    }
+}
+
+impl ObsidianNote {
+    pub fn tasks(&self) -> Vec<Task> {
+        parse_tasks(&self.file_body)
+    }
+}
+
+pub fn parse_tasks(text: &str) -> Vec<Task> {
+    let code = fenced_ranges(text);
+    let mut tasks = Vec::new();
+    let mut heading: Option<String> = None;
+    // Indentation widths of the list items enclosing the current line
+    let mut list_indents: Vec<usize> = Vec::new();
+    let mut offset = 0;
+
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        let start = offset;
+        offset += line.len();
+        if in_ranges(&code, start) {
+            continue;
+        }
+
+        let content = line.trim_end_matches(['\n', '\r']);
+        if let Some((_, text)) = atx_heading(content) {
+            heading = Some(text.to_string());
+            list_indents.clear();
+            continue;
+        }
+
+        let Some(item) = list_item(content) else {
+            if !content.trim().is_empty() && !content.starts_with([' ', '\t']) {
+                list_indents.clear();
+            }
+            continue;
+        };
+
+        while list_indents.last().is_some_and(|&i| i >= item.indent) {
+            list_indents.pop();
+        }
+        let depth = list_indents.len();
+        list_indents.push(item.indent);
+
+        if let Some((status, task_text)) = checkbox(item.content) {
+            tasks.push(Task {
+                status,
+                text: task_text.to_string(),
+                indent: depth,
+                heading: heading.clone(),
+                line: index + 1,
+                span: start..start + content.len(),
+            });
+        }
+    }
+
+    tasks
+}
+
+pub(crate) struct ListItem<'a> {
+    /// Width of the leading whitespace, counting tabs as four columns
+    pub indent: usize,
+    /// The text after the list marker
+    pub content: &'a str,
+}
+
+pub(crate) fn list_item(line: &str) -> Option<ListItem<'_>> {
+    let trimmed = line.trim_start_matches([' ', '\t']);
+    let indent = line[..line.len() - trimmed.len()]
+        .chars()
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum();
+
+    let content = if let Some(rest) = trimmed.strip_prefix(['-', '*', '+']) {
+        rest
+    } else {
+        let digits = trimmed.len()
+            - trimmed
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+        if digits == 0 {
+            return None;
+        }
+        trimmed[digits..].strip_prefix(['.', ')'])?
+    };
+
+    if !(content.is_empty() || content.starts_with([' ', '\t'])) {
+        return None;
+    }
+    Some(ListItem {
+        indent,
+        content: content.trim_start(),
+    })
+}
+
+fn checkbox(content: &str) -> Option<(char, &str)> {
+    let rest = content.strip_prefix('[')?;
+    let mut chars = rest.chars();
+    let status = chars.next()?;
+    let rest = chars.as_str().strip_prefix(']')?;
+    if !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+
+    Some((status, rest.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn parse_tasks_reads_status_and_text() {
+        let tasks = parse_tasks("- [ ] Open\n- [x] Done\n* [/] In progress\n1. [-] Cancelled");
+        let statuses: Vec<(char, &str, bool)> = tasks
+            .iter()
+            .map(|t| (t.status, t.text.as_str(), t.is_complete()))
+            .collect();
+
+        assert_eq!(
+            statuses,
+            vec![
+                (' ', "Open", false),
+                ('x', "Done", true),
+                ('/', "In progress", true),
+                ('-', "Cancelled", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_tasks_tracks_indent_heading_and_line() {
+        let tasks = parse_tasks(indoc! {r"
+            # Project
+            - [ ] Parent
+              - [ ] Child
+                - [x] Grandchild
+            ## Later
+            - regular item
+            	- [ ] Tabbed child
+        "});
+
+        let summary: Vec<(usize, Option<&str>, usize)> = tasks
+            .iter()
+            .map(|t| (t.indent, t.heading.as_deref(), t.line))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, Some("Project"), 2),
+                (1, Some("Project"), 3),
+                (2, Some("Project"), 4),
+                (1, Some("Later"), 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_tasks_ignores_code_and_links() {
+        let tasks = parse_tasks(indoc! {r"
+            ```
+            - [ ] Example
+            ```
+            - [link](https://example.com)
+            - [[Wikilink]]
+        "});
+        assert!(tasks.is_empty());
+    }
+}
@@ -0,0 +1,461 @@
+use chrono::Days;
+
+use crate::{format_moment, Error, TemplateContext};
+
+/// Evaluates the subset of Templater syntax that doesn't need a JavaScript engine
+///
+/// `<% ... %>` outputs `tp.file.title`, `tp.date.now/today/tomorrow/yesterday(format,
+/// offset)`, `tp.file.creation_date(format)`, the context's variables or string and number
+/// literals. `<%* ... %>` may hold `if (...) {`, `} else if (...) {`, `} else {` and `}`,
+/// with conditions using `==`, `!=`, `&&`, `||` and `!`. The `-` and `_` markers trim one
+/// newline or all whitespace next to a tag. Anything else is an [`Error::Templater`].
+pub fn expand_templater(template: &str, context: &TemplateContext) -> crate::Result<String> {
+    let mut expanded = String::new();
+    // Whether each enclosing `if` is active, and whether one of its branches already ran
+    let mut branches: Vec<(bool, bool)> = Vec::new();
+    let active = |branches: &[(bool, bool)]| branches.last().is_none_or(|(active, _)| *active);
+    let mut rest = template;
+    let mut trim_next = Trim::None;
+
+    while let Some(start) = rest.find("<%") {
+        let Some(end) = rest[start..].find("%>").map(|end| start + end) else {
+            return Err(Error::Templater(rest[start..].to_string()));
+        };
+        let tag = &rest[start + 2..end];
+        let trim_before = Trim::from_marker(tag.chars().next());
+        let trim_after = Trim::from_marker(tag.chars().last());
+        let tag = tag.trim_start_matches(['-', '_']);
+        let execute = tag.starts_with('*');
+        let code = tag
+            .trim_start_matches('*')
+            .trim_end_matches(['-', '_'])
+            .trim();
+
+        let text = trim_next.start(&rest[..start]);
+        let text = trim_before.end(text);
+        if active(&branches) {
+            expanded.push_str(text);
+        }
+        rest = &rest[end + 2..];
+        trim_next = trim_after;
+
+        if !execute {
+            if active(&branches) {
+                expanded.push_str(&evaluate(code, context)?.to_string());
+            }
+            continue;
+        }
+
+        let condition = |code: &str| -> crate::Result<bool> {
+            let condition = code
+                .strip_prefix('(')
+                .and_then(|code| code.strip_suffix('{'))
+                .and_then(|code| code.trim_end().strip_suffix(')'))
+                .ok_or_else(|| Error::Templater(code.to_string()))?;
+            Ok(evaluate(condition, context)?.is_truthy())
+        };
+        if let Some(code) = code.strip_prefix("if") {
+            let outer = active(&branches);
+            let taken = outer && condition(code.trim())?;
+            branches.push((taken, taken));
+        } else if let Some(code) = code.strip_prefix('}').map(str::trim) {
+            if code.is_empty() {
+                branches
+                    .pop()
+                    .ok_or_else(|| Error::Templater("}".to_string()))?;
+                continue;
+            }
+            let branch = code
+                .strip_prefix("else")
+                .map(str::trim)
+                .ok_or_else(|| Error::Templater(code.to_string()))?;
+            let outer = active(&branches[..branches.len().saturating_sub(1)]);
+            let (_, done) = *branches
+                .last()
+                .ok_or_else(|| Error::Templater(code.to_string()))?;
+            let taken = match branch.strip_prefix("if") {
+                Some(code) => outer && !done && condition(code.trim())?,
+                None if branch == "{" => outer && !done,
+                None => return Err(Error::Templater(code.to_string())),
+            };
+            *branches.last_mut().unwrap() = (taken, done || taken);
+        } else {
+            return Err(Error::Templater(code.to_string()));
+        }
+    }
+
+    if !branches.is_empty() {
+        return Err(Error::Templater("unclosed `if`".to_string()));
+    }
+    expanded.push_str(trim_next.start(rest));
+    Ok(expanded)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Trim {
+    None,
+    /// `-`: one newline
+    Newline,
+    /// `_`: all whitespace
+    Whitespace,
+}
+
+impl Trim {
+    fn from_marker(marker: Option<char>) -> Self {
+        match marker {
+            Some('-') => Self::Newline,
+            Some('_') => Self::Whitespace,
+            _ => Self::None,
+        }
+    }
+
+    fn start(self, text: &str) -> &str {
+        match self {
+            Self::None => text,
+            Self::Newline => text
+                .strip_prefix("\r\n")
+                .or_else(|| text.strip_prefix('\n'))
+                .unwrap_or(text),
+            Self::Whitespace => text.trim_start(),
+        }
+    }
+
+    fn end(self, text: &str) -> &str {
+        match self {
+            Self::None => text,
+            Self::Newline => text
+                .strip_suffix("\r\n")
+                .or_else(|| text.strip_suffix('\n'))
+                .unwrap_or(text),
+            Self::Whitespace => text.trim_end(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    String(String),
+    Number(f64),
+    Bool(bool),
+    Undefined,
+}
+
+impl Value {
+    fn is_truthy(&self) -> bool {
+        match self {
+            Self::String(s) => !s.is_empty(),
+            Self::Number(n) => *n != 0.0 && !n.is_nan(),
+            Self::Bool(b) => *b,
+            Self::Undefined => false,
+        }
+    }
+
+    fn loosely_equals(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Number(n), Self::String(s)) | (Self::String(s), Self::Number(n)) => {
+                s.trim().parse::<f64>().is_ok_and(|s| s == *n)
+            }
+            _ => self == other,
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::String(s) => f.write_str(s),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Undefined => f.write_str("undefined"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    String(String),
+    Number(f64),
+    Name(String),
+    Symbol(&'static str),
+}
+
+fn lex(code: &str) -> crate::Result<Vec<Token>> {
+    const SYMBOLS: [&str; 11] = [
+        "===", "!==", "==", "!=", "&&", "||", "!", "(", ")", ",", ".",
+    ];
+    let invalid = || Error::Templater(code.to_string());
+    let mut tokens = Vec::new();
+    let mut rest = code.trim_start();
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+            tokens.push(Token::Symbol(symbol));
+            rest = &rest[symbol.len()..];
+        } else if matches!(c, '"' | '\'' | '`') {
+            let end = rest[1..].find(c).ok_or_else(invalid)? + 1;
+            tokens.push(Token::String(rest[1..end].to_string()));
+            rest = &rest[end + 1..];
+        } else if c.is_ascii_digit() || c == '-' {
+            let len = 1 + rest[1..]
+                .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .unwrap_or(rest.len() - 1);
+            tokens.push(Token::Number(rest[..len].parse().map_err(|_| invalid())?));
+            rest = &rest[len..];
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Name(rest[..len].to_string()));
+            rest = &rest[len..];
+        } else {
+            return Err(invalid());
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+fn evaluate(code: &str, context: &TemplateContext) -> crate::Result<Value> {
+    let tokens = lex(code)?;
+    let mut parser = Parser {
+        code,
+        tokens: &tokens,
+        context,
+    };
+    let value = parser.or()?;
+    if !parser.tokens.is_empty() {
+        return Err(parser.error());
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    code: &'a str,
+    tokens: &'a [Token],
+    context: &'a TemplateContext,
+}
+
+impl Parser<'_> {
+    fn error(&self) -> Error {
+        Error::Templater(self.code.to_string())
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        match self.tokens.first() {
+            Some(Token::Symbol(s)) if *s == symbol => {
+                self.tokens = &self.tokens[1..];
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn or(&mut self) -> crate::Result<Value> {
+        let mut value = self.and()?;
+        while self.eat("||") {
+            let right = self.and()?;
+            if !value.is_truthy() {
+                value = right;
+            }
+        }
+        Ok(value)
+    }
+
+    fn and(&mut self) -> crate::Result<Value> {
+        let mut value = self.comparison()?;
+        while self.eat("&&") {
+            let right = self.comparison()?;
+            if value.is_truthy() {
+                value = right;
+            }
+        }
+        Ok(value)
+    }
+
+    fn comparison(&mut self) -> crate::Result<Value> {
+        let left = self.unary()?;
+        for (symbol, strict, negate) in [
+            ("===", true, false),
+            ("!==", true, true),
+            ("==", false, false),
+            ("!=", false, true),
+        ] {
+            if self.eat(symbol) {
+                let right = self.unary()?;
+                let equal = if strict {
+                    left == right
+                } else {
+                    left.loosely_equals(&right)
+                };
+                return Ok(Value::Bool(equal != negate));
+            }
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> crate::Result<Value> {
+        if self.eat("!") {
+            return Ok(Value::Bool(!self.unary()?.is_truthy()));
+        }
+        if self.eat("(") {
+            let value = self.or()?;
+            return if self.eat(")") {
+                Ok(value)
+            } else {
+                Err(self.error())
+            };
+        }
+
+        let (token, rest) = self.tokens.split_first().ok_or_else(|| self.error())?;
+        self.tokens = rest;
+        match token {
+            Token::String(s) => Ok(Value::String(s.clone())),
+            Token::Number(n) => Ok(Value::Number(*n)),
+            Token::Name(name) if name == "true" || name == "false" => {
+                Ok(Value::Bool(name == "true"))
+            }
+            Token::Name(name) => {
+                let mut path = vec![name.clone()];
+                while self.eat(".") {
+                    match self.tokens.split_first() {
+                        Some((Token::Name(name), rest)) => {
+                            path.push(name.clone());
+                            self.tokens = rest;
+                        }
+                        _ => return Err(self.error()),
+                    }
+                }
+                let args = if self.eat("(") {
+                    let mut args = Vec::new();
+                    while !self.eat(")") {
+                        if !args.is_empty() && !self.eat(",") {
+                            return Err(self.error());
+                        }
+                        args.push(self.or()?);
+                    }
+                    Some(args)
+                } else {
+                    None
+                };
+                self.lookup(&path.join("."), args)
+            }
+            Token::Symbol(_) => Err(self.error()),
+        }
+    }
+
+    fn lookup(&self, name: &str, args: Option<Vec<Value>>) -> crate::Result<Value> {
+        let context = self.context;
+        let arg = |i: usize| args.as_ref().and_then(|args| args.get(i));
+        let format = |default: &str| match arg(0) {
+            Some(Value::String(format)) => format.clone(),
+            _ => default.to_string(),
+        };
+        let shifted = |days: f64| {
+            let days = days as i64;
+            let datetime = if days >= 0 {
+                context.datetime.checked_add_days(Days::new(days as u64))
+            } else {
+                context
+                    .datetime
+                    .checked_sub_days(Days::new(days.unsigned_abs()))
+            };
+            datetime.ok_or_else(|| self.error())
+        };
+
+        let value = match (name, &args) {
+            ("tp.file.title", None) => Value::String(context.title.clone()),
+            ("tp.file.creation_date" | "tp.file.last_modified_date", Some(_)) => {
+                Value::String(format_moment(context.datetime, &format("YYYY-MM-DD HH:mm")))
+            }
+            ("tp.date.now", Some(_)) => {
+                let offset = match arg(1) {
+                    Some(Value::Number(days)) => *days,
+                    None => 0.0,
+                    Some(_) => return Err(self.error()),
+                };
+                Value::String(format_moment(shifted(offset)?, &format("YYYY-MM-DD")))
+            }
+            ("tp.date.today", Some(_)) => {
+                Value::String(format_moment(context.datetime, &format("YYYY-MM-DD")))
+            }
+            ("tp.date.tomorrow", Some(_)) => {
+                Value::String(format_moment(shifted(1.0)?, &format("YYYY-MM-DD")))
+            }
+            ("tp.date.yesterday", Some(_)) => {
+                Value::String(format_moment(shifted(-1.0)?, &format("YYYY-MM-DD")))
+            }
+            (name, None) if !name.contains('.') => match context.variables.get(name) {
+                Some(value) => Value::String(value.clone()),
+                None => Value::Undefined,
+            },
+            _ => return Err(self.error()),
+        };
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use indoc::indoc;
+
+    fn context() -> TemplateContext {
+        let datetime = NaiveDate::from_ymd_opt(2024, 2, 29)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+            .unwrap();
+        TemplateContext::new("Plan")
+            .at(datetime)
+            .variable("kind", "work")
+    }
+
+    #[test]
+    fn expand_templater_outputs_dates_and_titles() {
+        assert_eq!(
+            expand_templater(
+                r#"# <% tp.file.title %> <% tp.date.now("YYYY-MM-DD") %>, next <% tp.date.now("ddd", 7) %>, <% tp.date.yesterday() %>"#,
+                &context()
+            )
+            .unwrap(),
+            "# Plan 2024-02-29, next Thu, 2024-02-28"
+        );
+    }
+
+    #[test]
+    fn expand_templater_handles_conditionals() {
+        let template = indoc! {r#"
+            <%* if (kind == "home") { -%>
+            Chores
+            <%* } else if (kind === 'work' && tp.file.title != "") { -%>
+            Standup for <% tp.file.title %>
+            <%* } else { -%>
+            Rest
+            <%* } -%>
+            Done
+        "#};
+
+        assert_eq!(
+            expand_templater(template, &context()).unwrap(),
+            "Standup for Plan\nDone\n"
+        );
+    }
+
+    #[test]
+    fn expand_templater_rejects_unsupported_code() {
+        for template in [
+            "<% tp.system.prompt() %>",
+            "<%* let x = 1 %>",
+            "<%* if (true) { %>",
+            "<% tp.file.title",
+        ] {
+            assert!(
+                matches!(
+                    expand_templater(template, &context()),
+                    Err(Error::Templater(_))
+                ),
+                "{template}"
+            );
+        }
+    }
+}
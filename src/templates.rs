@@ -0,0 +1,230 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::{config::read_config, expand_templater, format_moment, ObsidianNote, Vault};
+
+/// `templates.json`, the templates core plugin's settings
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TemplatesConfig {
+    pub folder: String,
+    /// Empty means `YYYY-MM-DD`
+    pub date_format: String,
+    /// Empty means `HH:mm`
+    pub time_format: String,
+    /// Settings without a typed field
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+/// The values a template is filled in with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateContext {
+    /// `{{title}}`, the new note's name
+    pub title: String,
+    /// The moment `{{date}}` and `{{time}}` refer to
+    pub datetime: NaiveDateTime,
+    pub date_format: String,
+    pub time_format: String,
+    /// Extra `{{name}}` variables, which take precedence over the built-in ones
+    pub variables: BTreeMap<String, String>,
+}
+
+impl TemplateContext {
+    /// A context for a note called `title`, at the current local time
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            datetime: Local::now().naive_local(),
+            date_format: "YYYY-MM-DD".to_string(),
+            time_format: "HH:mm".to_string(),
+            variables: BTreeMap::new(),
+        }
+    }
+
+    pub fn at(mut self, datetime: NaiveDateTime) -> Self {
+        self.datetime = datetime;
+        self
+    }
+
+    pub fn variable(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.variables.insert(name.to_string(), value.into());
+        self
+    }
+}
+
+/// Fills in `{{title}}`, `{{date}}` and `{{time}}`, optionally with a moment.js format as in
+/// `{{date:YYYY-MM-DD}}`, and the context's own variables. Unknown variables are left as
+/// they are.
+pub fn expand_template(template: &str, context: &TemplateContext) -> String {
+    let mut expanded = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}").map(|end| start + end) else {
+            break;
+        };
+        expanded.push_str(&rest[..start]);
+        let variable = rest[start + 2..end].trim();
+        match expand_variable(variable, context) {
+            Some(value) => expanded.push_str(&value),
+            None => expanded.push_str(&rest[start..end + 2]),
+        }
+        rest = &rest[end + 2..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+fn expand_variable(variable: &str, context: &TemplateContext) -> Option<String> {
+    if let Some(value) = context.variables.get(variable) {
+        return Some(value.clone());
+    }
+
+    let (name, format) = match variable.split_once(':') {
+        Some((name, format)) => (name.trim(), Some(format.trim())),
+        None => (variable, None),
+    };
+    let value = match (name.to_lowercase().as_str(), format) {
+        ("title", None) => context.title.clone(),
+        ("date", format) => format_moment(context.datetime, format.unwrap_or(&context.date_format)),
+        ("time", format) => format_moment(context.datetime, format.unwrap_or(&context.time_format)),
+        _ => return None,
+    };
+    Some(value)
+}
+
+impl ObsidianNote {
+    /// A note at `path` from a template, ready to be written. Templater tags are evaluated
+    /// first, see [`expand_templater`].
+    pub fn from_template(
+        path: &Path,
+        template: &str,
+        context: &TemplateContext,
+    ) -> crate::Result<Self> {
+        let template = expand_templater(template, context)?;
+        Self::parse(path, expand_template(&template, context))
+    }
+}
+
+impl Vault {
+    pub fn templates_config(&self) -> crate::Result<TemplatesConfig> {
+        Ok(read_config(&self.config_dir(), "templates.json")?.unwrap_or_default())
+    }
+
+    /// A context for a new note at `path` using the configured date and time formats
+    pub fn template_context(&self, path: &Path) -> crate::Result<TemplateContext> {
+        let config = self.templates_config()?;
+        let title = path.file_stem().unwrap_or_default().to_string_lossy();
+        let mut context = TemplateContext::new(&title);
+        if !config.date_format.trim().is_empty() {
+            context.date_format = config.date_format.trim().to_string();
+        }
+        if !config.time_format.trim().is_empty() {
+            context.time_format = config.time_format.trim().to_string();
+        }
+        Ok(context)
+    }
+
+    /// Where a template lives: `name` in the templates folder, or a path from the vault root
+    /// if it isn't there. `.md` may be left off.
+    pub fn template_path(&self, name: &str) -> crate::Result<PathBuf> {
+        let name = name.trim().trim_start_matches('/');
+        let name = if name.ends_with(".md") {
+            name.to_string()
+        } else {
+            format!("{name}.md")
+        };
+
+        let folder = self.templates_config()?.folder;
+        let in_folder = self.path.join(folder.trim_matches('/')).join(&name);
+        if in_folder.is_file() {
+            return Ok(in_folder);
+        }
+        Ok(self.path.join(name))
+    }
+
+    /// The note the template `name` would create at `path`, a path from the vault root. The
+    /// note isn't written.
+    pub fn instantiate_template(
+        &self,
+        name: &str,
+        path: impl AsRef<Path>,
+        variables: &BTreeMap<String, String>,
+    ) -> crate::Result<ObsidianNote> {
+        let path = self.path.join(path);
+        let template = fs::read_to_string(self.template_path(name)?)?;
+        let mut context = self.template_context(&path)?;
+        context.variables.extend(variables.clone());
+        ObsidianNote::from_template(&path, &template, &context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CONFIG_DIR;
+    use chrono::NaiveDate;
+
+    fn datetime() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, 1)
+            .unwrap()
+            .and_hms_opt(9, 5, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn expand_template_fills_in_variables() {
+        let context = TemplateContext::new("Standup")
+            .at(datetime())
+            .variable("project", "Apollo");
+
+        assert_eq!(
+            expand_template(
+                "# {{title}} for {{project}}\n{{date}} {{time}} ({{date:dddd, MMM Do}}) {{other}}",
+                &context
+            ),
+            "# Standup for Apollo\n2024-03-01 09:05 (Friday, Mar 1st) {{other}}"
+        );
+    }
+
+    #[test]
+    fn instantiate_template_uses_templates_config() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(CONFIG_DIR)).unwrap();
+        fs::create_dir_all(dir.path().join("Templates")).unwrap();
+        fs::write(
+            dir.path().join(CONFIG_DIR).join("templates.json"),
+            r#"{"folder": "Templates", "dateFormat": "DD/MM"}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("Templates/Meeting.md"),
+            "---\ntopic: {{topic}}\n---\n# {{title}}\n",
+        )
+        .unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        let variables = BTreeMap::from([("topic".to_string(), "Budget".to_string())]);
+        let note = vault
+            .instantiate_template("Meeting", "Meetings/Q1 Review.md", &variables)
+            .unwrap();
+
+        assert_eq!(note.file_path, dir.path().join("Meetings/Q1 Review.md"));
+        assert_eq!(note.properties.unwrap()["topic"], "Budget");
+        assert_eq!(note.file_body, "# Q1 Review");
+        assert!(!note.file_path.exists());
+        assert_eq!(
+            vault
+                .template_context(Path::new("x.md"))
+                .unwrap()
+                .date_format,
+            "DD/MM"
+        );
+    }
+}
@@ -0,0 +1,15 @@
+use std::fs;
+
+use crate::Vault;
+
+/// A temporary vault holding `files`, given as paths relative to its root and their contents
+pub(crate) fn vault_with_files(files: &[(&str, &str)]) -> (tempfile::TempDir, Vault) {
+    let dir = tempfile::tempdir().unwrap();
+    for (path, contents) in files {
+        let path = dir.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    let vault = Vault::open(dir.path()).unwrap();
+    (dir, vault)
+}
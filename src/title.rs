@@ -0,0 +1,86 @@
+use crate::{ObsidianNote, Properties};
+
+/// How [`ObsidianNote::title_with`] picks a note's title
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TitleOptions {
+    /// The property holding the title
+    pub property: String,
+    /// Try the first `#` heading before falling back to the file name
+    pub first_heading: bool,
+}
+
+impl Default for TitleOptions {
+    fn default() -> Self {
+        Self {
+            property: "title".to_string(),
+            first_heading: false,
+        }
+    }
+}
+
+impl ObsidianNote {
+    /// The `title` property if it's set, otherwise the file name without its extension
+    pub fn title(&self) -> String {
+        self.title_with(&TitleOptions::default())
+    }
+
+    pub fn title_with(&self, options: &TitleOptions) -> String {
+        let property = self
+            .properties
+            .as_ref()
+            .and_then(|properties| properties.get(&options.property))
+            .and_then(|value| match value {
+                Properties::String(title) => Some(title.trim().to_string()),
+                Properties::Number(title) => Some(title.to_string()),
+                _ => None,
+            })
+            .filter(|title| !title.is_empty());
+        if let Some(title) = property {
+            return title;
+        }
+
+        if options.first_heading {
+            let heading = self.headings().into_iter().find(|h| h.level == 1);
+            if let Some(heading) = heading.filter(|h| !h.text.is_empty()) {
+                return heading.text;
+            }
+        }
+
+        self.file_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn note(contents: &str) -> ObsidianNote {
+        ObsidianNote::parse(Path::new("folder/File name.md"), contents.to_string()).unwrap()
+    }
+
+    #[test]
+    fn title_prefers_property_then_heading_then_file_name() {
+        let with_heading = TitleOptions {
+            first_heading: true,
+            ..TitleOptions::default()
+        };
+
+        let titled = note("---\ntitle: ' From properties '\n---\n# Heading");
+        assert_eq!(titled.title(), "From properties");
+        assert_eq!(titled.title_with(&with_heading), "From properties");
+
+        let headed = note("---\ntitle: ''\n---\n## Sub\n# Heading");
+        assert_eq!(headed.title(), "File name");
+        assert_eq!(headed.title_with(&with_heading), "Heading");
+
+        let custom = TitleOptions {
+            property: "name".to_string(),
+            ..TitleOptions::default()
+        };
+        assert_eq!(note("---\nname: 2024\n---").title_with(&custom), "2024");
+    }
+}
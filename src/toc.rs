@@ -0,0 +1,114 @@
+use crate::{headings::heading_link_text, ObsidianNote, TextEdit};
+
+/// The comment lines around a table of contents kept up to date by [`ObsidianNote::update_toc`]
+pub const TOC_START: &str = "%% toc %%";
+pub const TOC_END: &str = "%% /toc %%";
+
+impl ObsidianNote {
+    /// A nested list of `[[#Heading]]` links to the note's headings, down to `max_depth` (`1` for
+    /// just `#` headings)
+    pub fn toc(&self, max_depth: u8) -> String {
+        let mut toc = String::new();
+        let mut open_levels: Vec<u8> = Vec::new();
+
+        for heading in self.headings() {
+            if heading.level > max_depth {
+                continue;
+            }
+            while open_levels
+                .last()
+                .is_some_and(|&level| level >= heading.level)
+            {
+                open_levels.pop();
+            }
+
+            let link = heading_link_text(&heading.text);
+            let label = heading.text.replace(['[', ']', '|'], "");
+            toc.push_str(&"  ".repeat(open_levels.len()));
+            if link == label {
+                toc.push_str(&format!("- [[#{link}]]\n"));
+            } else {
+                toc.push_str(&format!("- [[#{link}|{label}]]\n"));
+            }
+            open_levels.push(heading.level);
+        }
+
+        toc
+    }
+
+    /// Rewrites the table of contents between [`TOC_START`] and [`TOC_END`], adding the block to
+    /// the top of the body if there isn't one yet
+    pub fn update_toc(&mut self, max_depth: u8) -> crate::Result<()> {
+        let toc = self.toc(max_depth);
+        let body = &self.file_body;
+
+        let block = body.find(TOC_START).and_then(|start| {
+            let contents = start + TOC_START.len();
+            let end = body[contents..].find(TOC_END)? + contents;
+            Some(contents..end)
+        });
+        let edit = match block {
+            Some(range) => TextEdit::new(range, format!("\n{toc}")),
+            None => {
+                let separator = if body.is_empty() { "" } else { "\n\n" };
+                TextEdit::new(0..0, format!("{TOC_START}\n{toc}{TOC_END}{separator}"))
+            }
+        };
+
+        self.edit_body(&[edit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::path::Path;
+
+    fn note(contents: &str) -> ObsidianNote {
+        ObsidianNote::parse(Path::new("a.md"), contents.to_string()).unwrap()
+    }
+
+    #[test]
+    fn toc_nests_headings_up_to_max_depth() {
+        let note = note(indoc! {r"
+            # Guide
+            ### Skipped a level
+            ## Setup: step #1
+            #### Too deep
+            # Appendix
+        "});
+
+        assert_eq!(
+            note.toc(3),
+            indoc! {r"
+                - [[#Guide]]
+                  - [[#Skipped a level]]
+                  - [[#Setup step 1|Setup: step #1]]
+                - [[#Appendix]]
+            "}
+        );
+        assert_eq!(note.toc(1), "- [[#Guide]]\n- [[#Appendix]]\n");
+    }
+
+    #[test]
+    fn update_toc_inserts_then_replaces_the_block() {
+        let mut note = note("---\ntitle: A\n---\n# One\nText\n");
+        note.update_toc(2).unwrap();
+        assert_eq!(
+            note.file_body,
+            "%% toc %%\n- [[#One]]\n%% /toc %%\n\n# One\nText"
+        );
+
+        note.edit_body(&[TextEdit::new(
+            note.file_body.len()..note.file_body.len(),
+            "\n## Two",
+        )])
+        .unwrap();
+        note.update_toc(2).unwrap();
+        assert_eq!(
+            note.to_string(),
+            "---\ntitle: A\n---\n%% toc %%\n- [[#One]]\n  - [[#Two]]\n%% /toc %%\n\n# One\nText\n## Two\n"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{Error, FileChange, ObsidianNote, Vault};
+
+/// Note creations, edits and renames staged with [`Vault::transaction`], made all at once by
+/// [`VaultTransaction::commit`]. Paths are relative to the vault root.
+///
+/// Staging checks each change against the vault as earlier staged changes would leave it, so an
+/// edit sees the contents staged before it and a rename frees up its old path. Nothing touches
+/// the vault until the commit, which undoes the changes it already made if a later one fails.
+#[derive(Debug)]
+pub struct VaultTransaction<'a> {
+    vault: &'a Vault,
+    changes: Vec<FileChange>,
+    staged: HashMap<PathBuf, Staged>,
+}
+
+/// What a staged change left at a path
+#[derive(Debug)]
+enum Staged {
+    Removed,
+    /// The file currently at this path on disk
+    MovedFrom(PathBuf),
+    Contents(String),
+}
+
+impl Vault {
+    pub fn transaction(&self) -> VaultTransaction<'_> {
+        VaultTransaction {
+            vault: self,
+            changes: Vec::new(),
+            staged: HashMap::new(),
+        }
+    }
+}
+
+impl VaultTransaction<'_> {
+    /// Stages a new note, failing if a file would already be at `path`
+    pub fn create_note(
+        &mut self,
+        path: impl AsRef<Path>,
+        contents: impl Into<String>,
+    ) -> crate::Result<()> {
+        let path = self.vault.path.join(path);
+        if self.exists(&path) {
+            return Err(Error::AlreadyExists(path));
+        }
+        self.stage_write(path, contents.into());
+        Ok(())
+    }
+
+    /// Stages running `update` on the note at `path`, as earlier staged changes would leave it
+    pub fn edit_note(
+        &mut self,
+        path: impl AsRef<Path>,
+        update: impl FnOnce(&mut ObsidianNote) -> crate::Result<()>,
+    ) -> crate::Result<()> {
+        let path = self.vault.path.join(path);
+        let read_options = self.vault.read_options;
+        let mut note = match self.staged.get(&path) {
+            Some(Staged::Removed) => return Err(Error::NoteNotFound(path)),
+            Some(Staged::MovedFrom(from)) => {
+                let mut note = ObsidianNote::read_from_path_with(from, read_options)?;
+                note.file_path = path.clone();
+                note
+            }
+            Some(Staged::Contents(contents)) => ObsidianNote::parse(&path, contents.clone())?,
+            None if path.is_file() => ObsidianNote::read_from_path_with(&path, read_options)?,
+            None => return Err(Error::NoteNotFound(path)),
+        };
+
+        update(&mut note).map_err(|err| err.in_file(&path))?;
+        let contents = note.to_string();
+        self.stage_write(path, contents);
+        Ok(())
+    }
+
+    /// Stages moving a file. Unlike [`Vault::rename_note`], links to it are left as they are.
+    pub fn rename(&mut self, from: impl AsRef<Path>, to: impl AsRef<Path>) -> crate::Result<()> {
+        let from = self.vault.path.join(from);
+        let to = self.vault.path.join(to);
+        if !self.exists(&from) {
+            return Err(Error::NoteNotFound(from));
+        }
+        if self.exists(&to) {
+            return Err(Error::AlreadyExists(to));
+        }
+
+        let moved = self
+            .staged
+            .insert(from.clone(), Staged::Removed)
+            .unwrap_or_else(|| Staged::MovedFrom(from.clone()));
+        self.staged.insert(to.clone(), moved);
+        self.changes.push(FileChange::Rename { from, to });
+        Ok(())
+    }
+
+    /// The changes staged so far, in the order they'll be made
+    pub fn changes(&self) -> &[FileChange] {
+        &self.changes
+    }
+
+    /// Makes every staged change, or none of them
+    pub fn commit(self) -> crate::Result<()> {
+        self.vault.writer.apply(self.changes)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        match self.staged.get(path) {
+            Some(Staged::Removed) => false,
+            Some(_) => true,
+            None => path.exists(),
+        }
+    }
+
+    fn stage_write(&mut self, path: PathBuf, contents: String) {
+        self.staged
+            .insert(path.clone(), Staged::Contents(contents.clone()));
+        self.changes.push(FileChange::Write { path, contents });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_support::vault_with_files, ReadOptions};
+    use std::fs;
+
+    #[test]
+    fn commit_makes_staged_changes_in_order() {
+        let (dir, vault) = vault_with_files(&[("a.md", "---\nstatus: draft\n---\nA")]);
+        let mut transaction = vault.transaction();
+
+        transaction.create_note("b.md", "B").unwrap();
+        transaction.rename("a.md", "Archive/a.md").unwrap();
+        transaction
+            .edit_note("Archive/a.md", |note| note.set_property("status", "done"))
+            .unwrap();
+        transaction.rename("b.md", "a.md").unwrap();
+        assert!(matches!(
+            transaction.create_note("Archive/a.md", ""),
+            Err(Error::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            transaction.edit_note("b.md", |_| Ok(())),
+            Err(Error::NoteNotFound(_))
+        ));
+        assert_eq!(transaction.changes().len(), 4);
+        transaction.commit().unwrap();
+
+        assert_eq!(fs::read_to_string(dir.path().join("a.md")).unwrap(), "B");
+        assert_eq!(
+            fs::read_to_string(dir.path().join("Archive/a.md")).unwrap(),
+            "---\nstatus: done\n---\nA"
+        );
+        assert!(!dir.path().join("b.md").exists());
+    }
+
+    #[test]
+    fn failed_commit_rolls_back() {
+        let (dir, vault) = vault_with_files(&[("a.md", "A"), ("b.md", "B")]);
+        let mut transaction = vault.transaction();
+        transaction.create_note("new.md", "New").unwrap();
+        transaction
+            .edit_note("a.md", |note| note.set_property("edited", true))
+            .unwrap();
+        transaction.rename("a.md", "c.md").unwrap();
+        transaction.rename("b.md", "d.md").unwrap();
+
+        fs::remove_file(dir.path().join("b.md")).unwrap();
+        assert!(transaction.commit().is_err());
+
+        let mut files: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        files.sort();
+        assert_eq!(files, ["a.md"]);
+        assert_eq!(fs::read_to_string(dir.path().join("a.md")).unwrap(), "A");
+    }
+
+    #[test]
+    fn edit_note_applies_read_options() {
+        let (dir, vault) = vault_with_files(&[]);
+        fs::write(dir.path().join("a.md"), b"A\xff").unwrap();
+        let mut transaction = vault.transaction();
+        assert!(matches!(
+            transaction.edit_note("a.md", |_| Ok(())),
+            Err(Error::InvalidUtf8(_))
+        ));
+
+        let vault = vault.with_read_options(ReadOptions {
+            lossy: true,
+            ..ReadOptions::default()
+        });
+        let mut transaction = vault.transaction();
+        transaction
+            .edit_note("a.md", |note| note.set_property("fixed", true))
+            .unwrap();
+        transaction.commit().unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("a.md")).unwrap(),
+            "---\nfixed: true\n---\nA\u{fffd}"
+        );
+    }
+}
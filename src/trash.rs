@@ -0,0 +1,233 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use walkdir::WalkDir;
+
+use crate::{
+    config::read_config,
+    edit::TextEdit,
+    links::scan_wikilinks,
+    rename::{path_to_link, same_name},
+    vault::is_note,
+    Error, FileChange, LinkResolver, Vault,
+};
+
+/// Obsidian's vault trash folder, used when "Deleted files" is set to "Move to Obsidian trash"
+pub const TRASH_DIR: &str = ".trash";
+
+/// Where trashed notes came from, by their path within the trash
+const LOCATIONS: &str = ".locations.json";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashedNote {
+    /// The note's path inside the trash folder
+    pub path: PathBuf,
+    /// Where the note was before it was trashed. Notes Obsidian trashed itself have no record.
+    pub original: Option<PathBuf>,
+}
+
+impl Vault {
+    pub fn trash_dir(&self) -> PathBuf {
+        self.path.join(TRASH_DIR)
+    }
+
+    /// Moves a note into the trash folder, numbering its name as in `Note 1.md` if the trash
+    /// already has one by that name, and records where it came from. Links to the note are
+    /// left as they are.
+    pub fn trash_note(&self, path: impl AsRef<Path>) -> crate::Result<TrashedNote> {
+        let original = self.path.join(path);
+        if !original.is_file() {
+            return Err(Error::NoteNotFound(original));
+        }
+
+        let trash = self.trash_dir();
+        let stem = original.file_stem().unwrap_or_default().to_string_lossy();
+        let trashed = (0..)
+            .map(|n| match n {
+                0 => trash.join(format!("{stem}.md")),
+                n => trash.join(format!("{stem} {n}.md")),
+            })
+            .find(|path| !path.exists())
+            .ok_or_else(|| Error::AlreadyExists(trash.clone()))?;
+
+        let mut locations = self.trash_locations()?;
+        locations.insert(
+            path_to_link(trashed.strip_prefix(&trash).unwrap_or(&trashed)),
+            path_to_link(self.relative_path(&original)),
+        );
+        self.writer.apply(vec![
+            FileChange::Rename {
+                from: original.clone(),
+                to: trashed.clone(),
+            },
+            FileChange::Write {
+                path: trash.join(LOCATIONS),
+                contents: serde_json::to_string_pretty(&locations)?,
+            },
+        ])?;
+
+        Ok(TrashedNote {
+            path: trashed,
+            original: Some(original),
+        })
+    }
+
+    /// The notes in the trash folder, sorted by path
+    pub fn trashed_notes(&self) -> crate::Result<Vec<TrashedNote>> {
+        let trash = self.trash_dir();
+        if !trash.is_dir() {
+            return Ok(Vec::new());
+        }
+        let locations = self.trash_locations()?;
+
+        let mut notes = Vec::new();
+        for entry in WalkDir::new(&trash).sort_by_file_name() {
+            let entry = entry?;
+            if !entry.file_type().is_file() || !is_note(entry.path()) {
+                continue;
+            }
+            let key = path_to_link(entry.path().strip_prefix(&trash).unwrap_or(entry.path()));
+            notes.push(TrashedNote {
+                original: locations.get(&key).map(|original| self.path.join(original)),
+                path: entry.into_path(),
+            });
+        }
+        Ok(notes)
+    }
+
+    /// Moves a trashed note back to where it was, or to the vault root when that isn't known.
+    /// Returns the restored note's path.
+    ///
+    /// With `repair_links`, wikilinks and embeds that don't resolve but name the note, such as
+    /// ones with its old folder, are rewritten to point at it in the vault's link format.
+    pub fn restore(&self, trashed: &TrashedNote, repair_links: bool) -> crate::Result<PathBuf> {
+        if !trashed.path.is_file() {
+            return Err(Error::NoteNotFound(trashed.path.clone()));
+        }
+        let destination = match &trashed.original {
+            Some(original) => original.clone(),
+            None => self.path.join(trashed.path.file_name().unwrap_or_default()),
+        };
+        if destination.exists() {
+            return Err(Error::AlreadyExists(destination));
+        }
+
+        let trash = self.trash_dir();
+        let mut locations = self.trash_locations()?;
+        locations.remove(&path_to_link(
+            trashed.path.strip_prefix(&trash).unwrap_or(&trashed.path),
+        ));
+        let mut changes = vec![
+            FileChange::Rename {
+                from: trashed.path.clone(),
+                to: destination.clone(),
+            },
+            FileChange::Write {
+                path: trash.join(LOCATIONS),
+                contents: serde_json::to_string_pretty(&locations)?,
+            },
+        ];
+        if repair_links {
+            changes.extend(self.link_repairs(&destination)?);
+        }
+
+        self.writer.apply(changes)?;
+        Ok(destination)
+    }
+
+    fn trash_locations(&self) -> crate::Result<BTreeMap<String, String>> {
+        Ok(read_config(&self.trash_dir(), LOCATIONS)?.unwrap_or_default())
+    }
+
+    /// Writes for the notes with broken links naming `restored`, as they will be once the note
+    /// is moved there
+    fn link_repairs(&self, restored: &Path) -> crate::Result<Vec<FileChange>> {
+        let notes = self.notes().collect::<crate::Result<Vec<_>>>()?;
+        let mut files = self.files().collect::<crate::Result<Vec<_>>>()?;
+        files.push(restored.to_path_buf());
+        let resolver = LinkResolver::new(&self.path, files);
+        let style = self.link_style()?;
+
+        let mut changes = Vec::new();
+        for mut note in notes {
+            let mut edits = Vec::new();
+            for (_, link) in scan_wikilinks(&note.file_body) {
+                let broken = !link.target.is_empty()
+                    && resolver.resolve_link(&link, &note.file_path).is_none();
+                if !broken || !same_name(&link.target, restored) {
+                    continue;
+                }
+
+                let raw = &note.file_body[link.span.clone()];
+                let target_start = raw.find("[[").unwrap_or_default() + 2;
+                let Some(offset) = raw[target_start..].find(&link.target) else {
+                    continue;
+                };
+                let start = link.span.start + target_start + offset;
+                let target = style.wikilink_target(self, &resolver, restored, &note.file_path);
+                edits.push(TextEdit::new(start..start + link.target.len(), target));
+            }
+
+            if !edits.is_empty() {
+                note.edit_body(&edits)?;
+                changes.push(FileChange::Write {
+                    path: note.file_path.clone(),
+                    contents: note.to_string(),
+                });
+            }
+        }
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::vault_with_files;
+    use std::fs;
+
+    #[test]
+    fn trash_and_restore_round_trip() {
+        let (dir, vault) = vault_with_files(&[
+            ("Projects/Plan.md", "# Plan"),
+            ("Plan.md", "Another plan"),
+            ("Index.md", "[[Projects/Plan]]"),
+        ]);
+
+        let first = vault.trash_note("Projects/Plan.md").unwrap();
+        let second = vault.trash_note("Plan.md").unwrap();
+        assert_eq!(first.path, dir.path().join(".trash/Plan.md"));
+        assert_eq!(second.path, dir.path().join(".trash/Plan 1.md"));
+        assert_eq!(vault.notes().count(), 1);
+
+        let trashed = vault.trashed_notes().unwrap();
+        assert_eq!(trashed, vec![second, first.clone()]);
+
+        let restored = vault.restore(&first, false).unwrap();
+        assert_eq!(restored, dir.path().join("Projects/Plan.md"));
+        assert_eq!(fs::read_to_string(&restored).unwrap(), "# Plan");
+        assert_eq!(vault.trashed_notes().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn restore_repairs_links_to_notes_without_a_record() {
+        let (dir, vault) = vault_with_files(&[
+            (".trash/Plan.md", "# Plan"),
+            (
+                "Index.md",
+                "See [[Projects/Plan#Goals|the plan]] and ![[Plan]]",
+            ),
+        ]);
+
+        let trashed = vault.trashed_notes().unwrap();
+        assert_eq!(trashed[0].original, None);
+
+        let restored = vault.restore(&trashed[0], true).unwrap();
+        assert_eq!(restored, dir.path().join("Plan.md"));
+        assert_eq!(
+            fs::read_to_string(dir.path().join("Index.md")).unwrap(),
+            "See [[Plan#Goals|the plan]] and ![[Plan]]"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use std::{fmt, path::Path};
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+use crate::{rename::path_to_link, Error, Vault};
+
+/// What JavaScript's `encodeURIComponent` leaves alone, which is what Obsidian expects
+const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'!')
+    .remove(b'~')
+    .remove(b'*')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')');
+
+/// An `obsidian://` URI that hands off to the Obsidian app
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObsidianUri {
+    /// Opens the vault, or a file in it
+    Open { vault: String, file: Option<String> },
+    /// Creates a note, optionally with content
+    New {
+        vault: String,
+        file: String,
+        content: Option<String>,
+    },
+    /// Opens search with a query
+    Search { vault: String, query: String },
+}
+
+impl ObsidianUri {
+    pub fn parse(uri: &str) -> crate::Result<Self> {
+        let invalid = || Error::InvalidUri(uri.to_string());
+        let rest = uri.strip_prefix("obsidian://").ok_or_else(invalid)?;
+        let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        let mut vault = None;
+        let mut file = None;
+        let mut content = None;
+        let mut search = None;
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode_str(value)
+                .decode_utf8()
+                .map_err(|_| invalid())?
+                .into_owned();
+            match key {
+                "vault" => vault = Some(value),
+                // `new` takes a `name` in the default folder or a `file` path
+                "file" | "name" => file = Some(value),
+                "content" => content = Some(value),
+                "query" => search = Some(value),
+                _ => {}
+            }
+        }
+
+        let vault = vault.ok_or_else(invalid)?;
+        match action.trim_end_matches('/') {
+            "open" => Ok(Self::Open { vault, file }),
+            "new" => Ok(Self::New {
+                vault,
+                file: file.ok_or_else(invalid)?,
+                content,
+            }),
+            "search" => Ok(Self::Search {
+                vault,
+                query: search.unwrap_or_default(),
+            }),
+            _ => Err(invalid()),
+        }
+    }
+
+    pub fn vault(&self) -> &str {
+        match self {
+            Self::Open { vault, .. } | Self::New { vault, .. } | Self::Search { vault, .. } => {
+                vault
+            }
+        }
+    }
+}
+
+impl fmt::Display for ObsidianUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let encode = |value: &str| utf8_percent_encode(value, COMPONENT).to_string();
+        let vault = encode(self.vault());
+        match self {
+            Self::Open { file: None, .. } => write!(f, "obsidian://open?vault={vault}"),
+            Self::Open {
+                file: Some(file), ..
+            } => write!(f, "obsidian://open?vault={vault}&file={}", encode(file)),
+            Self::New { file, content, .. } => {
+                write!(f, "obsidian://new?vault={vault}&file={}", encode(file))?;
+                if let Some(content) = content {
+                    write!(f, "&content={}", encode(content))?;
+                }
+                Ok(())
+            }
+            Self::Search { query, .. } => {
+                write!(f, "obsidian://search?vault={vault}&query={}", encode(query))
+            }
+        }
+    }
+}
+
+impl Vault {
+    /// The vault's name as Obsidian shows it, which is its folder name
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// A URI opening the note at `path`, which may be absolute or relative to the vault root
+    pub fn open_uri(&self, path: &Path) -> ObsidianUri {
+        ObsidianUri::Open {
+            vault: self.name(),
+            file: Some(self.uri_file(path)),
+        }
+    }
+
+    pub fn new_note_uri(&self, path: &Path, content: Option<&str>) -> ObsidianUri {
+        ObsidianUri::New {
+            vault: self.name(),
+            file: self.uri_file(path),
+            content: content.map(str::to_string),
+        }
+    }
+
+    pub fn search_uri(&self, query: &str) -> ObsidianUri {
+        ObsidianUri::Search {
+            vault: self.name(),
+            query: query.to_string(),
+        }
+    }
+
+    /// Obsidian's `file` parameter: vault-relative with `/` separators and no `.md`
+    fn uri_file(&self, path: &Path) -> String {
+        let file = path_to_link(self.relative_path(path));
+        match file.strip_suffix(".md") {
+            Some(stem) => stem.to_string(),
+            None => file,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vault_builds_encoded_uris() {
+        let vault = Vault::from_parts("/home/me/My Vault".into(), None);
+
+        assert_eq!(
+            vault
+                .open_uri(Path::new("/home/me/My Vault/Projects/Plan & Goals.md"))
+                .to_string(),
+            "obsidian://open?vault=My%20Vault&file=Projects%2FPlan%20%26%20Goals"
+        );
+        assert_eq!(
+            vault
+                .new_note_uri(Path::new("Inbox/Idea.md"), Some("# Idea\n"))
+                .to_string(),
+            "obsidian://new?vault=My%20Vault&file=Inbox%2FIdea&content=%23%20Idea%0A"
+        );
+        assert_eq!(
+            vault.search_uri("tag:#work").to_string(),
+            "obsidian://search?vault=My%20Vault&query=tag%3A%23work"
+        );
+    }
+
+    #[test]
+    fn parse_round_trips_and_rejects_other_uris() {
+        let uri = ObsidianUri::New {
+            vault: "Notes".to_string(),
+            file: "a/b c".to_string(),
+            content: Some("x=1&y=2".to_string()),
+        };
+        assert_eq!(ObsidianUri::parse(&uri.to_string()).unwrap(), uri);
+
+        assert_eq!(
+            ObsidianUri::parse("obsidian://open?vault=Notes").unwrap(),
+            ObsidianUri::Open {
+                vault: "Notes".to_string(),
+                file: None
+            }
+        );
+        assert!(matches!(
+            ObsidianUri::parse("https://example.com"),
+            Err(Error::InvalidUri(_))
+        ));
+        assert!(ObsidianUri::parse("obsidian://open?file=x").is_err());
+    }
+}
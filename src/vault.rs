@@ -0,0 +1,226 @@
+use std::path::{Path, PathBuf};
+
+use walkdir::{DirEntry, WalkDir};
+
+use crate::{
+    rename::path_to_link, resolver::nfc, AppConfig, Error, ExcludedFiles, ObsidianNote,
+    ReadOptions, VaultWriter, CONFIG_DIR,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vault {
+    pub path: PathBuf,
+    /// How the vault's operations write files, see [`Vault::with_write_options`]
+    pub writer: VaultWriter,
+    pub read_options: ReadOptions,
+    /// The vault's excluded files, read when it's opened, see [`Vault::including_excluded_files`]
+    pub excluded: ExcludedFiles,
+}
+
+impl Vault {
+    pub fn open(path: impl AsRef<Path>) -> crate::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if !path.is_dir() {
+            return Err(Error::NotADirectory(path));
+        }
+
+        let app_json = std::fs::read_to_string(app_json_path(&path)).ok();
+        Ok(Self::from_parts(path, app_json.as_deref()))
+    }
+
+    /// A vault at `path` with default options, excluding the files its `app.json` contents
+    /// exclude. Settings that can't be parsed are ignored, so a broken settings file doesn't
+    /// stop the vault from opening.
+    pub(crate) fn from_parts(path: PathBuf, app_json: Option<&str>) -> Self {
+        let app: AppConfig = app_json
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            writer: VaultWriter::default(),
+            read_options: ReadOptions::default(),
+            excluded: ExcludedFiles::new(&app.user_ignore_filters),
+        }
+    }
+
+    pub fn with_read_options(mut self, options: ReadOptions) -> Self {
+        self.read_options = options;
+        self
+    }
+
+    /// Every file in the vault, skipping hidden folders such as `.obsidian` and excluded files
+    pub fn files(&self) -> impl Iterator<Item = crate::Result<PathBuf>> {
+        let root = self.path.clone();
+        let excluded = self.excluded.clone();
+        WalkDir::new(&self.path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| entry.depth() == 0 || !is_hidden(entry))
+            .filter_map(move |entry| match entry {
+                Ok(entry) if entry.file_type().is_file() => {
+                    let relative = entry.path().strip_prefix(&root).unwrap_or(entry.path());
+                    (!excluded.excludes(relative)).then(|| Ok(entry.into_path()))
+                }
+                Ok(_) => None,
+                Err(err) => Some(Err(err.into())),
+            })
+    }
+
+    pub fn note_paths(&self) -> impl Iterator<Item = crate::Result<PathBuf>> {
+        self.files()
+            .filter(|path| path.as_ref().map_or(true, |path| is_note(path)))
+    }
+
+    pub fn notes(&self) -> impl Iterator<Item = crate::Result<ObsidianNote>> {
+        let options = self.read_options;
+        self.note_paths()
+            .map(move |path| {
+                path.and_then(|path| ObsidianNote::read_from_path_with(&path, options))
+            })
+            .filter(move |note| options.keeps(note))
+    }
+
+    /// The note the quick switcher would open for `name`: a path from the vault root, then a file
+    /// name, then an alias, all compared case-insensitively
+    pub fn find_note(&self, name: &str) -> crate::Result<Option<ObsidianNote>> {
+        let name = name.trim();
+        let name = nfc(&name.strip_suffix(".md").unwrap_or(name).to_lowercase()).into_owned();
+        let matches = |other: &str| nfc(&other.to_lowercase()) == name;
+        let mut by_stem = None;
+        let mut by_alias = None;
+
+        for note in self.notes() {
+            let note = note?;
+            let relative = self.relative_path(&note.file_path).with_extension("");
+            if matches(&path_to_link(&relative)) {
+                return Ok(Some(note));
+            }
+            let stem = relative.file_name().unwrap_or_default().to_string_lossy();
+            if by_stem.is_none() && matches(&stem) {
+                by_stem = Some(note);
+            } else if by_alias.is_none() && note.aliases().iter().any(|a| matches(a)) {
+                by_alias = Some(note);
+            }
+        }
+
+        Ok(by_stem.or(by_alias))
+    }
+
+    /// The path of a file relative to the vault root
+    pub fn relative_path<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.path).unwrap_or(path)
+    }
+}
+
+fn is_hidden(entry: &DirEntry) -> bool {
+    entry
+        .file_name()
+        .to_str()
+        .is_some_and(|name| name.starts_with('.'))
+}
+
+pub(crate) fn app_json_path(vault: &Path) -> PathBuf {
+    vault.join(CONFIG_DIR).join("app.json")
+}
+
+pub(crate) fn is_note(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "md")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::vault_with_files;
+    use std::fs;
+
+    #[test]
+    fn open_rejects_missing_directory() {
+        assert!(Vault::open("/definitely/not/a/vault").is_err());
+    }
+
+    #[test]
+    fn open_ignores_broken_settings() {
+        for app_json in ["{ not json", r#"{"userIgnoreFilters": null}"#] {
+            let (_dir, vault) =
+                vault_with_files(&[(".obsidian/app.json", app_json), ("a.md", "A")]);
+            assert!(vault.excluded.is_empty());
+            assert_eq!(vault.notes().count(), 1);
+        }
+    }
+
+    #[test]
+    fn notes_walks_subfolders() {
+        let (_dir, vault) = vault_with_files(&[
+            ("a.md", "A"),
+            ("folder/b.md", "B"),
+            ("folder/nested/c.md", "C"),
+        ]);
+
+        let bodies: Vec<String> = vault.notes().map(|n| n.unwrap().file_body).collect();
+        assert_eq!(bodies, vec!["A", "B", "C"]);
+    }
+
+    #[test]
+    fn read_options_handle_invalid_utf8() {
+        let (dir, vault) = vault_with_files(&[("a.md", "A")]);
+        fs::write(dir.path().join("b.md"), b"B\xff").unwrap();
+
+        let results: Vec<_> = vault.notes().collect();
+        assert!(matches!(results[1], Err(Error::InvalidUtf8(_))));
+
+        let lossy = vault.clone().with_read_options(ReadOptions {
+            lossy: true,
+            ..ReadOptions::default()
+        });
+        let bodies: Vec<String> = lossy.notes().map(|n| n.unwrap().file_body).collect();
+        assert_eq!(bodies, vec!["A", "B\u{fffd}"]);
+
+        let skipping = vault.with_read_options(ReadOptions {
+            skip_invalid: true,
+            ..ReadOptions::default()
+        });
+        let bodies: Vec<String> = skipping.notes().map(|n| n.unwrap().file_body).collect();
+        assert_eq!(bodies, vec!["A"]);
+    }
+
+    #[test]
+    fn notes_skips_obsidian_folder_and_attachments() {
+        let (_dir, vault) = vault_with_files(&[
+            (".obsidian/app.json", "{}"),
+            (".obsidian/snippets/readme.md", "Not a note"),
+            ("image.png", ""),
+            ("note.md", "A note"),
+        ]);
+
+        let paths: Vec<PathBuf> = vault
+            .notes()
+            .map(|n| vault.relative_path(&n.unwrap().file_path).to_path_buf())
+            .collect();
+        assert_eq!(paths, vec![PathBuf::from("note.md")]);
+    }
+
+    #[test]
+    fn find_note_matches_paths_names_and_aliases() {
+        let (_dir, vault) = vault_with_files(&[
+            ("People/Ada Lovelace.md", "---\naliases: [Countess]\n---\n"),
+            ("Projects/Engine.md", "---\nalias: Analytical\n---\n"),
+            ("Engine.md", "Root"),
+        ]);
+        let find = |name| {
+            vault
+                .find_note(name)
+                .unwrap()
+                .map(|note| vault.relative_path(&note.file_path).to_path_buf())
+        };
+
+        assert_eq!(find("ada lovelace"), Some("People/Ada Lovelace.md".into()));
+        assert_eq!(
+            find("Projects/Engine.md"),
+            Some("Projects/Engine.md".into())
+        );
+        assert_eq!(find("Engine"), Some("Engine.md".into()));
+        assert_eq!(find("countess"), Some("People/Ada Lovelace.md".into()));
+        assert_eq!(find("Analytical"), Some("Projects/Engine.md".into()));
+        assert_eq!(find("Nobody"), None);
+    }
+}
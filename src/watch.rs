@@ -0,0 +1,385 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Component, Path, PathBuf},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    time::Duration,
+};
+
+use notify::{
+    event::{ModifyKind, RenameMode},
+    Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+};
+
+use crate::{links::scan_wikilinks, vault::is_note, LinkResolver, ObsidianNote, Vault, WikiLink};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VaultEvent {
+    Created(PathBuf),
+    Modified(PathBuf),
+    Removed(PathBuf),
+    Renamed { from: PathBuf, to: PathBuf },
+}
+
+/// What a [`WatchedVault::poll`] or [`WatchedVault::wait`] applied
+#[derive(Debug, Default)]
+pub struct WatchChanges {
+    pub events: Vec<VaultEvent>,
+    /// Paths that couldn't be brought up to date, which keep their previous state
+    pub errors: Vec<(PathBuf, crate::Error)>,
+}
+
+/// A vault that keeps its notes, tag index and link index up to date as files change on disk
+pub struct WatchedVault {
+    pub vault: Vault,
+    notes: BTreeMap<PathBuf, ObsidianNote>,
+    /// Lowercased tag name to the notes with that tag
+    tags: BTreeMap<String, BTreeSet<PathBuf>>,
+    /// Outgoing wikilinks and embeds of each note
+    links: BTreeMap<PathBuf, Vec<WikiLink>>,
+    /// The canonical vault path, which the watcher reports events under
+    watched_root: PathBuf,
+    events: Receiver<notify::Result<Event>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl Vault {
+    pub fn watch(self) -> crate::Result<WatchedVault> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(&self.path, RecursiveMode::Recursive)?;
+
+        let mut watched = WatchedVault {
+            watched_root: self.path.canonicalize()?,
+            vault: self,
+            notes: BTreeMap::new(),
+            tags: BTreeMap::new(),
+            links: BTreeMap::new(),
+            events,
+            _watcher: watcher,
+        };
+        for note in watched.vault.notes() {
+            watched.insert(note?);
+        }
+        Ok(watched)
+    }
+}
+
+impl WatchedVault {
+    pub fn notes(&self) -> impl Iterator<Item = &ObsidianNote> {
+        self.notes.values()
+    }
+
+    pub fn note(&self, path: &Path) -> Option<&ObsidianNote> {
+        self.notes.get(path)
+    }
+
+    pub fn notes_with_tag(&self, tag: &str) -> Vec<&Path> {
+        let tag = tag.trim_start_matches('#').to_lowercase();
+        self.tags
+            .get(&tag)
+            .map(|paths| paths.iter().map(PathBuf::as_path).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn links(&self, path: &Path) -> &[WikiLink] {
+        self.links.get(path).map_or(&[], Vec::as_slice)
+    }
+
+    /// Notes with a link or embed resolving to `path`
+    pub fn backlinks(&self, path: &Path) -> Vec<&Path> {
+        let resolver = LinkResolver::new(&self.vault.path, self.notes.keys().cloned());
+        self.links
+            .iter()
+            .filter(|(source, links)| {
+                source.as_path() != path
+                    && links
+                        .iter()
+                        .any(|link| resolver.resolve_link(link, source) == Some(path))
+            })
+            .map(|(source, _)| source.as_path())
+            .collect()
+    }
+
+    /// Applies any pending file system changes without blocking. A path that can't be read
+    /// doesn't stop the others from being applied.
+    pub fn poll(&mut self) -> WatchChanges {
+        let mut changes = WatchChanges::default();
+        while let Ok(event) = self.events.try_recv() {
+            self.handle(event, &mut changes);
+        }
+        changes
+    }
+
+    /// Waits up to `timeout` for file system changes, then applies them. Fails only when the
+    /// watcher has stopped.
+    pub fn wait(&mut self, timeout: Duration) -> crate::Result<WatchChanges> {
+        let mut changes = WatchChanges::default();
+        match self.events.recv_timeout(timeout) {
+            Ok(event) => self.handle(event, &mut changes),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(notify::Error::generic("file watcher stopped").into())
+            }
+        }
+        let more = self.poll();
+        changes.events.extend(more.events);
+        changes.errors.extend(more.errors);
+        Ok(changes)
+    }
+
+    fn handle(&mut self, event: notify::Result<Event>, changes: &mut WatchChanges) {
+        let event = match event {
+            Ok(event) => event,
+            Err(err) => {
+                let path = err.paths.first().cloned();
+                let path = path.unwrap_or_else(|| self.vault.path.clone());
+                changes.errors.push((path, err.into()));
+                return;
+            }
+        };
+        let paths: Vec<PathBuf> = event
+            .paths
+            .iter()
+            .filter_map(|path| self.vault_path(path))
+            .collect();
+
+        if let (EventKind::Modify(ModifyKind::Name(RenameMode::Both)), [from, to]) =
+            (event.kind, paths.as_slice())
+        {
+            if self.notes.contains_key(from) && is_note(to) {
+                let note = self.read(to);
+                if let Ok(Some(note)) = note {
+                    self.remove(from);
+                    self.insert(note);
+                    changes.events.push(VaultEvent::Renamed {
+                        from: from.clone(),
+                        to: to.clone(),
+                    });
+                    return;
+                }
+            }
+        }
+
+        for path in paths {
+            match self.refresh(&path) {
+                Ok(event) => changes.events.extend(event),
+                Err(err) => changes.errors.push((path, err)),
+            }
+        }
+    }
+
+    /// Reads a note with the vault's read options, `None` if they leave it out
+    fn read(&self, path: &Path) -> crate::Result<Option<ObsidianNote>> {
+        let options = self.vault.read_options;
+        let note = ObsidianNote::read_from_path_with(path, options);
+        if options.keeps(&note) {
+            note.map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Brings one path in line with the file system
+    fn refresh(&mut self, path: &Path) -> crate::Result<Option<VaultEvent>> {
+        if !is_note(path) {
+            return Ok(None);
+        }
+        let note = if path.is_file() {
+            self.read(path)?
+        } else {
+            None
+        };
+        let Some(note) = note else {
+            return Ok(self
+                .remove(path)
+                .map(|_| VaultEvent::Removed(path.to_path_buf())));
+        };
+
+        let event = match self.notes.get(path) {
+            None => VaultEvent::Created(path.to_path_buf()),
+            Some(existing) if existing.file_contents != note.file_contents => {
+                VaultEvent::Modified(path.to_path_buf())
+            }
+            Some(_) => return Ok(None),
+        };
+        self.remove(path);
+        self.insert(note);
+        Ok(Some(event))
+    }
+
+    /// Maps a path reported by the watcher into the vault, skipping hidden folders and the
+    /// vault's excluded files
+    fn vault_path(&self, path: &Path) -> Option<PathBuf> {
+        let relative = path.strip_prefix(&self.watched_root).ok()?;
+        let hidden = relative.components().any(|component| {
+            matches!(component, Component::Normal(name) if name.to_string_lossy().starts_with('.'))
+        });
+        let skipped = hidden || self.vault.excluded.excludes(relative);
+        (!skipped).then(|| self.vault.path.join(relative))
+    }
+
+    fn insert(&mut self, note: ObsidianNote) {
+        let path = note.file_path.clone();
+        for tag in note.tags() {
+            self.tags
+                .entry(tag.name.to_lowercase())
+                .or_default()
+                .insert(path.clone());
+        }
+        self.links.insert(
+            path.clone(),
+            scan_wikilinks(&note.file_body)
+                .into_iter()
+                .map(|(_, link)| link)
+                .collect(),
+        );
+        self.notes.insert(path, note);
+    }
+
+    fn remove(&mut self, path: &Path) -> Option<ObsidianNote> {
+        let note = self.notes.remove(path)?;
+        self.links.remove(path);
+        self.tags.retain(|_, paths| {
+            paths.remove(path);
+            !paths.is_empty()
+        });
+        Some(note)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, time::Instant};
+
+    fn watched_vault(files: &[(&str, &str)]) -> (tempfile::TempDir, WatchedVault) {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, contents) in files {
+            fs::write(dir.path().join(path), contents).unwrap();
+        }
+        let watched = Vault::open(dir.path()).unwrap().watch().unwrap();
+        (dir, watched)
+    }
+
+    /// Collects events until `done` holds, failing after a few seconds
+    fn wait_until(
+        watched: &mut WatchedVault,
+        done: impl Fn(&WatchedVault) -> bool,
+    ) -> Vec<VaultEvent> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut events = Vec::new();
+        while !done(watched) {
+            assert!(Instant::now() < deadline, "timed out waiting for changes");
+            events.extend(watched.wait(Duration::from_millis(100)).unwrap().events);
+        }
+        events
+    }
+
+    #[test]
+    fn watch_loads_existing_notes_and_indexes() {
+        let (dir, watched) = watched_vault(&[("a.md", "#project [[b]]"), ("b.md", "")]);
+        let a = dir.path().join("a.md");
+        let b = dir.path().join("b.md");
+
+        assert_eq!(watched.notes().count(), 2);
+        assert_eq!(watched.notes_with_tag("#Project"), vec![a.as_path()]);
+        assert_eq!(watched.links(&a)[0].target, "b");
+        assert_eq!(watched.backlinks(&b), vec![a.as_path()]);
+    }
+
+    #[test]
+    fn refresh_tracks_changes() {
+        let (dir, mut watched) = watched_vault(&[("a.md", "#old")]);
+        let a = dir.path().join("a.md");
+        let c = dir.path().join("c.md");
+
+        fs::write(&a, "#new").unwrap();
+        assert_eq!(
+            watched.refresh(&a).unwrap(),
+            Some(VaultEvent::Modified(a.clone()))
+        );
+        assert_eq!(watched.refresh(&a).unwrap(), None);
+        assert!(watched.notes_with_tag("old").is_empty());
+        assert_eq!(watched.notes_with_tag("new"), vec![a.as_path()]);
+
+        fs::write(&c, "").unwrap();
+        assert_eq!(
+            watched.refresh(&c).unwrap(),
+            Some(VaultEvent::Created(c.clone()))
+        );
+
+        fs::remove_file(&a).unwrap();
+        assert_eq!(
+            watched.refresh(&a).unwrap(),
+            Some(VaultEvent::Removed(a.clone()))
+        );
+        assert!(watched.notes_with_tag("new").is_empty());
+    }
+
+    #[test]
+    fn refresh_applies_read_options() {
+        let (dir, mut watched) = watched_vault(&[("a.md", "A")]);
+        watched.vault.read_options.skip_invalid = true;
+        let a = dir.path().join("a.md");
+        let b = dir.path().join("b.md");
+
+        fs::write(&a, b"A\xff").unwrap();
+        assert_eq!(
+            watched.refresh(&a).unwrap(),
+            Some(VaultEvent::Removed(a.clone()))
+        );
+        fs::write(&b, b"B\xff").unwrap();
+        assert_eq!(watched.refresh(&b).unwrap(), None);
+        assert_eq!(watched.notes().count(), 0);
+    }
+
+    #[test]
+    fn vault_path_skips_hidden_and_excluded_files() {
+        let (dir, mut watched) = watched_vault(&[]);
+        watched.vault.excluded = crate::ExcludedFiles::new(&["Archive/"]);
+        let root = watched.watched_root.clone();
+
+        assert_eq!(
+            watched.vault_path(&root.join("Notes/a.md")),
+            Some(dir.path().join("Notes/a.md"))
+        );
+        assert_eq!(watched.vault_path(&root.join("Archive/a.md")), None);
+        assert_eq!(watched.vault_path(&root.join(".obsidian/app.json")), None);
+    }
+
+    #[test]
+    fn handle_reports_errors_per_path() {
+        let (dir, mut watched) = watched_vault(&[]);
+        let bad = dir.path().join("bad.md");
+        let good = dir.path().join("good.md");
+        fs::write(&bad, b"\xff").unwrap();
+        fs::write(&good, "").unwrap();
+
+        let mut changes = WatchChanges::default();
+        let event = Event::new(EventKind::Create(notify::event::CreateKind::File))
+            .add_path(watched.watched_root.join("bad.md"))
+            .add_path(watched.watched_root.join("good.md"));
+        watched.handle(Ok(event), &mut changes);
+
+        assert_eq!(changes.events, vec![VaultEvent::Created(good)]);
+        assert_eq!(changes.errors.len(), 1);
+        assert_eq!(changes.errors[0].0, bad);
+    }
+
+    #[test]
+    fn wait_applies_file_system_events() {
+        let (dir, mut watched) = watched_vault(&[("a.md", "")]);
+        let a = dir.path().join("a.md");
+        let b = dir.path().join("b.md");
+
+        fs::write(&b, "[[a]]").unwrap();
+        let events = wait_until(&mut watched, |w| w.note(&b).is_some());
+        assert!(events.contains(&VaultEvent::Created(b.clone())));
+        wait_until(&mut watched, |w| !w.links(&b).is_empty());
+        assert_eq!(watched.backlinks(&a), vec![b.as_path()]);
+
+        fs::remove_file(&b).unwrap();
+        wait_until(&mut watched, |w| w.note(&b).is_none());
+        assert!(watched.backlinks(&a).is_empty());
+    }
+}
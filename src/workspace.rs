@@ -0,0 +1,183 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::{config::read_config, Vault};
+
+/// `workspace.json`, where Obsidian saves its open panes and tabs
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Workspace {
+    /// The editor area
+    pub main: Option<WorkspaceItem>,
+    /// The left sidebar
+    pub left: Option<WorkspaceItem>,
+    /// The right sidebar
+    pub right: Option<WorkspaceItem>,
+    /// The ID of the focused leaf
+    pub active: Option<String>,
+    /// Recently opened files, most recent first, relative to the vault root
+    pub last_open_files: Vec<String>,
+}
+
+/// A split, a tab group, or a leaf holding a view
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WorkspaceItem {
+    pub id: String,
+    /// `split`, `tabs` or `leaf`
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub children: Vec<WorkspaceItem>,
+    /// Whether a leaf's tab is pinned
+    pub pinned: bool,
+    /// A leaf's view
+    pub state: Option<ViewState>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ViewState {
+    /// The view type, such as `markdown`, `canvas` or `graph`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// View-specific state, such as the `file` a markdown view shows
+    pub state: serde_json::Value,
+}
+
+impl WorkspaceItem {
+    /// The file a leaf shows, relative to the vault root
+    pub fn file(&self) -> Option<&str> {
+        self.state.as_ref()?.state.get("file")?.as_str()
+    }
+
+    /// This item and everything nested in it that is a leaf
+    pub fn leaves(&self) -> Vec<&WorkspaceItem> {
+        if self.children.is_empty() {
+            return if self.kind == "leaf" {
+                vec![self]
+            } else {
+                Vec::new()
+            };
+        }
+        self.children
+            .iter()
+            .flat_map(|child| child.leaves())
+            .collect()
+    }
+}
+
+impl Workspace {
+    /// Files open in the editor area, in tab order
+    pub fn open_files(&self) -> Vec<&str> {
+        self.main_leaves().filter_map(WorkspaceItem::file).collect()
+    }
+
+    /// Files open in pinned tabs in the editor area
+    pub fn pinned_files(&self) -> Vec<&str> {
+        self.main_leaves()
+            .filter(|leaf| leaf.pinned)
+            .filter_map(WorkspaceItem::file)
+            .collect()
+    }
+
+    /// The file in the focused leaf, falling back to the most recently opened file when the
+    /// focused leaf doesn't show one
+    pub fn active_file(&self) -> Option<&str> {
+        let active = self.active.as_deref().and_then(|id| {
+            [&self.main, &self.left, &self.right]
+                .into_iter()
+                .flatten()
+                .flat_map(WorkspaceItem::leaves)
+                .find(|leaf| leaf.id == id)
+        });
+        active
+            .and_then(WorkspaceItem::file)
+            .or_else(|| self.last_open_files.first().map(String::as_str))
+    }
+
+    fn main_leaves(&self) -> impl Iterator<Item = &WorkspaceItem> {
+        self.main.iter().flat_map(WorkspaceItem::leaves)
+    }
+}
+
+impl Vault {
+    /// The saved workspace, empty if Obsidian hasn't saved one
+    pub fn workspace(&self) -> crate::Result<Workspace> {
+        Ok(read_config(&self.config_dir(), "workspace.json")?.unwrap_or_default())
+    }
+
+    /// The path of the file the user last had open, if it still exists
+    pub fn active_file(&self) -> crate::Result<Option<PathBuf>> {
+        let workspace = self.workspace()?;
+        Ok(workspace
+            .active_file()
+            .map(|file| self.path.join(file))
+            .filter(|path| path.is_file()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CONFIG_DIR;
+    use std::fs;
+
+    const WORKSPACE: &str = r#"{
+      "main": {
+        "id": "root", "type": "split", "direction": "vertical",
+        "children": [{
+          "id": "tabs", "type": "tabs", "currentTab": 1,
+          "children": [
+            {"id": "a", "type": "leaf", "pinned": true, "state": {"type": "markdown", "state": {"file": "Inbox.md", "mode": "source"}}},
+            {"id": "b", "type": "leaf", "state": {"type": "markdown", "state": {"file": "Projects/Launch.md"}}},
+            {"id": "c", "type": "leaf", "state": {"type": "graph", "state": {}}}
+          ]
+        }]
+      },
+      "left": {
+        "id": "left", "type": "split",
+        "children": [{"id": "l", "type": "leaf", "state": {"type": "file-explorer", "state": {}}}]
+      },
+      "active": "b",
+      "lastOpenFiles": ["Projects/Launch.md", "Inbox.md"]
+    }"#;
+
+    #[test]
+    fn workspace_reads_open_pinned_and_active_files() {
+        let workspace: Workspace = serde_json::from_str(WORKSPACE).unwrap();
+
+        assert_eq!(
+            workspace.open_files(),
+            vec!["Inbox.md", "Projects/Launch.md"]
+        );
+        assert_eq!(workspace.pinned_files(), vec!["Inbox.md"]);
+        assert_eq!(workspace.active_file(), Some("Projects/Launch.md"));
+
+        let sidebar_focused = Workspace {
+            active: Some("l".to_string()),
+            ..workspace
+        };
+        assert_eq!(sidebar_focused.active_file(), Some("Projects/Launch.md"));
+    }
+
+    #[test]
+    fn vault_active_file_resolves_against_vault() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(CONFIG_DIR)).unwrap();
+        fs::create_dir_all(dir.path().join("Projects")).unwrap();
+        fs::write(dir.path().join("Projects/Launch.md"), "").unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+        assert_eq!(vault.active_file().unwrap(), None);
+
+        fs::write(
+            dir.path().join(CONFIG_DIR).join("workspace.json"),
+            WORKSPACE,
+        )
+        .unwrap();
+        assert_eq!(
+            vault.active_file().unwrap(),
+            Some(dir.path().join("Projects/Launch.md"))
+        );
+    }
+}
@@ -0,0 +1,358 @@
+use std::{
+    fs, mem,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use crate::{Error, Vault};
+
+/// How a [`Vault`] writes files
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Keep the old contents of every replaced file next to it, as the hidden `.Note.md.bak`
+    pub backup: bool,
+    /// Record changes for [`Vault::take_planned_changes`] instead of making them
+    pub dry_run: bool,
+}
+
+/// A change to one file in the vault
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileChange {
+    Write {
+        path: PathBuf,
+        contents: String,
+    },
+    Rename {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    /// Copies a file, which may be outside the vault, to `to`
+    Copy {
+        from: PathBuf,
+        to: PathBuf,
+    },
+}
+
+impl FileChange {
+    /// The path the change leaves a file at
+    pub fn path(&self) -> &Path {
+        match self {
+            FileChange::Write { path, .. } => path,
+            FileChange::Rename { to, .. } | FileChange::Copy { to, .. } => to,
+        }
+    }
+}
+
+/// Makes a vault's file changes. Every file is written to a hidden temporary sibling first and
+/// only renamed into place once all of them were staged, so a failed operation never leaves a
+/// half-written note behind.
+///
+/// Clones share the changes recorded in a dry run.
+#[derive(Debug, Default, Clone)]
+pub struct VaultWriter {
+    pub options: WriteOptions,
+    planned: Arc<Mutex<Vec<FileChange>>>,
+}
+
+impl PartialEq for VaultWriter {
+    fn eq(&self, other: &Self) -> bool {
+        self.options == other.options
+    }
+}
+
+impl Eq for VaultWriter {}
+
+impl VaultWriter {
+    pub fn new(options: WriteOptions) -> Self {
+        Self {
+            options,
+            planned: Arc::default(),
+        }
+    }
+
+    pub fn write(&self, path: &Path, contents: impl Into<String>) -> crate::Result<()> {
+        self.apply(vec![FileChange::Write {
+            path: path.to_path_buf(),
+            contents: contents.into(),
+        }])
+    }
+
+    pub fn copy(&self, from: &Path, to: &Path) -> crate::Result<()> {
+        self.apply(vec![FileChange::Copy {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+        }])
+    }
+
+    /// Makes the changes in order, after staging every write. If any change fails, the ones
+    /// already made are undone.
+    pub fn apply(&self, changes: Vec<FileChange>) -> crate::Result<()> {
+        if self.options.dry_run {
+            self.planned().extend(changes);
+            return Ok(());
+        }
+
+        let mut staged = Vec::new();
+        for change in &changes {
+            let FileChange::Write { path, contents } = change else {
+                continue;
+            };
+            if let Err(err) = stage(path, contents, &mut staged) {
+                remove_all(&staged);
+                return Err(err);
+            }
+        }
+
+        let mut undo = Vec::new();
+        let mut temps = staged.iter();
+        for change in changes {
+            let applied = match change {
+                FileChange::Write { path, .. } => match temps.next() {
+                    Some(temp) => self.replace(temp, path, &mut undo),
+                    None => Ok(()),
+                },
+                FileChange::Rename { from, to } => move_file(from, to, &mut undo),
+                FileChange::Copy { from, to } => self.copy_file(&from, to, &mut undo),
+            };
+            if let Err(err) = applied {
+                roll_back(undo);
+                remove_all(temps.as_slice());
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    fn replace(&self, temp: &Path, path: PathBuf, undo: &mut Vec<Undo>) -> crate::Result<()> {
+        let previous = if path.is_file() {
+            Some(fs::read(&path)?)
+        } else {
+            None
+        };
+        if self.options.backup && previous.is_some() {
+            fs::copy(&path, backup_path(&path))?;
+        }
+        fs::rename(temp, &path)?;
+        undo.push(Undo::Restore { path, previous });
+        Ok(())
+    }
+
+    fn copy_file(&self, from: &Path, to: PathBuf, undo: &mut Vec<Undo>) -> crate::Result<()> {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp = temp_path(&to);
+        let copied = fs::copy(from, &temp)
+            .map_err(Into::into)
+            .and_then(|_| self.replace(&temp, to, undo));
+        if copied.is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+        copied
+    }
+
+    /// The changes recorded in a dry run so far, leaving none recorded
+    pub fn take_planned(&self) -> Vec<FileChange> {
+        mem::take(&mut *self.planned())
+    }
+
+    fn planned(&self) -> std::sync::MutexGuard<'_, Vec<FileChange>> {
+        self.planned.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Vault {
+    pub fn with_write_options(mut self, options: WriteOptions) -> Self {
+        self.writer.options = options;
+        self
+    }
+
+    /// What the vault's operations would have written since the last call, when opened with
+    /// [`WriteOptions::dry_run`]
+    pub fn take_planned_changes(&self) -> Vec<FileChange> {
+        self.writer.take_planned()
+    }
+}
+
+/// What undoes one applied change
+enum Undo {
+    /// Puts back a file's old contents, or removes it if it didn't exist
+    Restore {
+        path: PathBuf,
+        previous: Option<Vec<u8>>,
+    },
+    Rename {
+        from: PathBuf,
+        to: PathBuf,
+    },
+}
+
+/// Moves a file, failing rather than replacing one already at `to`. A rename that only changes
+/// the case of the name, on file systems that ignore case, is allowed.
+fn move_file(from: PathBuf, to: PathBuf, undo: &mut Vec<Undo>) -> crate::Result<()> {
+    if to.symlink_metadata().is_ok() && fs::canonicalize(&from)? != fs::canonicalize(&to)? {
+        return Err(Error::AlreadyExists(to));
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(&from, &to)?;
+    undo.push(Undo::Rename { from: to, to: from });
+    Ok(())
+}
+
+/// Undoes changes newest first, on a best-effort basis since the vault is already failing
+fn roll_back(undo: Vec<Undo>) {
+    for step in undo.into_iter().rev() {
+        let _ = match step {
+            Undo::Restore {
+                path,
+                previous: Some(contents),
+            } => fs::write(path, contents),
+            Undo::Restore {
+                path,
+                previous: None,
+            } => fs::remove_file(path),
+            Undo::Rename { from, to } => fs::rename(from, to),
+        };
+    }
+}
+
+fn stage(path: &Path, contents: &str, staged: &mut Vec<PathBuf>) -> crate::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Numbered when the same file is written more than once
+    let mut temp = temp_path(path);
+    for n in 1.. {
+        if !staged.contains(&temp) {
+            break;
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        temp = path.with_file_name(format!(".{name}.{n}.tmp"));
+    }
+    let written = fs::write(&temp, contents);
+    staged.push(temp);
+    Ok(written?)
+}
+
+fn remove_all(paths: &[PathBuf]) {
+    for path in paths {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// A hidden file next to `path`, so vault walks skip it
+pub(crate) fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{name}.tmp"))
+}
+
+/// Hidden like the temporary files, so backups don't show up as attachments
+fn backup_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{name}.bak"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writer_keeps_backups_and_cleans_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.md");
+        fs::write(&path, "old").unwrap();
+        let writer = VaultWriter::new(WriteOptions {
+            backup: true,
+            ..WriteOptions::default()
+        });
+
+        writer.write(&path, "new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".a.md.bak")).unwrap(),
+            "old"
+        );
+
+        let result = writer.apply(vec![
+            FileChange::Write {
+                path: dir.path().join("b.md"),
+                contents: "b".to_string(),
+            },
+            FileChange::Write {
+                path: dir.path().join("a.md/c.md"),
+                contents: "c".to_string(),
+            },
+        ]);
+        assert!(result.is_err());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn rename_never_replaces_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.md"), "a").unwrap();
+        fs::write(dir.path().join("b.md"), "b").unwrap();
+        let writer = VaultWriter::default();
+
+        let result = writer.apply(vec![FileChange::Rename {
+            from: dir.path().join("a.md"),
+            to: dir.path().join("b.md"),
+        }]);
+        assert!(matches!(result, Err(Error::AlreadyExists(_))));
+        assert_eq!(fs::read_to_string(dir.path().join("a.md")).unwrap(), "a");
+        assert_eq!(fs::read_to_string(dir.path().join("b.md")).unwrap(), "b");
+    }
+
+    #[test]
+    fn vault_walks_skip_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.md"), "old").unwrap();
+        let vault = Vault::open(dir.path())
+            .unwrap()
+            .with_write_options(WriteOptions {
+                backup: true,
+                ..WriteOptions::default()
+            });
+
+        vault.writer.write(&dir.path().join("a.md"), "new").unwrap();
+        assert!(dir.path().join(".a.md.bak").is_file());
+        assert_eq!(
+            vault.files().map(Result::unwrap).collect::<Vec<_>>(),
+            vec![dir.path().join("a.md")]
+        );
+    }
+
+    #[test]
+    fn dry_run_records_changes_without_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.md"), "#old").unwrap();
+        let vault = Vault::open(dir.path())
+            .unwrap()
+            .with_write_options(WriteOptions {
+                dry_run: true,
+                ..WriteOptions::default()
+            });
+
+        vault.rename_tag("old", "new").unwrap();
+        let note = vault.create_note("Idea", "Body", None).unwrap();
+
+        assert_eq!(
+            vault.take_planned_changes(),
+            vec![
+                FileChange::Write {
+                    path: dir.path().join("a.md"),
+                    contents: "#new".to_string(),
+                },
+                FileChange::Write {
+                    path: note.file_path,
+                    contents: "Body".to_string(),
+                },
+            ]
+        );
+        assert!(vault.take_planned_changes().is_empty());
+        assert_eq!(fs::read_to_string(dir.path().join("a.md")).unwrap(), "#old");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
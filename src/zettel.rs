@@ -0,0 +1,184 @@
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+use crate::{config::read_config, format_moment, parse_moment, ObsidianNote, Vault};
+
+pub const DEFAULT_ZETTEL_FORMAT: &str = "YYYYMMDDHHmm";
+
+/// `zk-prefixer.json`, the unique note creator core plugin's settings
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ZettelConfig {
+    pub folder: String,
+    /// Empty means [`DEFAULT_ZETTEL_FORMAT`]
+    pub format: String,
+    pub template: String,
+    /// Settings without a typed field
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+impl ZettelConfig {
+    pub fn format(&self) -> &str {
+        match self.format.trim() {
+            "" => DEFAULT_ZETTEL_FORMAT,
+            format => format,
+        }
+    }
+}
+
+/// A timestamp ID at the start of a note name, such as `202403151230` in
+/// `202403151230 Spaced repetition`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZettelId {
+    pub id: String,
+    pub datetime: NaiveDateTime,
+}
+
+impl ZettelId {
+    pub fn new(datetime: NaiveDateTime, format: &str) -> Self {
+        Self {
+            id: format_moment(datetime, format),
+            datetime,
+        }
+    }
+
+    /// The ID at the start of `name`, which must be followed by the end of the name or a
+    /// character that can't continue it
+    pub fn parse(name: &str, format: &str) -> Option<Self> {
+        let len = id_len(format);
+        let id = name.get(..len)?;
+        let next = name[len..].chars().next();
+        if next.is_some_and(|c| c.is_alphanumeric()) {
+            return None;
+        }
+
+        let datetime = parse_moment(id, format)?;
+        // Reject IDs like `202402301200` that only parse by rolling over
+        (format_moment(datetime, format) == id).then(|| Self {
+            id: id.to_string(),
+            datetime,
+        })
+    }
+
+    /// Whether `id` is exactly an ID in `format`
+    pub fn is_valid(id: &str, format: &str) -> bool {
+        Self::parse(id, format).is_some_and(|parsed| parsed.id.len() == id.len())
+    }
+}
+
+impl ObsidianNote {
+    pub fn zettel_id(&self, format: &str) -> Option<ZettelId> {
+        let name = self.file_path.file_stem()?.to_string_lossy();
+        ZettelId::parse(&name, format)
+    }
+}
+
+impl Vault {
+    pub fn zettel_config(&self) -> crate::Result<ZettelConfig> {
+        Ok(read_config(&self.config_dir(), "zk-prefixer.json")?.unwrap_or_default())
+    }
+
+    /// An ID for the current time in the configured format that no note uses yet, moving
+    /// forward a minute (or a second, if the format has seconds) at a time
+    pub fn new_zettel_id(&self) -> crate::Result<ZettelId> {
+        self.zettel_id_at(Local::now().naive_local())
+    }
+
+    pub fn zettel_id_at(&self, datetime: NaiveDateTime) -> crate::Result<ZettelId> {
+        let format = self.zettel_config()?.format().to_string();
+        let mut taken = HashSet::new();
+        for path in self.note_paths() {
+            let path = path?;
+            let name = path.file_stem().unwrap_or_default().to_string_lossy();
+            if let Some(id) = ZettelId::parse(&name, &format) {
+                taken.insert(id.id);
+            }
+        }
+
+        let step = if format.contains('s') {
+            TimeDelta::seconds(1)
+        } else {
+            TimeDelta::minutes(1)
+        };
+        let mut id = ZettelId::new(datetime, &format);
+        while taken.contains(&id.id) {
+            id = ZettelId::new(id.datetime + step, &format);
+        }
+        Ok(id)
+    }
+
+    /// The note whose name starts with the ID
+    pub fn note_by_zettel_id(&self, id: &str) -> crate::Result<Option<ObsidianNote>> {
+        let format = self.zettel_config()?.format().to_string();
+        for path in self.note_paths() {
+            let path = path?;
+            let name = path.file_stem().unwrap_or_default().to_string_lossy();
+            if ZettelId::parse(&name, &format).is_some_and(|parsed| parsed.id == id) {
+                return ObsidianNote::read_from_path(&path).map(Some);
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// How long IDs in `format` are, which is fixed for the numeric formats IDs use
+fn id_len(format: &str) -> usize {
+    let sample = NaiveDate::from_ymd_opt(2000, 12, 31)
+        .and_then(|date| date.and_hms_opt(23, 59, 59))
+        .unwrap_or_default();
+    format_moment(sample, format).len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn datetime(h: u32, m: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, 15)
+            .unwrap()
+            .and_hms_opt(h, m, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn zettel_ids_round_trip_and_validate() {
+        let id = ZettelId::new(datetime(12, 30), DEFAULT_ZETTEL_FORMAT);
+        assert_eq!(id.id, "202403151230");
+
+        assert_eq!(
+            ZettelId::parse("202403151230 Spaced repetition", DEFAULT_ZETTEL_FORMAT),
+            Some(id)
+        );
+        assert!(ZettelId::is_valid("202403151230", DEFAULT_ZETTEL_FORMAT));
+        assert!(!ZettelId::is_valid("2024031512301", DEFAULT_ZETTEL_FORMAT));
+        assert!(!ZettelId::is_valid("202402301230", DEFAULT_ZETTEL_FORMAT));
+        assert!(ZettelId::parse("Not an ID", DEFAULT_ZETTEL_FORMAT).is_none());
+        assert!(ZettelId::is_valid("2024-03-15-1230", "YYYY-MM-DD-HHmm"));
+    }
+
+    #[test]
+    fn vault_finds_and_avoids_existing_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("202403151230 Idea.md"), "Idea").unwrap();
+        fs::write(dir.path().join("202403151231.md"), "Next").unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+
+        assert_eq!(
+            vault
+                .note_by_zettel_id("202403151230")
+                .unwrap()
+                .unwrap()
+                .file_body,
+            "Idea"
+        );
+        assert!(vault.note_by_zettel_id("202403151232").unwrap().is_none());
+        assert_eq!(
+            vault.zettel_id_at(datetime(12, 30)).unwrap().id,
+            "202403151232"
+        );
+    }
+}